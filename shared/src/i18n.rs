@@ -0,0 +1,346 @@
+//! UI strings by language: settings window, help, tray labels, notification texts.
+//! A key missing in a translation falls back to English.
+
+/// selectable languages: code, name
+pub const LANGUAGES: [(&str, &str); 2] = [("en", "English"), ("ru", "Русский")];
+
+/// supported language for a locale or browser tag, "ru_RU.UTF-8" / "ru-RU" -> "ru", unknown -> "en"
+pub fn normalize(lang: &str) -> &'static str {
+    let code = lang.split(['_', '-', '.', '@']).next().unwrap_or_default().to_lowercase();
+    LANGUAGES
+        .iter()
+        .map(|(c, _)| *c)
+        .find(|c| *c == code)
+        .unwrap_or("en")
+}
+
+/// text for key in lang
+pub fn tr(lang: &str, key: &'static str) -> &'static str {
+    let table = match normalize(lang) {
+        "ru" => RU,
+        _ => EN,
+    };
+    lookup(table, key).or_else(|| lookup(EN, key)).unwrap_or(key)
+}
+
+fn lookup(table: &[(&str, &'static str)], key: &str) -> Option<&'static str> {
+    table.iter().find(|(k, _)| *k == key).map(|(_, text)| *text)
+}
+
+static EN: &[(&str, &str)] = &[
+    ("tray.settings", "⚙️   Settings"),
+    ("tray.restart", "🔃   Restart"),
+    ("tray.debug_log", "Debug logging"),
+    ("tray.quit", "✝️   Quit"),
+    ("tray.terminal_at", "📂   Terminal at"),
+    ("tray.recent", "🕘   Recent results"),
+    ("tray.recent_none", "No runs yet"),
+    ("notify.executed", "Command `{}` executed"),
+    ("notify.failed", "Command `{}` failed"),
+    ("notify.unreachable", "Host `{}` not reachable"),
+    ("notify.result", "Result"),
+    ("notify.error", "Error"),
+    ("notify.view_full", "View full output"),
+    ("notify.running", "Running `{}`…"),
+    ("tab.commands", "Commands [F1]"),
+    ("tab.help", "Find help || man [F2]"),
+    ("tab.about", "About [F3]"),
+    ("tab.stats", "Stats [F5]"),
+    ("tab.log", "Log [F6]"),
+    ("label.follow", "Follow"),
+    ("log.empty", "The log is empty, or written to the journal (log_target in [settings])"),
+    ("label.custom_theme", "Custom theme [F4 → 🎨]"),
+    ("label.theme_accent", "Accent"),
+    ("label.theme_background", "Background"),
+    ("label.theme_text", "Text"),
+    ("label.theme_font_size", "Font size, px"),
+    ("btn.reset_theme", "Reset theme"),
+    ("palette.title", "Command palette"),
+    ("palette.placeholder", "Type a command, tab or action… [Ctrl+K]"),
+    ("palette.empty", "Nothing found"),
+    ("palette.tab", "tab"),
+    ("palette.run", "run"),
+    ("palette.action", "action"),
+    ("palette.theme", "Switch theme [F4]"),
+    ("stats.runs", "runs"),
+    ("stats.failure_rate", "failed"),
+    ("stats.last_failed", "last failure"),
+    ("stats.avg", "avg time"),
+    ("stats.empty", "No runs in gucli.log yet"),
+    ("processes.title", "Running"),
+    ("processes.empty", "No started command is running"),
+    ("processes.started", "started"),
+    ("btn.kill", "Kill"),
+    ("stats.note", "Counted from gucli.log, which keeps the last 100 lines. Times are logged since this version."),
+    ("btn.autostart_on", "Autostart: ON"),
+    ("btn.autostart_off", "Autostart: OFF"),
+    ("label.autostart_backend", "Autostart via"),
+    ("label.autostart_delay", "Autostart delay, s"),
+    ("label.start_hidden", "Start hidden (tray only)"),
+    ("label.check_updates", "Check GitHub for new versions (at start and daily)"),
+    ("label.on_quit", "Run on quit"),
+    ("label.notify_length", "notification length"),
+    ("placeholder.default", "default"),
+    ("btn.open_log", "Open Log"),
+    ("btn.open_config", "Open Config"),
+    ("btn.encrypt", "🔓 Encrypt"),
+    ("btn.decrypt", "🔒 Decrypt"),
+    ("label.encryption", "Keep commands.toml encrypted to your GPG key (commands.toml.gpg), gpg-agent asks for the passphrase at start"),
+    ("btn.reset", "Reset & Restart"),
+    ("btn.really_reset", "Really reset?"),
+    ("btn.restore_backup", "Restore backup & Restart"),
+    ("label.line", "line"),
+    ("launcher.started", "Started"),
+    ("launcher.empty", "No commands for this machine, add them in the settings"),
+    ("config.invalid", "commands.toml could not be loaded, the tray runs without commands:"),
+    ("config.overwrite", "Saving here replaces the file with the commands shown below."),
+    ("btn.add", "Add command"),
+    ("btn.test_all", "Test all"),
+    ("label.dangerous", "Looks destructive, runs only after two confirmations"),
+    ("btn.preview", "Preview: show what would run, without running it"),
+    ("btn.export_service", "Export as systemd service"),
+    ("btn.export_timer", "Export as systemd timer"),
+    ("btn.really_test_all", "Really run all?"),
+    ("btn.testing", "Testing..."),
+    ("btn.save", "Save"),
+    ("btn.duplicate", "Duplicate"),
+    ("btn.delete", "Delete"),
+    ("btn.run_test", "Run test"),
+    ("btn.search", "Search"),
+    ("btn.refresh", "Refresh"),
+    ("btn.self_test", "Run self-test"),
+    ("btn.diagnostics", "Collect diagnostics"),
+    ("btn.collecting", "Collecting..."),
+    ("head.shell", "shell"),
+    ("head.command", "command"),
+    ("head.icon", "icon"),
+    ("head.sn", "sn"),
+    ("head.rows", "duplicate / delete"),
+    ("head.test", "test"),
+    ("label.status", "STATUS"),
+    ("label.count", "count: "),
+    ("label.options", "Options"),
+    ("label.help", "Help"),
+    ("label.language", "Language"),
+    ("label.left_click", "Tray left click"),
+    ("man.title", "Get console help with command: man or built-in --help"),
+    ("about.title", "Your personal command center in the system tray"),
+    ("about.intro", "Gucli (from GUI + CLI) is a simple system tray application that turns your frequent console commands into menu items for one-click launching."),
+    ("about.homepage", "For information on compatibility, dependencies, or to report issues, please visit the homepage."),
+    ("help.settings", "<ul>
+    <li>Program executes shell commands that return either nothing or string-convertible output</li>
+    <li>Command settings can be edited in <code>/home/$USER/.config/gucli/commands.toml</code> (the dir in <code>$GUCLI_CONFIG_DIR</code> when set) without opening this window, <code>gucli --reload</code> applies them</li>
+    <li>Errors and results are logged to <code>/home/$USER/.config/gucli/gucli.log</code> (100 line limit, no rotation needed)</li>
+    <li>Interactive commands or commands with continuous output are not recommended, unless Options → in terminal is checked: the command then opens in your terminal emulator (detected, or <code>terminal</code> in <code>[settings]</code>)</li>
+    <li>Command execution timeout: 500ms (<code>long_running = true</code> to run without it)</li>
+    <li>Notification text limited to 200 characters (long messages may freeze GTK)</li>
+    <li>Commands in tray menu display as-is - set Options → label (e.g. <code>Restart VPN</code>) to show a short text instead</li>
+    <li>shell: cycles through the installed shells; more interpreters (nu, pwsh, python...) can be registered in the <code>[settings]</code> section of <code>commands.toml</code></li>
+    <li>icon: up to 8 UTF-8 characters (emoji, short text, or empty), or the path of a <code>.png</code>/<code>.svg</code> image, e.g. <code>~/icons/vpn.svg</code></li>
+    <li>sn (show notification): show system notification (default: true). Error notifications always show</li>
+    <li>While typing a command, binaries from <code>$PATH</code> and previously run commands are suggested</li>
+    <li>Options → run on: execute locally, over <code>ssh</code> on a host or inside a <code>podman</code>/<code>docker</code> container</li>
+    <li>Options → interactive: run as <code>shell -i -c</code>, so aliases and functions from <code>~/.bashrc</code>, <code>~/.zshrc</code>, etc. are available</li>
+    <li>Options → confirm: a notification asks <code>Run now / Skip</code> before the command runs from the tray; without an answer it is skipped after 60 seconds. Useful for privileged commands that must not run while you are away</li>
+    <li>Options → gui: for commands that change the desktop (monitor layout, themes...). On failure a screenshot is saved to <code>~/.config/gucli/screenshots</code> (last 20 kept) and its path is added to the log entry</li>
+    <li>Options → hosts: comma-separated hostnames, the command is only shown in the tray of these machines (rows for other hosts are dimmed). Useful when <code>commands.toml</code> is synced between computers</li>
+    <li>Options → working dir: directory the command runs in (local and in terminal). The tray menu <code>Terminal at</code> opens a terminal there, e.g. to follow up on a failure</li>
+    <li>Options → default: the command run by a double click on the tray icon, or by a single click when About → Tray left click is <code>default</code> (only one command can be the default)</li>
+    <li>Options → sandbox: run through <code>bwrap</code> or <code>firejail</code> without network, with read-only home and private <code>/tmp</code> (fails if neither is installed)</li>
+    <li>Check rows next to their number to delete them or switch their notifications at once</li>
+    <li>Drag a row by its number to move it, or use ↑/↓</li>
+    <li>sort: order the table alphabetically, by number of runs or by the latest failure (from the log). Sorting only changes the view, <code>Apply order</code> makes it the tray order</li>
+    <li>Options → mnemonic: a key that runs the command while the tray menu is open (underlined where the desktop supports it)</li>
+    <li>☆/★: pinned commands are listed first in the tray menu, above a separator</li>
+    <li>Tray order: arrange the tray menu independently of this table (saved as <code>tray_position</code>), <code>Use table order</code> drops it</li>
+    <li>Always test commands first, even if you know what you're doing</li>
+</ul>"),
+    ("help.search", "<ul>
+    <li>If you need an exact reference with specific attributes, write it in full</li>
+    <li>Otherwise, the program will iterate through this set:<br />
+    <code>[ --help, -h, --usage, help, -help, -?, --longhelp, --long-help, --help-all, info]</code><br />
+    then check the man pages, and return the first matching option found.</li>
+    <li>To prevent the window from freezing, the maximum result length is limited to 30,000 characters</li>
+    <li>Start typing to pick from the binaries installed in <code>$PATH</code></li>
+    <li>Prefix the command with a man section to open that page only, e.g. <code>3 printf</code>. When a command has pages in several sections, they are listed above the result</li>
+    <li>Found help is cached for 30 minutes, use <code>Refresh</code> to search again</li>
+    <li>Don't know the command name? Check <code>keyword</code> to list matching commands (<code>apropos</code>) and click one to load its help</li>
+</ul>"),
+];
+
+static RU: &[(&str, &str)] = &[
+    ("tray.settings", "⚙️   Настройки"),
+    ("tray.restart", "🔃   Перезапуск"),
+    ("tray.debug_log", "Отладочный журнал"),
+    ("tray.quit", "✝️   Выход"),
+    ("tray.terminal_at", "📂   Терминал в"),
+    ("tray.recent", "🕘   Последние результаты"),
+    ("tray.recent_none", "Запусков ещё не было"),
+    ("notify.executed", "Команда `{}` выполнена"),
+    ("notify.failed", "Команда `{}` завершилась с ошибкой"),
+    ("notify.unreachable", "Хост `{}` недоступен"),
+    ("notify.result", "Результат"),
+    ("notify.error", "Ошибка"),
+    ("notify.view_full", "Показать весь вывод"),
+    ("notify.running", "Выполняется `{}`…"),
+    ("tab.commands", "Команды [F1]"),
+    ("tab.help", "Справка || man [F2]"),
+    ("tab.about", "О программе [F3]"),
+    ("tab.stats", "Статистика [F5]"),
+    ("tab.log", "Лог [F6]"),
+    ("label.follow", "Следить"),
+    ("log.empty", "Лог пуст или пишется в журнал (log_target в [settings])"),
+    ("label.custom_theme", "Своя тема [F4 → 🎨]"),
+    ("label.theme_accent", "Акцент"),
+    ("label.theme_background", "Фон"),
+    ("label.theme_text", "Текст"),
+    ("label.theme_font_size", "Размер шрифта, px"),
+    ("btn.reset_theme", "Сбросить тему"),
+    ("palette.title", "Палитра команд"),
+    ("palette.placeholder", "Команда, вкладка или действие… [Ctrl+K]"),
+    ("palette.empty", "Ничего не найдено"),
+    ("palette.tab", "вкладка"),
+    ("palette.run", "запуск"),
+    ("palette.action", "действие"),
+    ("palette.theme", "Сменить тему [F4]"),
+    ("stats.runs", "запуски"),
+    ("stats.failure_rate", "ошибки"),
+    ("stats.last_failed", "последняя ошибка"),
+    ("stats.avg", "ср. время"),
+    ("stats.empty", "В gucli.log пока нет запусков"),
+    ("processes.title", "Запущено"),
+    ("processes.empty", "Ни одна запущенная команда не работает"),
+    ("processes.started", "запущена"),
+    ("btn.kill", "Завершить"),
+    ("stats.note", "Подсчитано по gucli.log, где хранятся последние 100 строк. Время пишется начиная с этой версии."),
+    ("btn.autostart_on", "Автозапуск: ВКЛ"),
+    ("btn.autostart_off", "Автозапуск: ВЫКЛ"),
+    ("label.autostart_backend", "Автозапуск через"),
+    ("label.autostart_delay", "Задержка автозапуска, с"),
+    ("label.start_hidden", "Запускать скрыто (только трей)"),
+    ("label.check_updates", "Проверять новые версии на GitHub (при запуске и раз в день)"),
+    ("label.on_quit", "Выполнить при выходе"),
+    ("label.notify_length", "длина уведомления"),
+    ("placeholder.default", "по умолчанию"),
+    ("btn.open_log", "Открыть лог"),
+    ("btn.open_config", "Открыть конфиг"),
+    ("btn.encrypt", "🔓 Зашифровать"),
+    ("btn.decrypt", "🔒 Расшифровать"),
+    ("label.encryption", "Хранить commands.toml зашифрованным вашим ключом GPG (commands.toml.gpg), gpg-agent спросит пароль при запуске"),
+    ("btn.reset", "Сброс и перезапуск"),
+    ("btn.really_reset", "Точно сбросить?"),
+    ("btn.restore_backup", "Восстановить копию и перезапустить"),
+    ("label.line", "строка"),
+    ("launcher.started", "Запущено"),
+    ("launcher.empty", "Нет команд для этой машины, добавьте их в настройках"),
+    ("config.invalid", "Не удалось загрузить commands.toml, трей работает без команд:"),
+    ("config.overwrite", "Сохранение здесь заменит файл командами ниже."),
+    ("btn.add", "Добавить команду"),
+    ("btn.test_all", "Проверить все"),
+    ("label.dangerous", "Похоже на разрушительную команду, запуск только после двух подтверждений"),
+    ("btn.preview", "Предпросмотр: что будет запущено, без запуска"),
+    ("btn.export_service", "Экспорт в службу systemd"),
+    ("btn.export_timer", "Экспорт в таймер systemd"),
+    ("btn.really_test_all", "Точно запустить все?"),
+    ("btn.testing", "Проверка..."),
+    ("btn.save", "Сохранить"),
+    ("btn.duplicate", "Дублировать"),
+    ("btn.delete", "Удалить"),
+    ("btn.run_test", "Тест"),
+    ("btn.search", "Найти"),
+    ("btn.refresh", "Обновить"),
+    ("btn.self_test", "Самопроверка"),
+    ("btn.diagnostics", "Собрать диагностику"),
+    ("btn.collecting", "Сбор..."),
+    ("head.shell", "оболочка"),
+    ("head.command", "команда"),
+    ("head.icon", "иконка"),
+    ("head.sn", "увед."),
+    ("head.rows", "дублировать / удалить"),
+    ("head.test", "тест"),
+    ("label.status", "СТАТУС"),
+    ("label.count", "всего: "),
+    ("label.options", "Параметры"),
+    ("label.help", "Справка"),
+    ("label.language", "Язык"),
+    ("label.left_click", "Левый клик по трею"),
+    ("man.title", "Консольная справка по команде: man или встроенный --help"),
+    ("about.title", "Ваш личный командный центр в системном трее"),
+    ("about.intro", "Gucli (от GUI + CLI) - простое приложение в системном трее, превращающее частые консольные команды в пункты меню для запуска одним щелчком."),
+    ("about.homepage", "О совместимости, зависимостях и для сообщений о проблемах - на домашней странице."),
+    ("help.settings", "<ul>
+    <li>Программа выполняет shell-команды, которые ничего не возвращают или возвращают текст</li>
+    <li>Команды можно редактировать в <code>/home/$USER/.config/gucli/commands.toml</code> (или в каталоге из <code>$GUCLI_CONFIG_DIR</code>), не открывая это окно, <code>gucli --reload</code> применяет их</li>
+    <li>Ошибки и результаты пишутся в <code>/home/$USER/.config/gucli/gucli.log</code> (не больше 100 строк, ротация не нужна)</li>
+    <li>Интерактивные команды и команды с непрерывным выводом не рекомендуются, если не отмечено Параметры → in terminal: тогда команда открывается в эмуляторе терминала (найденном автоматически или <code>terminal</code> в <code>[settings]</code>)</li>
+    <li>Таймаут выполнения команды: 500 мс (<code>long_running = true</code>, чтобы выполнять без него)</li>
+    <li>Текст уведомления ограничен 200 символами (длинные сообщения могут подвесить GTK)</li>
+    <li>Команды показываются в меню трея как есть - задайте Параметры → label (например <code>Restart VPN</code>), чтобы показать короткий текст</li>
+    <li>shell: переключает установленные оболочки; другие интерпретаторы (nu, pwsh, python...) регистрируются в секции <code>[settings]</code> файла <code>commands.toml</code></li>
+    <li>icon: до 8 символов UTF-8 (эмодзи, короткий текст или пусто) или путь к картинке <code>.png</code>/<code>.svg</code>, например <code>~/icons/vpn.svg</code></li>
+    <li>sn (show notification): показывать системное уведомление (по умолчанию: да). Уведомления об ошибках показываются всегда</li>
+    <li>При вводе команды предлагаются программы из <code>$PATH</code> и ранее запускавшиеся команды</li>
+    <li>Параметры → run on: выполнить локально, по <code>ssh</code> на хосте или внутри контейнера <code>podman</code>/<code>docker</code></li>
+    <li>Параметры → interactive: запуск как <code>shell -i -c</code>, чтобы были доступны алиасы и функции из <code>~/.bashrc</code>, <code>~/.zshrc</code> и т.д.</li>
+    <li>Параметры → confirm: перед запуском из трея уведомление спрашивает <code>Run now / Skip</code>; без ответа команда пропускается через 60 секунд. Полезно для привилегированных команд, которые не должны выполняться без вас</li>
+    <li>Параметры → gui: для команд, меняющих рабочий стол (раскладка мониторов, темы...). При ошибке снимок экрана сохраняется в <code>~/.config/gucli/screenshots</code> (хранятся последние 20), путь добавляется в лог</li>
+    <li>Параметры → hosts: имена хостов через запятую, команда показывается в трее только на этих машинах (строки других хостов приглушены). Удобно, когда <code>commands.toml</code> синхронизируется между компьютерами</li>
+    <li>Параметры → working dir: каталог, в котором выполняется команда (локально и в терминале). Пункт трея <code>Терминал в</code> открывает там терминал, например чтобы разобраться с ошибкой</li>
+    <li>Параметры → default: команда, запускаемая двойным щелчком по иконке в трее или одиночным, если в О программе → Левый клик по трею выбрано <code>default</code> (только одна команда)</li>
+    <li>Параметры → sandbox: запуск через <code>bwrap</code> или <code>firejail</code> без сети, с домашним каталогом только для чтения и отдельным <code>/tmp</code> (ошибка, если ни один не установлен)</li>
+    <li>Отметьте строки рядом с номером, чтобы удалить их или переключить уведомления разом</li>
+    <li>Перетащите строку за номер, чтобы переместить её, или используйте ↑/↓</li>
+    <li>sort: сортировка таблицы по алфавиту, по числу запусков или по последней ошибке (из лога). Сортировка меняет только вид, <code>Apply order</code> делает её порядком в трее</li>
+    <li>Параметры → mnemonic: клавиша, запускающая команду при открытом меню трея (подчёркивается, если окружение это поддерживает)</li>
+    <li>☆/★: закреплённые команды идут первыми в меню трея, над разделителем</li>
+    <li>Tray order: порядок меню трея независимо от этой таблицы (сохраняется как <code>tray_position</code>), <code>Use table order</code> его сбрасывает</li>
+    <li>Всегда сначала тестируйте команды, даже если знаете, что делаете</li>
+</ul>"),
+    ("help.search", "<ul>
+    <li>Если нужна точная справка с конкретными аргументами, введите команду полностью</li>
+    <li>Иначе программа перебирает набор:<br />
+    <code>[ --help, -h, --usage, help, -help, -?, --longhelp, --long-help, --help-all, info]</code><br />
+    затем проверяет man-страницы и возвращает первый найденный вариант.</li>
+    <li>Чтобы окно не зависало, длина результата ограничена 30 000 символов</li>
+    <li>Начните вводить, чтобы выбрать программу из установленных в <code>$PATH</code></li>
+    <li>Укажите раздел man перед командой, чтобы открыть только эту страницу, например <code>3 printf</code>. Если у команды есть страницы в нескольких разделах, они перечислены над результатом</li>
+    <li>Найденная справка кэшируется на 30 минут, <code>Обновить</code> ищет заново</li>
+    <li>Не знаете имя команды? Отметьте <code>keyword</code>, чтобы найти подходящие команды (<code>apropos</code>), и нажмите на одну, чтобы загрузить её справку</li>
+</ul>"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_locales() {
+        assert_eq!(normalize("ru_RU.UTF-8"), "ru");
+        assert_eq!(normalize("RU-ru"), "ru");
+        assert_eq!(normalize("de_DE"), "en");
+        assert_eq!(normalize(""), "en");
+    }
+
+    #[test]
+    fn falls_back_to_english_then_the_key() {
+        assert_eq!(tr("ru", "tray.settings"), lookup(RU, "tray.settings").unwrap());
+        assert_eq!(tr("fr", "tray.settings"), "⚙️   Settings");
+        assert_eq!(tr("ru", "no.such.key"), "no.such.key");
+    }
+
+    #[test]
+    fn tables_have_unique_keys() {
+        for table in [EN, RU] {
+            for (i, (key, _)) in table.iter().enumerate() {
+                assert!(!table[i + 1..].iter().any(|(k, _)| k == key), "duplicate key {key}");
+            }
+        }
+    }
+
+    #[test]
+    fn translations_only_use_english_keys() {
+        for (key, _) in RU {
+            assert!(lookup(EN, key).is_some(), "{key} has no English text");
+        }
+    }
+}
@@ -0,0 +1,529 @@
+//! Types exchanged between the Leptos frontend and the Tauri backend.
+//! Both sides (de)serialize the same definitions, so a field added here is seen everywhere.
+
+use serde::{Deserialize, Serialize};
+
+pub mod i18n;
+pub mod lint;
+
+/// shells a command can be run with. The cfg only holds in the backend, the UI gets them by get_builtin_shells
+#[cfg(not(windows))]
+pub const SHELLS: [&str; 4] = ["sh", "bash", "zsh", "fish"];
+#[cfg(windows)]
+pub const SHELLS: [&str; 3] = ["cmd", "powershell", "pwsh"];
+
+/// shell of a new command: zsh is the login shell on macOS, aliases live in ~/.zshrc. The UI gets it by get_default_shell
+#[cfg(target_os = "macos")]
+pub const DEFAULT_SHELL: &str = "zsh";
+#[cfg(not(target_os = "macos"))]
+pub const DEFAULT_SHELL: &str = "sh";
+
+/// command as used by the app: the TOML entry plus a runtime id
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UserCommand {
+    pub id: String,
+    pub shell: String,
+    pub command: String,
+    pub icon: String,
+    pub sn: bool,
+    #[serde(default)]
+    pub sandbox: bool,
+    #[serde(default)]
+    pub executor: String,
+    #[serde(default)]
+    pub host: String,
+    #[serde(default)]
+    pub container: String,
+    #[serde(default)]
+    pub interactive: bool,
+    #[serde(default)]
+    pub confirm: bool,
+    #[serde(default)]
+    pub in_terminal: bool,
+    #[serde(default)]
+    pub cwd: String,
+    #[serde(default)]
+    pub tray_position: Option<u32>,
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default)]
+    pub gui: bool,
+    #[serde(default)]
+    pub label: String,
+    #[serde(default)]
+    pub mnemonic: String,
+    #[serde(default)]
+    pub hosts: Vec<String>,
+    #[serde(default)]
+    pub default: bool,
+    #[serde(default)]
+    pub health_interval: Option<u64>,
+    #[serde(default)]
+    pub ssh_key: String,
+    #[serde(default)]
+    pub output_file: String,
+    #[serde(default)]
+    pub notify_cooldown: Option<u64>,
+    #[serde(default)]
+    pub on_busy: String,
+    #[serde(default)]
+    pub queue: String,
+    #[serde(default)]
+    pub notify_length: Option<usize>,
+    #[serde(default)]
+    pub long_running: bool,
+    #[serde(default)]
+    pub run_on_start: bool,
+}
+
+impl UserCommand {
+    /// new row in the settings window
+    pub fn new(id: String) -> Self {
+        UserCommand {
+            id,
+            shell: String::from(DEFAULT_SHELL),
+            command: String::from("new"),
+            icon: String::from(""),
+            sn: true,
+            sandbox: false,
+            executor: String::from("local"),
+            host: String::new(),
+            container: String::new(),
+            interactive: false,
+            confirm: false,
+            in_terminal: false,
+            cwd: String::new(),
+            tray_position: None,
+            pinned: false,
+            gui: false,
+            label: String::new(),
+            mnemonic: String::new(),
+            hosts: Vec::new(),
+            default: false,
+            health_interval: None,
+            ssh_key: String::new(),
+            output_file: String::new(),
+            notify_cooldown: None,
+            on_busy: String::new(),
+            queue: String::new(),
+            notify_length: None,
+            long_running: false,
+            run_on_start: false,
+        }
+    }
+
+    /// no hosts filter, or hostname is listed in it
+    pub fn available_on(&self, hostname: &str) -> bool {
+        self.hosts.is_empty() || self.hosts.iter().any(|h| h.trim().eq_ignore_ascii_case(hostname))
+    }
+
+    /// label, or the command when no label is set
+    pub fn display_name(&self) -> &str {
+        if self.label.trim().is_empty() { &self.command } else { self.label.trim() }
+    }
+}
+
+/// commands as shown in the tray: pinned first, by tray_position, then in table order
+pub fn tray_order(commands: &[UserCommand]) -> Vec<&UserCommand> {
+    let mut ordered: Vec<&UserCommand> = commands.iter().collect();
+    ordered.sort_by_key(|c| (!c.pinned, c.tray_position.unwrap_or(u32::MAX)));
+    ordered
+}
+
+// Structure for TOML (without ID)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TomlCommand {
+    pub shell: String,
+    pub command: String,
+    pub icon: String,
+    pub sn: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub sandbox: bool,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub executor: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub host: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub container: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub interactive: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub confirm: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub in_terminal: bool,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub cwd: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tray_position: Option<u32>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub gui: bool,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub label: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub mnemonic: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hosts: Vec<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub default: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health_interval: Option<u64>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub ssh_key: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub output_file: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notify_cooldown: Option<u64>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub on_busy: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub queue: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notify_length: Option<usize>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub long_running: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub run_on_start: bool,
+}
+
+impl TomlCommand {
+    /// add id on load
+    pub fn with_id(self, id: String) -> UserCommand {
+        UserCommand {
+            id,
+            shell: self.shell,
+            command: self.command,
+            icon: self.icon,
+            sn: self.sn,
+            sandbox: self.sandbox,
+            executor: self.executor,
+            host: self.host,
+            container: self.container,
+            interactive: self.interactive,
+            confirm: self.confirm,
+            in_terminal: self.in_terminal,
+            cwd: self.cwd,
+            tray_position: self.tray_position,
+            pinned: self.pinned,
+            gui: self.gui,
+            label: self.label,
+            mnemonic: self.mnemonic,
+            hosts: self.hosts,
+            default: self.default,
+            health_interval: self.health_interval,
+            ssh_key: self.ssh_key,
+            output_file: self.output_file,
+            notify_cooldown: self.notify_cooldown,
+            on_busy: self.on_busy,
+            queue: self.queue,
+            notify_length: self.notify_length,
+            long_running: self.long_running,
+            run_on_start: self.run_on_start,
+        }
+    }
+}
+
+// remove id on save
+impl From<&UserCommand> for TomlCommand {
+    fn from(cmd: &UserCommand) -> Self {
+        TomlCommand {
+            shell: cmd.shell.clone(),
+            command: cmd.command.clone(),
+            icon: cmd.icon.clone(),
+            sn: cmd.sn,
+            sandbox: cmd.sandbox,
+            executor: cmd.executor.clone(),
+            host: cmd.host.clone(),
+            container: cmd.container.clone(),
+            interactive: cmd.interactive,
+            confirm: cmd.confirm,
+            in_terminal: cmd.in_terminal,
+            cwd: cmd.cwd.clone(),
+            tray_position: cmd.tray_position,
+            pinned: cmd.pinned,
+            gui: cmd.gui,
+            label: cmd.label.clone(),
+            mnemonic: cmd.mnemonic.clone(),
+            hosts: cmd.hosts.clone(),
+            default: cmd.default,
+            health_interval: cmd.health_interval,
+            ssh_key: cmd.ssh_key.clone(),
+            output_file: cmd.output_file.clone(),
+            notify_cooldown: cmd.notify_cooldown,
+            on_busy: cmd.on_busy.clone(),
+            queue: cmd.queue.clone(),
+            notify_length: cmd.notify_length,
+            long_running: cmd.long_running,
+            run_on_start: cmd.run_on_start,
+        }
+    }
+}
+
+/// interpreter registered in [settings] in addition to SHELLS
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CustomShell {
+    pub name: String,
+    pub path: String,
+    /// flag placed before the command string
+    #[serde(default = "default_shell_flag")]
+    pub flag: String,
+}
+
+fn default_shell_flag() -> String {
+    "-c".to_string()
+}
+
+/// [settings] section of commands.toml
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AppSettings {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub shells: Vec<CustomShell>,
+    /// terminal emulator for in_terminal commands, detected when empty
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub terminal: String,
+    /// strftime format for timestamps in the UI and log, locale default when empty
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub time_format: String,
+    /// UI language code from i18n::LANGUAGES, system locale when empty
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub language: String,
+    /// what a left click on the tray icon does, one of LEFT_CLICK_ACTIONS, "menu" when empty
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub left_click: String,
+    /// one of AUTOSTART_BACKENDS, "desktop" when empty
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub autostart: String,
+    /// seconds to wait at login before starting, for tray hosts that come up late
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub autostart_delay: Option<u32>,
+    /// never open a window at startup, only the tray icon (same as --hidden)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub start_hidden: bool,
+    /// localhost port of the token-protected HTTP trigger, off when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http_port: Option<u16>,
+    /// also send audit log entries to syslog (LOG_AUTHPRIV)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub audit_syslog: bool,
+    /// one of LOG_TARGETS, "file" when empty
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub log_target: String,
+    /// one of LOG_LEVELS, "info" when empty. The tray toggles debug at runtime
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub log_level: String,
+    /// app log path, "~/" is the home dir. ~/.config/gucli/gucli.log when empty
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub log_file: String,
+    /// lines kept in the log file, newest first
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_max_lines: Option<usize>,
+    /// characters of output in a notification, 200 when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notify_length: Option<usize>,
+    /// look for new releases on GitHub at start and once a day, off by default
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub check_updates: bool,
+    /// sh command run when gucli quits, e.g. to stop a VPN started from the tray
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub on_quit: String,
+    /// colors and font size of the "custom" theme
+    #[serde(default, skip_serializing_if = "CustomTheme::is_empty")]
+    pub custom_theme: CustomTheme,
+}
+
+/// [settings.custom_theme], unset values keep the light theme's
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CustomTheme {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub accent: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub background: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub text: String,
+    /// px
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub font_size: Option<u32>,
+}
+
+impl CustomTheme {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// inline style for the root element: the theme's CSS variables, values that could
+    /// break out of a declaration are dropped
+    pub fn css(&self) -> String {
+        fn safe(v: &str) -> Option<&str> {
+            let v = v.trim();
+            (!v.is_empty() && v.chars().all(|c| c.is_ascii_alphanumeric() || "#(),.% -".contains(c))).then_some(v)
+        }
+        let mut css = String::new();
+        if let Some(accent) = safe(&self.accent) {
+            css.push_str(&format!("--act:{accent};--act1:{accent};"));
+        }
+        if let Some(background) = safe(&self.background) {
+            css.push_str(&format!("--mbc:{background};"));
+        }
+        if let Some(text) = safe(&self.text) {
+            css.push_str(&format!("--mc:{text};"));
+        }
+        if let Some(size) = self.font_size.filter(|s| (8..=40).contains(s)) {
+            css.push_str(&format!("font-size:{size}px;"));
+        }
+        css
+    }
+}
+
+/// show the menu, open the settings window, run the default command
+pub const LEFT_CLICK_ACTIONS: [&str; 3] = ["menu", "settings", "default"];
+
+/// what a start of an already running command does, "skip" when empty
+pub const ON_BUSY_POLICIES: [&str; 3] = ["skip", "queue", "parallel"];
+
+/// where the app log goes: gucli.log, the systemd journal or both
+pub const LOG_TARGETS: [&str; 3] = ["file", "journald", "both"];
+
+pub const LOG_LEVELS: [&str; 5] = ["error", "warn", "info", "debug", "trace"];
+
+/// XDG autostart .desktop file or a systemd user unit
+pub const AUTOSTART_BACKENDS: [&str; 2] = ["desktop", "systemd"];
+
+impl AppSettings {
+    pub fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    pub fn custom_shell(&self, name: &str) -> Option<&CustomShell> {
+        self.shells.iter().find(|s| s.name == name)
+    }
+}
+
+// Configuration for TOML
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandsConfig {
+    /// layout of the file, configs without it predate versioning (0)
+    #[serde(default)]
+    pub version: u32,
+    #[serde(default, skip_serializing_if = "AppSettings::is_default")]
+    pub settings: AppSettings,
+    pub commands: Vec<TomlCommand>,
+}
+
+/// one line of `apropos` output
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AproposEntry {
+    pub name: String,
+    pub section: String,
+    pub description: String,
+}
+
+/// runs of one command recorded in gucli.log
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct RunStats {
+    pub command: String,
+    pub runs: u32,
+    /// log timestamp of the newest failure, empty if none
+    pub last_failed: String,
+    /// 0 = the newest run in the log failed, None = never failed
+    pub failed_rank: Option<usize>,
+    pub failures: u32,
+    /// mean duration of the runs logged with one
+    pub avg_ms: Option<u64>,
+}
+
+impl RunStats {
+    /// share of failed runs, 0.0..=1.0
+    pub fn failure_rate(&self) -> f64 {
+        if self.runs == 0 { 0.0 } else { self.failures as f64 / self.runs as f64 }
+    }
+}
+
+/// one run of a command, tracked by the execution registry
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct Execution {
+    pub run_id: u64,
+    pub command_id: String,
+    pub command: String,
+    pub started: String,
+    /// empty while running
+    pub finished: String,
+    pub running: bool,
+    pub success: bool,
+    /// result or error text
+    pub output: String,
+}
+
+/// process group of a started command, still alive
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunningProcess {
+    /// process group id, the same as the pid of the spawned shell
+    pub pid: i32,
+    pub command: String,
+    pub started: String,
+}
+
+/// newest release found by the update check
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct ReleaseInfo {
+    pub version: String,
+    /// release page
+    pub url: String,
+    /// newer than the running version
+    pub newer: bool,
+}
+
+/// outcome of a run from the settings window (run_test)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TestResult {
+    pub success: bool,
+    /// None when killed, timed out or run in a terminal
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    /// error text of a failed run: stderr with hints, or why it could not run
+    pub stderr: String,
+    pub duration_ms: u64,
+    /// set instead when the command did not run here: skipped, or left running in the background
+    pub note: String,
+}
+
+impl TestResult {
+    pub fn note(note: impl Into<String>) -> Self {
+        Self { note: note.into(), ..Default::default() }
+    }
+}
+
+/// payload of the `command-executed` event, sent after every run from the tray, the window or a trigger
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CommandExecuted {
+    pub command: String,
+    pub result: TestResult,
+}
+
+/// problem of one command row, see validate_config
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigProblem {
+    /// index in the command list
+    pub row: usize,
+    /// field the problem is in, e.g. "command", "shell"
+    pub field: String,
+    pub message: String,
+    /// line of the command a syntax error is on
+    pub line: Option<usize>,
+    /// saved anyway, e.g. a shell missing only on this machine
+    pub warning: bool,
+}
+
+impl ConfigProblem {
+    pub fn new(row: usize, field: &str, message: impl Into<String>) -> Self {
+        Self { row, field: field.to_string(), message: message.into(), line: None, warning: false }
+    }
+}
+
+/// result of one self-test check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelfTestItem {
+    pub name: String,
+    pub passed: bool,
+    pub detail: String,
+}
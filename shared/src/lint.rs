@@ -0,0 +1,88 @@
+//! Heuristics for obviously destructive commands: flagged in the settings row,
+//! confirmed twice before they run. Not a sandbox, only a guard against slips.
+
+/// reason the command looks destructive, None when nothing matched
+pub fn dangerous(command: &str) -> Option<&'static str> {
+    // one space between words, so "rm  -rf   /" matches too
+    let normalized = command.split_whitespace().collect::<Vec<_>>().join(" ");
+    let words: Vec<&str> = normalized.split(' ').collect();
+
+    if is_recursive_rm_of_root(&words) {
+        return Some("recursive rm of / or the home directory");
+    }
+    if words.iter().any(|w| w.starts_with("mkfs") || *w == "wipefs") {
+        return Some("formats or wipes a filesystem");
+    }
+    if words.contains(&"dd") && words.iter().any(|w| w.starts_with("of=/dev/") && !w.starts_with("of=/dev/null")) {
+        return Some("dd writes to a block device");
+    }
+    if words.iter().any(|w| w.starts_with(">/dev/sd") || w.starts_with(">/dev/nvme"))
+        || normalized.contains("> /dev/sd")
+        || normalized.contains("> /dev/nvme")
+    {
+        return Some("redirects output onto a disk");
+    }
+    let compact: String = command.chars().filter(|c| !c.is_whitespace()).collect();
+    if compact.contains(":(){:|:&};:") {
+        return Some("fork bomb");
+    }
+    if ["chmod", "chown"].iter().any(|c| words.contains(c))
+        && words.iter().any(|w| *w == "-R" || *w == "--recursive")
+        && words.contains(&"/")
+    {
+        return Some("recursive chmod/chown of /");
+    }
+    None
+}
+
+fn is_recursive_rm_of_root(words: &[&str]) -> bool {
+    let Some(rm) = words.iter().position(|w| *w == "rm" || w.ends_with("/rm")) else {
+        return false;
+    };
+    let args = &words[rm + 1..];
+    let recursive = args.iter().any(|a| {
+        *a == "--recursive" || (a.starts_with('-') && !a.starts_with("--") && (a.contains('r') || a.contains('R')))
+    });
+    let root = args
+        .iter()
+        .any(|a| matches!(*a, "/" | "/*" | "~" | "~/" | "~/*" | "$HOME" | "$HOME/" | "$HOME/*" | "--no-preserve-root"));
+    recursive && root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::dangerous;
+
+    #[test]
+    fn flags_destructive_commands() {
+        for command in [
+            "rm -rf /",
+            "sudo rm  -rf   /",
+            "rm -r --no-preserve-root /",
+            "/bin/rm -fR ~/",
+            "rm --recursive $HOME",
+            "mkfs.ext4 /dev/sda1",
+            "wipefs -a /dev/sdb",
+            "dd if=/dev/zero of=/dev/sda bs=1M",
+            "echo x > /dev/nvme0n1",
+            ":(){ :|:& };:",
+            "chown -R nobody /",
+        ] {
+            assert!(dangerous(command).is_some(), "{command}");
+        }
+    }
+
+    #[test]
+    fn passes_ordinary_commands() {
+        for command in [
+            "rm -rf ./build",
+            "rm /tmp/file",
+            "ls -la /",
+            "dd if=/dev/zero of=/dev/null count=1",
+            "chmod -R 755 ./site",
+            "echo rm -rf",
+        ] {
+            assert_eq!(dangerous(command), None, "{command}");
+        }
+    }
+}
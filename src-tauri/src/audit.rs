@@ -0,0 +1,277 @@
+use crate::files::{config_dir, make_private, private_file, settings, state_dir};
+use chrono::Local;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+#[cfg(unix)]
+use std::ffi::CString;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use tracing::{error, warn};
+use uuid::Uuid;
+
+/// one line per execution in the state dir, separate from gucli.log
+pub const AUDIT_FILE: &str = "audit.log";
+
+/// audit.log is moved to the next free audit.log.N at this size, older ones are kept
+pub const MAX_AUDIT_SIZE: u64 = 4 * 1024 * 1024;
+
+// key of the prev= chain, in the keyring: who can edit the file can't recompute the chain
+const KEY_SERVICE: &str = "gucli-audit";
+const KEY_USER: &str = "chain-key";
+// in the config dir when there is no keyring, still not next to the log
+const KEY_FILE: &str = "audit.key";
+// count and HMAC of the last line, kept with the key: cutting the end off the log no longer goes unnoticed
+const HEAD_USER: &str = "chain-head";
+const HEAD_FILE: &str = "audit.head";
+
+/// end of the chain: lines written so far and the HMAC of the last one
+#[derive(Debug, Clone, PartialEq)]
+struct Head {
+    count: u64,
+    mac: String,
+}
+
+// lines are chained: each one carries the HMAC of the line before it,
+// read from the stored head (or the files) once, then kept here
+static LAST: Mutex<Option<Head>> = Mutex::new(None);
+
+/// append `when user outcome command` to the audit log, and to syslog with `audit_syslog`.
+/// Editing or deleting a line breaks the `prev=` HMAC of the next one, see `gucli --verify-audit`
+pub fn record(command: &str, outcome: &str) {
+    let entry = format!(
+        "{} {} {} command={:?}",
+        Local::now().to_rfc3339(),
+        user(),
+        outcome,
+        command
+    );
+    if let Err(e) = append(&entry) {
+        error!("Audit log not written: {}", e);
+    }
+    if settings().audit_syslog {
+        syslog(&entry);
+    }
+}
+
+fn append(entry: &str) -> Result<(), String> {
+    let path = state_dir()?.join(AUDIT_FILE);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let key = key()?;
+    let mut last = LAST.lock().unwrap_or_else(|e| e.into_inner());
+    let head = match last.take() {
+        Some(head) => head,
+        None => load_head().unwrap_or_else(|| scan(&key, &path)),
+    };
+    // the chain goes on in the new file, verify reads them all
+    let files = files(&path);
+    if fs::metadata(&path).is_ok_and(|m| m.len() >= MAX_AUDIT_SIZE)
+        && let Err(e) = fs::rename(&path, rotated(&path, files.len()))
+    {
+        warn!("Audit log not rotated (append-only?): {}", e);
+    }
+    let line = format!("{} prev={}", entry, head.mac);
+    let mut file = private_file()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    // older files may have been created with a wider mode
+    make_private(&path);
+    writeln!(file, "{}", line).map_err(|e| e.to_string())?;
+    let head = Head { count: head.count + 1, mac: mac(&key, &line) };
+    store_head(&head);
+    *last = Some(head);
+    Ok(())
+}
+
+/// check the prev= chain from audit.log.1 on to audit.log against the stored head,
+/// Err names the first line that breaks it
+pub fn verify() -> Result<String, String> {
+    let key = key()?;
+    let path = state_dir()?.join(AUDIT_FILE);
+    let mut prev = genesis();
+    let mut count = 0;
+    for file in files(&path) {
+        let Ok(content) = fs::read_to_string(&file) else {
+            continue;
+        };
+        for (number, line) in content.lines().enumerate() {
+            let found = line.rsplit_once(" prev=").map(|(_, mac)| mac);
+            if found != Some(prev.as_str()) {
+                return Err(if count == 0 {
+                    format!(
+                        "{}:{}: prev= does not start the chain, older lines or a rotated file were removed",
+                        file.display(),
+                        number + 1
+                    )
+                } else {
+                    format!(
+                        "{}:{}: prev= does not match the line before, a line was edited, inserted or removed",
+                        file.display(),
+                        number + 1
+                    )
+                });
+            }
+            prev = mac(&key, line);
+            count += 1;
+        }
+    }
+    match load_head() {
+        Some(head) if head != (Head { count, mac: prev }) => Err(format!(
+            "the chain ends after {} lines, {} were written: lines at the end were edited or removed",
+            count, head.count
+        )),
+        Some(_) => Ok(format!("Audit log intact, {} lines verified", count)),
+        None => Ok(format!("Audit log intact, {} lines verified (no chain head stored yet)", count)),
+    }
+}
+
+/// rotated files oldest first, then audit.log
+fn files(path: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = (1..).map(|n| rotated(path, n)).take_while(|file| file.exists()).collect();
+    files.push(path.to_path_buf());
+    files
+}
+
+fn rotated(path: &Path, number: usize) -> PathBuf {
+    path.with_extension(format!("log.{}", number))
+}
+
+/// prev= of the first line
+fn genesis() -> String {
+    "0".repeat(64)
+}
+
+/// head from the files, before one was stored
+fn scan(key: &str, path: &Path) -> Head {
+    let mut head = Head { count: 0, mac: genesis() };
+    for file in files(path) {
+        for line in fs::read_to_string(&file).unwrap_or_default().lines() {
+            head = Head { count: head.count + 1, mac: mac(key, line) };
+        }
+    }
+    head
+}
+
+/// the head is stored where the key is: the keyring, or audit.head next to audit.key
+fn load_head() -> Option<Head> {
+    let text = if key_in_file() {
+        fs::read_to_string(config_dir().ok()?.join(HEAD_FILE)).ok()?
+    } else {
+        keyring::Entry::new(KEY_SERVICE, HEAD_USER).ok()?.get_password().ok()?
+    };
+    let (count, mac) = text.trim().split_once(' ')?;
+    Some(Head { count: count.parse().ok()?, mac: mac.to_string() })
+}
+
+fn store_head(head: &Head) {
+    let text = format!("{} {}", head.count, head.mac);
+    let stored = if key_in_file() {
+        config_dir().and_then(|dir| {
+            let path = dir.join(HEAD_FILE);
+            private_file()
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(&path)
+                .and_then(|mut file| file.write_all(text.as_bytes()))
+                .map_err(|e| format!("{}: {}", path.display(), e))
+        })
+    } else {
+        keyring::Entry::new(KEY_SERVICE, HEAD_USER)
+            .and_then(|entry| entry.set_password(&text))
+            .map_err(|e| e.to_string())
+    };
+    if let Err(e) = stored {
+        warn!("Audit chain head not stored: {}", e);
+    }
+}
+
+fn key_in_file() -> bool {
+    config_dir().is_ok_and(|dir| dir.join(KEY_FILE).exists())
+}
+
+fn mac(key: &str, line: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key.as_bytes()).expect("HMAC takes keys of any size");
+    mac.update(line.as_bytes());
+    format!("{:x}", mac.finalize().into_bytes())
+}
+
+/// the chain key from the keyring, created on first use; a 0600 file without a keyring
+fn key() -> Result<String, String> {
+    static KEY: OnceLock<String> = OnceLock::new();
+    if let Some(key) = KEY.get() {
+        return Ok(key.clone());
+    }
+    let path = config_dir()?.join(KEY_FILE);
+    // once without a keyring, always the file: a new key would break the chain
+    let key = match fs::read_to_string(&path) {
+        Ok(key) if !key.trim().is_empty() => key.trim().to_string(),
+        _ => match keyring::Entry::new(KEY_SERVICE, KEY_USER).and_then(|entry| {
+            entry.get_password().or_else(|e| match e {
+                keyring::Error::NoEntry => {
+                    let key = new_key();
+                    entry.set_password(&key).map(|_| key)
+                }
+                other => Err(other),
+            })
+        }) {
+            Ok(key) => key,
+            Err(e) => {
+                warn!("No keyring ({}), the audit chain key is kept in {}", e, path.display());
+                write_key_file(&path)?
+            }
+        },
+    };
+    Ok(KEY.get_or_init(|| key).clone())
+}
+
+fn write_key_file(path: &Path) -> Result<String, String> {
+    let key = new_key();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let _ = fs::remove_file(path);
+    private_file()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(key.as_bytes()))
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(key)
+}
+
+// two v4 uuids, 244 random bits
+fn new_key() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+#[cfg(unix)]
+fn user() -> String {
+    format!("user={} uid={}", std::env::var("USER").unwrap_or_default(), unsafe { nix::libc::getuid() })
+}
+
+#[cfg(windows)]
+fn user() -> String {
+    format!("user={}", std::env::var("USERNAME").unwrap_or_default())
+}
+
+#[cfg(unix)]
+fn syslog(entry: &str) {
+    let (Ok(ident), Ok(message)) = (CString::new("gucli-audit"), CString::new(entry.replace('\0', ""))) else {
+        return;
+    };
+    unsafe {
+        nix::libc::openlog(ident.as_ptr(), nix::libc::LOG_PID, nix::libc::LOG_AUTHPRIV);
+        nix::libc::syslog(nix::libc::LOG_NOTICE, c"%s".as_ptr(), message.as_ptr());
+        nix::libc::closelog();
+    }
+}
+
+// no syslog on Windows, audit.log only
+#[cfg(windows)]
+fn syslog(_entry: &str) {}
@@ -0,0 +1,546 @@
+use crate::files::{get_home_dir, load_commands, save_commands, settings};
+use crate::{UserCommand, hostname};
+use gucli_shared::{AUTOSTART_BACKENDS, tray_order};
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use tracing::{error, info};
+
+// is_set / set / remove of the platform, `backend` only chooses between the Linux ones
+#[cfg(target_os = "macos")]
+use launch_agent::{is_set as is_enabled, remove as disable, set as enable};
+#[cfg(windows)]
+use run_key::{is_set as is_enabled, remove as disable, set as enable};
+#[cfg(all(unix, not(target_os = "macos")))]
+use xdg::{is_set as is_enabled, remove as disable, set as enable};
+
+// overrides the packaged launcher, its right-click menu runs the pinned commands
+const LAUNCHER_FILE: &str = ".local/share/applications/gucli.desktop";
+const LAUNCHER_MARKER: &str = "X-Gucli-Generated=true";
+
+#[tauri::command]
+pub async fn autostart_toggle() -> Result<String, String> {
+    let enabled = autostart_status().await.map_err(|e| {
+        error!(%e, "cannot get autostart status");
+        e
+    })?;
+    let backend = backend();
+    if enabled {
+        disable(&backend)?;
+        Ok("autostart disabled".into())
+    } else {
+        enable(&backend)?;
+        Ok("autostart enabled".into())
+    }
+}
+
+#[tauri::command]
+pub async fn autostart_status() -> Result<bool, String> {
+    is_enabled(&backend())
+}
+
+#[tauri::command]
+pub fn get_autostart_backend() -> String {
+    backend()
+}
+
+/// switch backends, an enabled autostart moves to the new one
+#[tauri::command]
+pub fn set_autostart_backend(backend: String) -> Result<String, String> {
+    if !AUTOSTART_BACKENDS.contains(&backend.as_str()) {
+        return Err(format!("Unknown autostart backend `{}`", backend));
+    }
+    let old = self::backend();
+    let was_enabled = is_enabled(&old)?;
+    if was_enabled {
+        disable(&old)?;
+    }
+
+    let mut config = load_commands().map_err(|e| e.to_string())?;
+    config.settings.autostart = if backend == "desktop" { String::new() } else { backend.clone() };
+    save_commands(&config).map_err(|e| e.to_string())?;
+
+    if was_enabled {
+        enable(&backend)?;
+    }
+    Ok(format!("Autostart backend: {}", backend))
+}
+
+#[tauri::command]
+pub fn get_autostart_delay() -> u32 {
+    settings().autostart_delay.unwrap_or(0)
+}
+
+/// saved in [settings], an enabled autostart entry is rewritten with the new delay
+#[tauri::command]
+pub async fn set_autostart_delay(delay: u32) -> Result<String, String> {
+    let mut config = load_commands().map_err(|e| e.to_string())?;
+    config.settings.autostart_delay = (delay > 0).then_some(delay);
+    save_commands(&config).map_err(|e| e.to_string())?;
+
+    let backend = backend();
+    if is_enabled(&backend)? {
+        enable(&backend)?;
+    }
+    Ok(format!("Autostart delay: {} s", delay))
+}
+
+fn backend() -> String {
+    match settings().autostart.as_str() {
+        "" => "desktop".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// binary to start: inside an AppImage current_exe is a temporary mount, $APPIMAGE is the file
+fn exec_path() -> Result<PathBuf, String> {
+    match std::env::var_os("APPIMAGE") {
+        Some(appimage) if !appimage.is_empty() => Ok(PathBuf::from(appimage)),
+        _ => std::env::current_exe().map_err(|e| e.to_string()),
+    }
+}
+
+/// rewrite an enabled entry whose binary moved, e.g. after an update of the AppImage
+pub fn repair() {
+    #[cfg(all(unix, not(target_os = "macos")))]
+    xdg::repair();
+}
+
+/// [Desktop Entry] group shared by the autostart entry and the launcher
+fn desktop_entry(exec: &str) -> String {
+    format!(
+        "[Desktop Entry]\n\
+        Name=Gucli\n\
+        Type=Application\n\
+        Categories=Utility\n\
+        StartupNotify=true\n\
+        Exec={}\n",
+        exec
+    )
+}
+
+/// application entry with a desktop action per pinned command, for launcher menus, krunner and rofi.
+/// Removed again when nothing is pinned, the packaged entry shows then
+pub fn write_launcher(commands: &[UserCommand]) -> Result<(), String> {
+    let path = get_home_dir()?.join(LAUNCHER_FILE);
+    let host = hostname();
+    let pinned: Vec<&UserCommand> = tray_order(commands)
+        .into_iter()
+        .filter(|c| c.pinned && c.available_on(&host))
+        .collect();
+    // a hand-written entry is left alone
+    let ours = fs::read_to_string(&path).map_or(true, |entry| entry.contains(LAUNCHER_MARKER));
+    if !ours {
+        return Ok(());
+    }
+    if pinned.is_empty() {
+        let _ = fs::remove_file(&path);
+        return Ok(());
+    }
+
+    let exec = exec_path()?;
+    let mut entry = desktop_entry(&exec_arg(&exec.to_string_lossy()));
+    let ids: Vec<String> = (0..pinned.len()).map(|n| format!("run{};", n)).collect();
+    entry.push_str(&format!("Icon=gucli\n{}\nActions={}\n", LAUNCHER_MARKER, ids.concat()));
+    for (n, cmd) in pinned.iter().enumerate() {
+        entry.push_str(&format!(
+            "\n[Desktop Action run{}]\nName={}\nExec={} --run {}\n",
+            n,
+            cmd.display_name().replace('\n', " "),
+            exec_arg(&exec.to_string_lossy()),
+            exec_arg(cmd.display_name())
+        ));
+    }
+    write_file(&path, &entry)?;
+    info!("Launcher written with {} action(s): {}", pinned.len(), path.display());
+    Ok(())
+}
+
+/// one quoted Exec= argument: the spec's escapes inside quotes, then the string value's own backslash
+fn exec_arg(arg: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in arg.chars() {
+        match c {
+            '"' | '`' | '$' | '\\' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            '%' => quoted.push_str("%%"),
+            '\n' => quoted.push(' '),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted.replace('\\', "\\\\")
+}
+
+pub(crate) fn write_file(path: &PathBuf, content: &str) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    fs::write(path, content).map_err(|e| e.to_string())
+}
+
+/// `systemctl --user ...` stdout, is-enabled reports "disabled" with a failure status
+pub(crate) fn systemctl(args: &[&str]) -> Result<String, String> {
+    let output = Command::new("systemctl")
+        .arg("--user")
+        .args(args)
+        .output()
+        .map_err(|e| format!("systemctl not available: {}", e))?;
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    if output.status.success() || args.first() == Some(&"is-enabled") {
+        Ok(stdout)
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// Windows: a value under HKCU\...\Run instead of .desktop files and units
+#[cfg(windows)]
+mod run_key {
+    use std::process::Command;
+    use tracing::info;
+
+    const KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+    const VALUE: &str = "Gucli";
+
+    pub fn is_set(_backend: &str) -> Result<bool, String> {
+        let status = Command::new("reg")
+            .args(["query", KEY, "/v", VALUE])
+            .output()
+            .map_err(|e| format!("reg not available: {}", e))?
+            .status;
+        Ok(status.success())
+    }
+
+    pub fn set(_backend: &str) -> Result<(), String> {
+        let exec = super::exec_path()?;
+        reg(&["add", KEY, "/v", VALUE, "/t", "REG_SZ", "/f", "/d", &format!("\"{}\" --hidden", exec.display())])?;
+        info!("Autostart enabled (registry): {}", exec.display());
+        Ok(())
+    }
+
+    pub fn remove(backend: &str) -> Result<(), String> {
+        if is_set(backend)? {
+            reg(&["delete", KEY, "/v", VALUE, "/f"])?;
+        }
+        info!("Autostart disabled (registry)");
+        Ok(())
+    }
+
+    fn reg(args: &[&str]) -> Result<(), String> {
+        let output = Command::new("reg").args(args).output().map_err(|e| format!("reg not available: {}", e))?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+        }
+    }
+}
+
+/// macOS: a LaunchAgent, launchd starts it at every login
+#[cfg(target_os = "macos")]
+mod launch_agent {
+    use super::write_file;
+    use crate::files::get_home_dir;
+    use std::fs;
+    use std::path::PathBuf;
+    use tracing::info;
+
+    const LABEL: &str = "site.jager.gucli";
+
+    fn plist_path() -> Result<PathBuf, String> {
+        Ok(get_home_dir()?.join("Library/LaunchAgents").join(format!("{}.plist", LABEL)))
+    }
+
+    pub fn is_set(_backend: &str) -> Result<bool, String> {
+        Ok(plist_path()?.exists())
+    }
+
+    pub fn set(_backend: &str) -> Result<(), String> {
+        let exec = super::exec_path()?.display().to_string().replace('&', "&amp;").replace('<', "&lt;");
+        let plist = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+            <!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n\
+            <plist version=\"1.0\">\n\
+            <dict>\n\
+            \t<key>Label</key><string>{}</string>\n\
+            \t<key>ProgramArguments</key>\n\
+            \t<array><string>{}</string><string>--hidden</string></array>\n\
+            \t<key>RunAtLoad</key><true/>\n\
+            \t<key>ProcessType</key><string>Interactive</string>\n\
+            </dict>\n\
+            </plist>\n",
+            LABEL, exec
+        );
+        write_file(&plist_path()?, &plist)?;
+        info!("Autostart enabled (LaunchAgent): {}", exec);
+        Ok(())
+    }
+
+    pub fn remove(_backend: &str) -> Result<(), String> {
+        let _ = fs::remove_file(plist_path()?);
+        info!("Autostart disabled (LaunchAgent)");
+        Ok(())
+    }
+}
+
+
+/// Linux: an XDG autostart entry or a systemd user unit, picked by the `autostart` setting
+#[cfg(all(unix, not(target_os = "macos")))]
+mod xdg {
+    use super::{desktop_entry, exec_arg, exec_path, systemctl, write_file};
+    use crate::files::{get_home_dir, settings};
+    use crate::flatpak;
+    use std::fs;
+    use std::path::PathBuf;
+    use tracing::{error, info};
+
+    const DESKTOP_FILE: &str = ".config/autostart/gucli.desktop";
+    // overrides the packaged socket-activated unit of the same name
+    const UNIT_FILE: &str = ".config/systemd/user/gucli.service";
+    const UNIT_MARKER: &str = "# written by gucli autostart";
+
+    pub fn is_set(backend: &str) -> Result<bool, String> {
+        // in Flatpak the Background portal owns the entry, whatever the backend says
+        if flatpak::active() {
+            return Ok(flatpak::autostart_entry(&get_home_dir()?).exists());
+        }
+        match backend {
+            "systemd" => {
+                let output = systemctl(&["is-enabled", "gucli.service"])?;
+                Ok(output.trim() == "enabled")
+            }
+            _ => {
+                // the user entry overrides a packaged one in /etc/xdg/autostart, Hidden=true included
+                let entry = fs::read_to_string(get_home_dir()?.join(DESKTOP_FILE))
+                    .ok()
+                    .or_else(|| system_entries().into_iter().find_map(|path| fs::read_to_string(path).ok()));
+                Ok(entry.is_some_and(|entry| starts_here(&entry, &current_desktops())))
+            }
+        }
+    }
+
+    /// XDG_CURRENT_DESKTOP, e.g. ["ubuntu", "gnome"], lowercase
+    fn current_desktops() -> Vec<String> {
+        std::env::var("XDG_CURRENT_DESKTOP")
+            .unwrap_or_default()
+            .split(':')
+            .filter(|d| !d.is_empty())
+            .map(str::to_lowercase)
+            .collect()
+    }
+
+    /// packaged autostart entries of gucli in XDG_CONFIG_DIRS
+    fn system_entries() -> Vec<PathBuf> {
+        let dirs = std::env::var("XDG_CONFIG_DIRS").unwrap_or_default();
+        let dirs = if dirs.is_empty() { "/etc/xdg".to_string() } else { dirs };
+        dirs.split(':')
+            .map(|dir| PathBuf::from(dir).join("autostart/gucli.desktop"))
+            .filter(|path| path.exists())
+            .collect()
+    }
+
+    /// what the session does with the entry: Hidden=true, OnlyShowIn and NotShowIn of another desktop skip it
+    fn starts_here(entry: &str, desktops: &[String]) -> bool {
+        let value = |key: &str| {
+            entry
+                .lines()
+                .find_map(|line| line.strip_prefix(key)?.trim_start().strip_prefix('='))
+                .map(str::trim)
+        };
+        let listed = |list: &str| list.split(';').any(|d| desktops.contains(&d.to_lowercase()));
+        if value("Hidden") == Some("true") || value("X-GNOME-Autostart-enabled") == Some("false") {
+            return false;
+        }
+        // without XDG_CURRENT_DESKTOP the lists cannot be checked
+        if desktops.is_empty() {
+            return true;
+        }
+        value("OnlyShowIn").is_none_or(listed) && !value("NotShowIn").is_some_and(listed)
+    }
+
+    /// keys for the desktops of this session only, the others ignore them anyway
+    fn desktop_keys(desktops: &[String]) -> String {
+        let mut keys = String::new();
+        for desktop in desktops {
+            match desktop.as_str() {
+                "kde" => keys.push_str("X-KDE-autostart-after=panel\n"),
+                "lxqt" => keys.push_str("X-LXQt-Need-Tray=true\n"),
+                // the delay is the sleep in Exec, X-GNOME-Autostart-Delay would add to it
+                "gnome" | "unity" | "budgie" | "pantheon" => keys.push_str("X-GNOME-Autostart-enabled=true\n"),
+                "mate" => keys.push_str("X-MATE-Autostart-enabled=true\n"),
+                _ => {}
+            }
+        }
+        keys
+    }
+
+    /// path the autostart entry or unit starts, None when it has none we wrote
+    fn started_path(entry: &str) -> Option<PathBuf> {
+        let line = entry
+            .lines()
+            .find_map(|line| line.strip_prefix("ExecStart=").or_else(|| line.strip_prefix("Exec=")))?;
+        // `"/path" --hidden`, `sh -c "sleep 5; exec '/path' --hidden"` with a delay, unquoted from older versions
+        let start = line.find('/')?;
+        let rest = &line[start..];
+        let end = match line[..start].chars().last() {
+            Some(quote @ ('"' | '\'')) => rest.find(quote)?,
+            _ => rest.find(' ').unwrap_or(rest.len()),
+        };
+        Some(PathBuf::from(&rest[..end]))
+    }
+
+    /// rewrite an enabled entry whose binary moved, e.g. after an update of the AppImage
+    pub fn repair() {
+        let backend = super::backend();
+        if flatpak::active() || !is_set(&backend).unwrap_or(false) {
+            return;
+        }
+        let Ok(home) = get_home_dir() else {
+            return;
+        };
+        let path = home.join(if backend == "systemd" { UNIT_FILE } else { DESKTOP_FILE });
+        let Ok(entry) = fs::read_to_string(&path) else {
+            return;
+        };
+        if let Some(started) = started_path(&entry)
+            && !started.exists()
+        {
+            info!("Autostart starts {}, which is gone. Rewriting it", started.display());
+            if let Err(e) = set(&backend) {
+                error!("Autostart not rewritten: {}", e);
+            }
+        }
+    }
+
+    pub fn set(backend: &str) -> Result<(), String> {
+        if flatpak::active() {
+            flatpak::request_autostart(true)?;
+            info!("Autostart requested from the Background portal");
+            return Ok(());
+        }
+        let exec = exec_path()?;
+        let delay = settings().autostart_delay.unwrap_or(0);
+        match backend {
+            "systemd" => {
+                let unit_path = get_home_dir()?.join(UNIT_FILE);
+                let unit = format!(
+                    "{}\n\
+                    [Unit]\n\
+                    Description=Gucli - your personal command center in the system tray\n\
+                    After=graphical-session.target\n\
+                    PartOf=graphical-session.target\n\
+                    \n\
+                    [Service]\n\
+                    Type=simple\n\
+                    {}ExecStart={} --hidden\n\
+                    Restart=on-failure\n\
+                    \n\
+                    [Install]\n\
+                    WantedBy=graphical-session.target\n",
+                    UNIT_MARKER,
+                    if delay > 0 { format!("ExecStartPre=/bin/sleep {}\n", delay) } else { String::new() },
+                    crate::units::unit_arg(&exec.to_string_lossy())
+                );
+                write_file(&unit_path, &unit)?;
+                systemctl(&["daemon-reload"])?;
+                systemctl(&["enable", "gucli.service"])?;
+            }
+            _ => {
+                let desktop_file = format!(
+                    "{}{}",
+                    desktop_entry(&desktop_exec(&exec, delay)),
+                    desktop_keys(&current_desktops())
+                );
+                write_file(&get_home_dir()?.join(DESKTOP_FILE), &desktop_file)?;
+            }
+        }
+        info!("Autostart enabled ({}, delay {} s): {}", backend, delay, exec.display());
+        Ok(())
+    }
+
+    /// a plain sleep wrapper, X-GNOME-Autostart-Delay is ignored outside GNOME
+    fn desktop_exec(exec: &std::path::Path, delay: u32) -> String {
+        let exec = exec.to_string_lossy();
+        if delay == 0 {
+            return format!("{} --hidden", exec_arg(&exec));
+        }
+        let script = format!("sleep {}; exec {} --hidden", delay, crate::executor::shell_quote(&exec));
+        format!("sh -c {}", exec_arg(&script))
+    }
+
+    pub fn remove(backend: &str) -> Result<(), String> {
+        if flatpak::active() {
+            flatpak::request_autostart(false)?;
+            info!("Autostart withdrawn from the Background portal");
+            return Ok(());
+        }
+        match backend {
+            "systemd" => {
+                systemctl(&["disable", "gucli.service"])?;
+                // only our own unit, a hand-written one is left alone
+                let unit_path = get_home_dir()?.join(UNIT_FILE);
+                if fs::read_to_string(&unit_path).is_ok_and(|unit| unit.starts_with(UNIT_MARKER)) {
+                    let _ = fs::remove_file(&unit_path);
+                    systemctl(&["daemon-reload"])?;
+                }
+            }
+            _ => {
+                let path = get_home_dir()?.join(DESKTOP_FILE);
+                if system_entries().is_empty() {
+                    let _ = fs::remove_file(path);
+                } else {
+                    // deleting ours would let the packaged entry start it again
+                    write_file(&path, &format!("{}Hidden=true\n", desktop_entry("gucli --hidden")))?;
+                }
+            }
+        }
+        info!("Autostart disabled ({})", backend);
+        Ok(())
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use std::path::Path;
+
+        #[test]
+        fn started_path_of_written_entries() {
+            let entry = desktop_entry(&desktop_exec(Path::new("/opt/My Apps/gucli"), 0));
+            assert_eq!(started_path(&entry), Some(PathBuf::from("/opt/My Apps/gucli")));
+            let entry = desktop_entry(&desktop_exec(Path::new("/opt/My Apps/gucli"), 5));
+            assert_eq!(started_path(&entry), Some(PathBuf::from("/opt/My Apps/gucli")));
+            let unit = format!("[Service]\nExecStart={} --hidden\n", crate::units::unit_arg("/usr/bin/gucli"));
+            assert_eq!(started_path(&unit), Some(PathBuf::from("/usr/bin/gucli")));
+        }
+
+        #[test]
+        fn started_path_of_older_entries() {
+            assert_eq!(started_path("Exec=/usr/bin/gucli --hidden"), Some(PathBuf::from("/usr/bin/gucli")));
+            assert_eq!(started_path("Exec=gucli --hidden"), None);
+            assert_eq!(started_path("[Desktop Entry]\nName=Gucli"), None);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::exec_arg;
+
+    #[test]
+    fn exec_arg_quotes_and_escapes() {
+        assert_eq!(exec_arg("/usr/bin/gucli"), r#""/usr/bin/gucli""#);
+        assert_eq!(exec_arg("/opt/My Apps/gucli"), r#""/opt/My Apps/gucli""#);
+        assert_eq!(exec_arg("50%"), r#""50%%""#);
+        assert_eq!(exec_arg("a\nb"), r#""a b""#);
+    }
+
+    #[test]
+    fn exec_arg_escapes_twice() {
+        // `\$` for the Exec= quoting, then every backslash doubled for the string value
+        assert_eq!(exec_arg("a\"b$c`d"), r#""a\\"b\\$c\\`d""#);
+        assert_eq!(exec_arg(r"a\b"), r#""a\\\\b""#);
+    }
+}
@@ -0,0 +1,240 @@
+use crate::{confirm, send_notification, tooltip};
+use crate::files::format_timestamp;
+use chrono::Local;
+use gucli_shared::RunningProcess;
+use std::process::Child;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::State;
+use tracing::{info, warn};
+
+/// process groups of started commands: `cmd &` leaves jobs running after the shell returns.
+/// A Job Object per command on Windows
+#[derive(Default)]
+pub struct Children(Mutex<Vec<RunningProcess>>);
+
+impl Children {
+    /// a child spawned in its own process group, on Windows it is put in a new job here
+    pub fn add(&self, child: &Child, command: &str) {
+        let mut groups = self.0.lock().unwrap();
+        // forget finished groups, their ids may be reused
+        groups.retain(|p| group::alive(p.pid));
+        if let Err(e) = group::adopt(child) {
+            warn!("`{}` not tracked: {}", command, e);
+            return;
+        }
+        groups.push(RunningProcess {
+            pid: child.id() as i32,
+            command: command.to_string(),
+            started: format_timestamp(&Local::now()),
+        });
+    }
+
+    /// commands with a live process left, finished groups are forgotten
+    pub fn alive(&self) -> Vec<String> {
+        self.list().into_iter().map(|p| p.command).collect()
+    }
+
+    /// live process groups, oldest first
+    pub fn list(&self) -> Vec<RunningProcess> {
+        let mut groups = self.0.lock().unwrap();
+        groups.retain(|p| group::alive(p.pid));
+        groups.clone()
+    }
+
+    /// SIGTERM to one group; only groups started here, the pid comes from the window
+    pub fn kill(&self, pgid: i32) -> Result<(), String> {
+        let mut groups = self.0.lock().unwrap();
+        let index = groups
+            .iter()
+            .position(|p| p.pid == pgid)
+            .ok_or_else(|| format!("Process group {} was not started by gucli", pgid))?;
+        let process = groups.remove(index);
+        info!("Killing `{}` (process group {})", process.command, pgid);
+        if let Err(e) = group::terminate(pgid) {
+            groups.insert(index, process);
+            return Err(format!("Failed to kill process group {}: {}", pgid, e));
+        }
+        Ok(())
+    }
+
+    pub fn kill_all(&self) {
+        for process in self.0.lock().unwrap().drain(..) {
+            info!("Killing `{}` (process group {})", process.command, process.pid);
+            let _ = group::terminate(process.pid);
+        }
+    }
+}
+
+#[tauri::command]
+pub fn get_processes(children: State<'_, Children>) -> Vec<RunningProcess> {
+    children.list()
+}
+
+#[tauri::command]
+pub fn kill_process(pid: i32, children: State<'_, Children>) -> Result<String, String> {
+    children.kill(pid).map(|_| format!("Process group {} killed", pid))
+}
+
+/// "Wait & quit" asks again after this, a hung job must not keep a half-quit app forever
+const QUIT_WAIT: Duration = Duration::from_secs(10 * 60);
+
+/// exit the app, asking first when started commands are still running
+pub fn quit_guarded(app: &tauri::AppHandle, children: &Children) {
+    let running = children.alive();
+    if running.is_empty() {
+        app.exit(0);
+        return;
+    }
+    warn!("Quit requested while running: {}", running.join(", "));
+
+    let answer = confirm::choose(
+        &format!("{} command(s) still running", running.len()),
+        &running.join("\n"),
+        &[("wait", "Wait & quit"), ("kill", "Kill & quit"), ("quit", "Quit anyway")],
+    );
+    match answer.as_ref().map(|a| a.as_deref()) {
+        Ok(Some("wait")) => {
+            if wait_for(app, children) {
+                app.exit(0);
+            } else {
+                warn!("Still running after {} min, asking again", QUIT_WAIT.as_secs() / 60);
+                quit_guarded(app, children);
+            }
+        }
+        Ok(Some("kill")) => {
+            children.kill_all();
+            app.exit(0);
+        }
+        // without a notification server nobody can answer, quit as before
+        Ok(Some(_)) | Err(_) => app.exit(0),
+        // dismissed: keep running
+        Ok(None) => info!("Quit cancelled"),
+    }
+}
+
+/// true when every command ended within QUIT_WAIT; meanwhile the tray tooltip shows how many are left
+fn wait_for(app: &tauri::AppHandle, children: &Children) -> bool {
+    let start = Instant::now();
+    send_notification(
+        "Gucli quits when the running commands end",
+        &format!("Waiting at most {} min, then asking again", QUIT_WAIT.as_secs() / 60),
+    );
+    loop {
+        let running = children.alive().len();
+        let waiting = running > 0 && start.elapsed() < QUIT_WAIT;
+        tooltip::update(app, |status| status.quitting = if waiting { running } else { 0 });
+        if !waiting {
+            return running == 0;
+        }
+        thread::sleep(Duration::from_millis(500));
+    }
+}
+
+#[cfg(unix)]
+mod group {
+    use nix::libc;
+    use std::io;
+    use std::process::Child;
+
+    /// nothing to do, execute_output spawns with process_group(0)
+    pub fn adopt(_child: &Child) -> io::Result<()> {
+        Ok(())
+    }
+
+    pub fn alive(pgid: i32) -> bool {
+        // signal 0 only checks that the group exists
+        unsafe { libc::killpg(pgid, 0) == 0 }
+    }
+
+    /// SIGTERM to the group, already gone is what was asked for
+    pub fn terminate(pgid: i32) -> io::Result<()> {
+        if unsafe { libc::killpg(pgid, libc::SIGTERM) } != 0 {
+            let e = io::Error::last_os_error();
+            if e.raw_os_error() != Some(libc::ESRCH) {
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(windows)]
+mod group {
+    use std::collections::HashMap;
+    use std::io;
+    use std::os::windows::io::AsRawHandle;
+    use std::process::Child;
+    use std::sync::{LazyLock, Mutex};
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows_sys::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, JOBOBJECT_BASIC_ACCOUNTING_INFORMATION,
+        JobObjectBasicAccountingInformation, QueryInformationJobObject, TerminateJobObject,
+    };
+
+    /// job handle, owned here and closed when the job is forgotten
+    struct Job(HANDLE);
+
+    // a kernel handle, usable from any thread
+    unsafe impl Send for Job {}
+
+    impl Drop for Job {
+        fn drop(&mut self) {
+            unsafe { CloseHandle(self.0) };
+        }
+    }
+
+    /// jobs by the pid of the spawned shell, which stands in for the process group id
+    static JOBS: LazyLock<Mutex<HashMap<i32, Job>>> = LazyLock::new(Default::default);
+
+    /// a new job for the child; what it starts from now on joins the job too
+    pub fn adopt(child: &Child) -> io::Result<()> {
+        let job = unsafe { CreateJobObjectW(std::ptr::null(), std::ptr::null()) };
+        if job.is_null() {
+            return Err(io::Error::last_os_error());
+        }
+        let job = Job(job);
+        if unsafe { AssignProcessToJobObject(job.0, child.as_raw_handle() as HANDLE) } == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        JOBS.lock().unwrap().insert(child.id() as i32, job);
+        Ok(())
+    }
+
+    /// some process of the job still runs, a finished job is forgotten
+    pub fn alive(pid: i32) -> bool {
+        let mut jobs = JOBS.lock().unwrap();
+        let Some(job) = jobs.get(&pid) else {
+            return false;
+        };
+        let mut info: JOBOBJECT_BASIC_ACCOUNTING_INFORMATION = unsafe { std::mem::zeroed() };
+        let ok = unsafe {
+            QueryInformationJobObject(
+                job.0,
+                JobObjectBasicAccountingInformation,
+                (&mut info as *mut JOBOBJECT_BASIC_ACCOUNTING_INFORMATION).cast(),
+                size_of::<JOBOBJECT_BASIC_ACCOUNTING_INFORMATION>() as u32,
+                std::ptr::null_mut(),
+            )
+        };
+        if ok != 0 && info.ActiveProcesses > 0 {
+            return true;
+        }
+        jobs.remove(&pid);
+        false
+    }
+
+    /// ends every process of the job; there is no SIGTERM to ask politely with
+    pub fn terminate(pid: i32) -> io::Result<()> {
+        let mut jobs = JOBS.lock().unwrap();
+        let Some(job) = jobs.get(&pid) else {
+            return Ok(());
+        };
+        if unsafe { TerminateJobObject(job.0, 1) } == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        jobs.remove(&pid);
+        Ok(())
+    }
+}
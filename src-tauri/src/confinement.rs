@@ -0,0 +1,105 @@
+use regex::Regex;
+use std::fs;
+use std::process::Command;
+use tracing::debug;
+
+/// explain a permission error caused by SELinux/AppArmor confinement, None if unrelated
+pub fn explain(err: &str) -> Option<String> {
+    if !err.contains("Permission denied") && !err.contains("Operation not permitted") {
+        return None;
+    }
+
+    let selinux = selinux_enforcing();
+    let apparmor = apparmor_enabled();
+    if !selinux && !apparmor {
+        return None;
+    }
+
+    // the most recent denial explains the error best
+    if let Some(line) = recent_denials().last()
+        && let Some(hint) = apparmor_hint(line).or_else(|| selinux_hint(line))
+    {
+        return Some(hint);
+    }
+
+    // journal not readable (user is not in systemd-journal/adm group) or no denial logged
+    let system = match (selinux, apparmor) {
+        (true, true) => "SELinux/AppArmor",
+        (true, false) => "SELinux (enforcing)",
+        _ => "AppArmor",
+    };
+    Some(format!(
+        "{} is active, the denial may come from its policy. Check `journalctl -g 'DENIED|avc'`",
+        system
+    ))
+}
+
+fn selinux_enforcing() -> bool {
+    fs::read_to_string("/sys/fs/selinux/enforce")
+        .map(|s| s.trim() == "1")
+        .unwrap_or(false)
+}
+
+fn apparmor_enabled() -> bool {
+    fs::read_to_string("/sys/module/apparmor/parameters/enabled")
+        .map(|s| s.trim() == "Y")
+        .unwrap_or(false)
+}
+
+/// AVC/apparmor denials logged during the last 30 seconds
+fn recent_denials() -> Vec<String> {
+    let output = Command::new("journalctl")
+        .args(["-q", "--no-pager", "-o", "cat", "--since=-30s"])
+        .arg(r#"--grep=apparmor="DENIED"|avc: +denied"#)
+        .output();
+
+    match output {
+        Ok(output) => String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::to_string)
+            .collect(),
+        Err(e) => {
+            debug!("journalctl unavailable: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+// apparmor="DENIED" operation="open" profile="/usr/bin/foo" name="/etc/shadow" ...
+fn apparmor_hint(line: &str) -> Option<String> {
+    if !line.contains(r#"apparmor="DENIED""#) {
+        return None;
+    }
+    let field = |name: &str| {
+        Regex::new(&format!(r#"\b{}="([^"]*)""#, name))
+            .ok()?
+            .captures(line)
+            .map(|caps| caps[1].to_string())
+    };
+    Some(format!(
+        "AppArmor profile `{}` denied `{}` on `{}`",
+        field("profile").unwrap_or_else(|| "?".into()),
+        field("operation").unwrap_or_else(|| "?".into()),
+        field("name").unwrap_or_else(|| "?".into()),
+    ))
+}
+
+// avc:  denied  { read } for  pid=1 comm="cat" name="shadow" scontext=... tcontext=... tclass=file
+fn selinux_hint(line: &str) -> Option<String> {
+    let re = Regex::new(r"avc:\s+denied\s+\{\s*([^}]*?)\s*\}").ok()?;
+    let caps = re.captures(line)?;
+    let field = |name: &str| {
+        Regex::new(&format!(r#"\b{}="?([^"\s]*)"?"#, name))
+            .ok()?
+            .captures(line)
+            .map(|caps| caps[1].to_string())
+    };
+    Some(format!(
+        "SELinux denied `{}` for `{}` on {} `{}` (target context {})",
+        &caps[1],
+        field("comm").unwrap_or_else(|| "?".into()),
+        field("tclass").unwrap_or_else(|| "object".into()),
+        field("name").unwrap_or_else(|| "?".into()),
+        field("tcontext").unwrap_or_else(|| "?".into()),
+    ))
+}
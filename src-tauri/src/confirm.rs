@@ -0,0 +1,94 @@
+use crate::flatpak;
+use notify_rust::{Notification, Timeout};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+use tracing::{error, info};
+
+/// unanswered confirmations are skipped after this delay
+pub const CONFIRM_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// ask with a "Run now / Skip" notification, blocks until answered or timed out
+pub fn ask(command: &str) -> bool {
+    let answer = choose(
+        &format!("Run `{}`?", command),
+        "Gucli waits for your confirmation",
+        &[("run", "Run now"), ("skip", "Skip")],
+    );
+    let accepted = matches!(answer.as_ref().map(Option::as_deref), Ok(Some("run")));
+    info!(
+        "Command `{}` {} by user",
+        command,
+        if accepted { "confirmed" } else { "skipped" }
+    );
+    accepted
+}
+
+/// two confirmations for a command flagged by the lint, the second one names the reason
+pub fn ask_dangerous(command: &str, reason: &str) -> bool {
+    let first = choose(
+        &format!("Run `{}`?", command),
+        &format!("Looks destructive: {}", reason),
+        &[("run", "Run anyway"), ("skip", "Skip")],
+    );
+    let accepted = matches!(first.as_ref().map(Option::as_deref), Ok(Some("run")))
+        && matches!(
+            choose(
+                &format!("Really run `{}`?", command),
+                &format!("Last chance: {}", reason),
+                &[("run", "Yes, run it"), ("skip", "Skip")],
+            )
+            .as_ref()
+            .map(Option::as_deref),
+            Ok(Some("run"))
+        );
+    info!(
+        "Destructive command `{}` ({}) {} by user",
+        command,
+        reason,
+        if accepted { "confirmed twice" } else { "skipped" }
+    );
+    accepted
+}
+
+/// notification with action buttons, the chosen action id or None when dismissed/timed out
+pub fn choose(summary: &str, body: &str, actions: &[(&str, &str)]) -> Result<Option<String>, String> {
+    // buttons through the portal, the sandbox has no notification server of its own
+    if flatpak::active() {
+        return flatpak::choose(summary, body, actions, CONFIRM_TIMEOUT)
+            .inspect_err(|e| error!("Notification `{}` not shown: {}", summary, e));
+    }
+    let (tx, rx) = mpsc::channel();
+
+    let mut notification = Notification::new();
+    notification
+        .summary(summary)
+        .body(body)
+        .appname("gucli-notification")
+        .icon("dialog-question")
+        .timeout(Timeout::Milliseconds(CONFIRM_TIMEOUT.as_millis() as u32));
+    for (id, label) in actions {
+        notification.action(id, label);
+    }
+
+    match notification.show() {
+        Ok(handle) => {
+            // the answer arrives over D-Bus, wait for it aside so the timeout can win
+            thread::spawn(move || {
+                handle.wait_for_action(|action| {
+                    let _ = tx.send(action.to_string());
+                });
+            });
+        }
+        Err(e) => {
+            error!("Notification `{}` not shown: {}", summary, e);
+            return Err(e.to_string());
+        }
+    }
+
+    // "__closed" is sent when the notification is dismissed
+    Ok(rx
+        .recv_timeout(CONFIRM_TIMEOUT)
+        .ok()
+        .filter(|action| actions.iter().any(|(id, _)| id == action)))
+}
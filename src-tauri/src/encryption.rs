@@ -0,0 +1,103 @@
+use crate::files::{BACKUP_FILE, CACHE_FILE, config_dir, full_path_commands, get_home_dir, make_private, private_file};
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use tracing::info;
+
+/// commands.toml encrypted to the user's own GPG key, used instead of commands.toml when present
+pub const ENCRYPTED_FILE: &str = "commands.toml.gpg";
+
+pub fn encrypted_path() -> Result<PathBuf, String> {
+    Ok(config_dir()?.join(ENCRYPTED_FILE))
+}
+
+pub fn active() -> bool {
+    encrypted_path().is_ok_and(|path| path.exists())
+}
+
+/// plain text of commands.toml.gpg, only in memory. gpg-agent's pinentry asks for the passphrase
+/// and caches it, so reloads and saves within its cache time do not ask again
+pub fn decrypt() -> Result<String, String> {
+    let path = encrypted_path()?;
+    let output = Command::new("gpg")
+        .args(["--quiet", "--decrypt"])
+        .arg(&path)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("gpg not available: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} not decrypted: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    String::from_utf8(output.stdout).map_err(|e| format!("{} is not UTF-8: {}", path.display(), e))
+}
+
+/// write commands.toml.gpg, encrypted to the default key. Needs no passphrase, only the public key
+pub fn encrypt(content: &str) -> Result<(), String> {
+    let path = encrypted_path()?;
+    let partial = path.with_extension("gpg.part");
+    let mut child = Command::new("gpg")
+        .args(["--batch", "--yes", "--quiet", "--encrypt", "--default-recipient-self", "--output"])
+        .arg(&partial)
+        .stdin(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("gpg not available: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(content.as_bytes()).map_err(|e| e.to_string())?;
+    }
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        let _ = fs::remove_file(&partial);
+        return Err(format!("commands.toml not encrypted: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    // never a half-written file in place of the only copy
+    fs::rename(&partial, &path).map_err(|e| e.to_string())
+}
+
+// async commands: pinentry may wait for the passphrase, the main thread must not
+#[tauri::command]
+pub async fn is_commands_encrypted() -> bool {
+    active()
+}
+
+/// switch between commands.toml and commands.toml.gpg; plain copies (backup, cache) go with the plain file
+#[tauri::command]
+pub async fn set_commands_encrypted(on: bool) -> Result<String, String> {
+    let plain = full_path_commands()?;
+    if on == active() {
+        return Ok(format!("commands.toml is {}", if on { "encrypted" } else { "not encrypted" }));
+    }
+    if on {
+        let content = fs::read_to_string(&plain).map_err(|e| e.to_string())?;
+        encrypt(&content)?;
+        // a key without its secret half would lock the commands away
+        if let Err(e) = decrypt() {
+            let _ = fs::remove_file(encrypted_path()?);
+            return Err(e);
+        }
+        let _ = fs::remove_file(&plain);
+        let _ = fs::remove_file(config_dir()?.join(BACKUP_FILE));
+        let _ = fs::remove_file(get_home_dir()?.join(CACHE_FILE));
+        info!("commands.toml encrypted to {}", encrypted_path()?.display());
+        Ok("Ok( commands.toml encrypted )".to_string())
+    } else {
+        let content = decrypt()?;
+        // readable by the user only, it held encrypted content
+        private_file()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&plain)
+            .and_then(|mut file| file.write_all(content.as_bytes()))
+            .map_err(|e| e.to_string())?;
+        make_private(&plain);
+        fs::remove_file(encrypted_path()?).map_err(|e| e.to_string())?;
+        info!("commands.toml decrypted to {}", plain.display());
+        Ok("Ok( commands.toml decrypted )".to_string())
+    }
+}
@@ -0,0 +1,384 @@
+use crate::UserCommand;
+use crate::files::{expand_home, get_home_dir, settings, working_dir};
+use crate::find_in_path;
+use std::path::PathBuf;
+use std::process::{Command, ExitStatus};
+use std::time::Duration;
+
+pub const EXECUTORS: [&str; 3] = ["local", "ssh", "container"];
+
+/// builds the process that runs a user command, supervision (timeout, output) is shared
+pub trait Executor {
+    fn prepare(&self, cmd: &UserCommand) -> Result<Command, String>;
+}
+
+/// ssh exits with this on connection and authentication errors
+const SSH_CONNECTION_ERROR: i32 = 255;
+const SSH_FAILED_PREFIX: &str = "SSH connection to ";
+
+/// executor selected by the `executor` field (empty = local, or ssh when `host` is set)
+pub fn select(cmd: &UserCommand) -> Result<Box<dyn Executor>, String> {
+    match cmd.executor.as_str() {
+        "" if !cmd.host.trim().is_empty() => Ok(Box::new(Ssh {
+            host: cmd.host.clone(),
+            key: cmd.ssh_key.clone(),
+        })),
+        "" | "local" => Ok(Box::new(LocalShell)),
+        "ssh" => Ok(Box::new(Ssh {
+            host: cmd.host.clone(),
+            key: cmd.ssh_key.clone(),
+        })),
+        "container" => Ok(Box::new(Container {
+            name: cmd.container.clone(),
+            engine: ["podman", "docker"]
+                .into_iter()
+                .find_map(find_in_path)
+                .ok_or("Executor `container` requires podman or docker")?,
+        })),
+        other => Err(format!(
+            "Unknown executor `{}`. Available values: {:?}",
+            other, EXECUTORS
+        )),
+    }
+}
+
+/// `shell -c command` on this machine, optionally sandboxed
+pub struct LocalShell;
+
+impl Executor for LocalShell {
+    fn prepare(&self, cmd: &UserCommand) -> Result<Command, String> {
+        let (program, flags) = shell_invocation(cmd);
+        let mut process = if cmd.sandbox {
+            sandboxed(&program)?
+        } else {
+            Command::new(&program)
+        };
+        process.args(flags).arg(&cmd.command);
+        if !cmd.cwd.trim().is_empty() {
+            process.current_dir(working_dir(&cmd.cwd)?);
+        }
+        Ok(process)
+    }
+}
+
+/// `ssh host -- shell -c 'command'`, never prompts: a tray has no terminal for passwords
+pub struct Ssh {
+    pub host: String,
+    /// identity file, ssh config and agent decide when empty
+    pub key: String,
+}
+
+impl Executor for Ssh {
+    fn prepare(&self, cmd: &UserCommand) -> Result<Command, String> {
+        if self.host.trim().is_empty() {
+            return Err("Executor `ssh` requires the `host` field".to_string());
+        }
+        // checked on save too, a hand-edited file may still have it
+        if self.host.trim().starts_with('-') {
+            return Err(format!("Host `{}` cannot start with `-`", self.host));
+        }
+        let mut process = Command::new("ssh");
+        process.args(["-o", "BatchMode=yes", "-o", "ConnectTimeout=5"]);
+        if !self.key.trim().is_empty() {
+            process
+                .arg("-i")
+                .arg(expand_home(&self.key))
+                .args(["-o", "IdentitiesOnly=yes"]);
+        }
+        process
+            .arg(&self.host)
+            .arg("--")
+            .arg(format!(
+                "{} {} {}",
+                cmd.shell,
+                shell_invocation(cmd).1.join(" "),
+                shell_quote(&cmd.command)
+            ));
+        Ok(process)
+    }
+}
+
+/// `podman|docker exec name shell -c command`
+pub struct Container {
+    pub name: String,
+    /// podman or docker, found by `select`
+    pub engine: PathBuf,
+}
+
+impl Executor for Container {
+    fn prepare(&self, cmd: &UserCommand) -> Result<Command, String> {
+        if self.name.trim().is_empty() {
+            return Err("Executor `container` requires the `container` field".to_string());
+        }
+        if self.name.trim().starts_with('-') {
+            return Err(format!("Container `{}` cannot start with `-`", self.name));
+        }
+        let mut process = Command::new(&self.engine);
+        process
+            .arg("exec")
+            .arg(&self.name)
+            .arg(&cmd.shell)
+            .args(shell_invocation(cmd).1)
+            .arg(&cmd.command);
+        Ok(process)
+    }
+}
+
+fn is_ssh(cmd: &UserCommand) -> bool {
+    cmd.executor == "ssh" || (cmd.executor.is_empty() && !cmd.host.trim().is_empty())
+}
+
+/// 500 ms locally; remote commands also need the connection, so they get 10 s
+pub fn timeout(cmd: &UserCommand) -> Duration {
+    if is_ssh(cmd) { Duration::from_secs(10) } else { Duration::from_millis(500) }
+}
+
+/// error text of a failed run, ssh's own connection failures are told apart from the remote command's
+pub fn failure_message(cmd: &UserCommand, status: ExitStatus, stderr: String) -> String {
+    if is_ssh(cmd) && status.code() == Some(SSH_CONNECTION_ERROR) {
+        format!("{}{} failed: {}", SSH_FAILED_PREFIX, cmd.host, stderr.trim())
+    } else {
+        stderr
+    }
+}
+
+/// the host was not reached, the command itself never ran
+pub fn is_connection_error(err: &str) -> bool {
+    err.lines().any(|line| line.starts_with(SSH_FAILED_PREFIX))
+}
+
+/// what a run would launch, nothing is executed. Secrets stay placeholders,
+/// {stdin} / {file} / {dir} are described instead of asked for
+pub fn preview(cmd: &UserCommand) -> Result<String, String> {
+    let mut lines = Vec::new();
+    let mut shown = cmd.clone();
+    shown.command = crate::prompt::strip_marker(&cmd.command);
+    if crate::prompt::wants_stdin(&cmd.command) {
+        lines.push("stdin: text typed into an input dialog".to_string());
+    } else {
+        lines.push("stdin: none".to_string());
+    }
+    if crate::prompt::wants_path(&cmd.command) {
+        lines.push("{file} / {dir}: replaced by the quoted path picked in a chooser".to_string());
+    }
+    if crate::prompt::wants_password(&cmd.command) {
+        lines.push(format!(
+            "{{password:…}}: asked in a masked dialog, passed as ${} in the environment",
+            crate::prompt::PASSWORD_ENV
+        ));
+    }
+    if crate::secrets::wants_secret(&shown.command) {
+        lines.push("{secret:…}: resolved from the keyring right before the run".to_string());
+    }
+
+    if cmd.in_terminal {
+        let terminal = crate::terminal::detect_terminal()?;
+        let (program, flags) = shell_invocation(&shown);
+        lines.insert(0, format!("terminal: {}", terminal));
+        lines.insert(1, format!("runs: {} {} {}", shell_quote(&program), flags.join(" "), shell_quote(&shown.command)));
+    } else {
+        let process = select(&shown)?.prepare(&shown)?;
+        let args: Vec<String> = process
+            .get_args()
+            .map(|arg| shell_quote(&arg.to_string_lossy()))
+            .collect();
+        let executor = match cmd.executor.as_str() {
+            _ if is_ssh(cmd) => "ssh",
+            "" => "local",
+            other => other,
+        };
+        lines.insert(0, format!("executor: {}", executor));
+        lines.insert(1, format!("runs: {} {}", process.get_program().to_string_lossy(), args.join(" ")));
+        lines.insert(2, format!("timeout: {} s", timeout(cmd).as_secs_f64()));
+    }
+    let cwd = if cmd.cwd.trim().is_empty() {
+        "gucli's own".to_string()
+    } else {
+        working_dir(&cmd.cwd)?.display().to_string()
+    };
+    lines.push(format!("working dir: {}", cwd));
+    lines.push("environment: inherited from gucli, unchanged".to_string());
+    Ok(lines.join("\n"))
+}
+
+/// parse-only run of the command by its shell (`-n`), None when fine or not checkable:
+/// custom shells and shells missing on this machine (e.g. only on the ssh host) are skipped
+pub fn syntax_error(shell: &str, command: &str) -> Option<String> {
+    if settings().custom_shell(shell).is_some() {
+        return None;
+    }
+    let program = find_in_path(shell)?;
+    let flags: &[&str] = if shell == "fish" { &["--no-execute", "-c"] } else { &["-n", "-c"] };
+    let mut check = Command::new(program);
+    check.args(flags).arg(crate::prompt::strip_marker(command));
+    let output = crate::flatpak::host(check)
+        .stdin(std::process::Stdio::null())
+        .output()
+        .ok()?;
+    if output.status.success() {
+        return None;
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    Some(if stderr.is_empty() { "syntax error".to_string() } else { stderr })
+}
+
+/// program and flags for the command's shell, custom shells come from [settings]
+pub(crate) fn shell_invocation(cmd: &UserCommand) -> (String, Vec<String>) {
+    if let Some(custom) = settings().custom_shell(&cmd.shell) {
+        return (custom.path.clone(), vec![custom.flag.clone()]);
+    }
+    // `-i -c` sources rc files (aliases, functions) for interactive commands
+    let flags: &[&str] = match cmd.shell.as_str() {
+        "cmd" => &["/C"],
+        // a profile is what `interactive` means for PowerShell
+        "powershell" | "pwsh" if cmd.interactive => &["-Command"],
+        "powershell" | "pwsh" => &["-NoProfile", "-Command"],
+        _ if cmd.interactive => &["-i", "-c"],
+        _ => &["-c"],
+    };
+    (cmd.shell.clone(), flags.iter().map(|f| f.to_string()).collect())
+}
+
+/// single-quote a string for a POSIX shell
+pub fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+/// shells that expand `"$NAME"`, {password:…} and {secret:…} values reach the command that way
+pub fn expands_variables(shell: &str) -> bool {
+    matches!(shell, "sh" | "bash" | "zsh" | "fish" | "dash" | "ksh" | "mksh" | "ash")
+}
+
+/// `"$name"` to append to `before`; inside a quoted string that quote is closed around it,
+/// `echo 'a {secret:x}'` becomes `echo 'a '"$GUCLI_SECRET_1"''`
+pub fn variable_reference(before: &str, name: &str) -> String {
+    let (mut single, mut double, mut escaped) = (false, false, false);
+    for c in before.chars() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if !single => escaped = true,
+            '\'' if !double => single = !single,
+            '"' if !single => double = !double,
+            _ => {}
+        }
+    }
+    let quote = if single { "'" } else if double { "\"" } else { "" };
+    format!("{}\"${}\"{}", quote, name, quote)
+}
+
+/// shell wrapped in bwrap (preferred) or firejail: no network, read-only home, private /tmp
+fn sandboxed(shell: &str) -> Result<Command, String> {
+    if let Some(bwrap) = find_in_path("bwrap") {
+        let mut process = Command::new(bwrap);
+        process
+            .args(["--ro-bind", "/", "/"])
+            .args(["--dev", "/dev"])
+            .args(["--proc", "/proc"])
+            .args(["--tmpfs", "/tmp"])
+            .args(["--unshare-net", "--die-with-parent", "--new-session"])
+            .arg(shell);
+        return Ok(process);
+    }
+    if let Some(firejail) = find_in_path("firejail") {
+        let home = get_home_dir()?;
+        let mut process = Command::new(firejail);
+        process
+            .args(["--quiet", "--net=none", "--private-tmp"])
+            .arg(format!("--read-only={}", home.display()))
+            .arg(shell);
+        return Ok(process);
+    }
+    Err("Sandbox requested, but neither bwrap nor firejail is installed".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// stands in for a real executor where only the argv handling is under test
+    struct Fake;
+
+    impl Executor for Fake {
+        fn prepare(&self, cmd: &UserCommand) -> Result<Command, String> {
+            let mut process = Command::new("fake");
+            process.arg(&cmd.command);
+            Ok(process)
+        }
+    }
+
+    fn command(command: &str) -> UserCommand {
+        UserCommand { shell: "sh".into(), command: command.into(), ..Default::default() }
+    }
+
+    /// program and arguments as they would be spawned
+    fn argv(executor: &dyn Executor, cmd: &UserCommand) -> Result<Vec<String>, String> {
+        let process = executor.prepare(cmd)?;
+        Ok(std::iter::once(process.get_program())
+            .chain(process.get_args())
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect())
+    }
+
+    #[test]
+    fn fake_argv() {
+        assert_eq!(argv(&Fake, &command("uptime")).unwrap(), ["fake", "uptime"]);
+    }
+
+    #[test]
+    fn ssh_argv() {
+        let ssh = Ssh { host: "user@server".into(), key: String::new() };
+        assert_eq!(
+            argv(&ssh, &command("echo 'hi'")).unwrap(),
+            ["ssh", "-o", "BatchMode=yes", "-o", "ConnectTimeout=5", "user@server", "--", r"sh -c 'echo '\''hi'\'''"]
+        );
+    }
+
+    #[test]
+    fn ssh_argv_with_key() {
+        let ssh = Ssh { host: "server".into(), key: "/keys/id".into() };
+        let args = argv(&ssh, &command("uptime")).unwrap();
+        assert_eq!(args[5..9], ["-i", "/keys/id", "-o", "IdentitiesOnly=yes"]);
+        assert_eq!(args[9..], ["server", "--", "sh -c 'uptime'"]);
+    }
+
+    #[test]
+    fn ssh_rejects_option_host() {
+        let ssh = Ssh { host: "-oProxyCommand=touch /tmp/x".into(), key: String::new() };
+        assert!(argv(&ssh, &command("uptime")).is_err());
+        let ssh = Ssh { host: " ".into(), key: String::new() };
+        assert!(argv(&ssh, &command("uptime")).is_err());
+    }
+
+    #[test]
+    fn container_argv() {
+        let container = Container { name: "web".into(), engine: "podman".into() };
+        assert_eq!(argv(&container, &command("ls -l")).unwrap(), ["podman", "exec", "web", "sh", "-c", "ls -l"]);
+    }
+
+    #[test]
+    fn container_rejects_option_name() {
+        let container = Container { name: "--privileged".into(), engine: "docker".into() };
+        assert!(argv(&container, &command("ls")).is_err());
+    }
+
+    #[test]
+    fn variable_reference_closes_the_open_quote() {
+        assert_eq!(variable_reference("echo ", "V"), r#""$V""#);
+        assert_eq!(variable_reference("echo 'a ", "V"), r#"'"$V"'"#);
+        assert_eq!(variable_reference(r#"echo "a "#, "V"), r#"""$V"""#);
+        // an escaped quote or one inside the other kind doesn't open a string
+        assert_eq!(variable_reference(r"echo \' ", "V"), r#""$V""#);
+        assert_eq!(variable_reference(r#"echo "it's "#, "V"), r#"""$V"""#);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn variable_reference_expands_in_sh() {
+        for (before, after) in [("printf %s ", ""), ("printf %s 'a ", " b'"), ("printf %s \"a ", " b\"")] {
+            let command = format!("{}{}{}", before, variable_reference(before, "GUCLI_TEST"), after);
+            let output = Command::new("sh").args(["-c", &command]).env("GUCLI_TEST", "x 'y\" $z").output().unwrap();
+            let expected = if after.is_empty() { "x 'y\" $z".to_string() } else { "a x 'y\" $z b".to_string() };
+            assert_eq!(String::from_utf8_lossy(&output.stdout), expected, "{command}");
+        }
+    }
+}
@@ -1,28 +1,37 @@
+pub use gucli_shared::{AppSettings, CommandsConfig, ConfigProblem, TomlCommand};
+use crate::encryption;
+use chrono::{DateTime, Local, Locale};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::HashSet;
-use std::fs;
+use std::fs::{self, OpenOptions};
 use std::io::{self, Write};
-use std::path::PathBuf;
-use tracing::{error};
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, RwLock};
+use toml_edit::DocumentMut;
+use tracing::{error, warn};
 use tracing_subscriber::fmt::writer::MakeWriter;
 use uuid::Uuid;
 
-pub const COMMANDS_FILE: &str = ".config/gucli/commands.toml";
-pub const LOG_FILE: &str = ".config/gucli/gucli.log";
-
-// Structure for TOML (without ID)
-#[derive(Serialize, Deserialize)]
-pub struct TomlCommand {
-    pub shell: String,
-    pub command: String,
-    pub icon: String,
-    pub sn: bool,
+/// config dir override, ~/.config/gucli (XDG_CONFIG_HOME/gucli) when unset
+pub const CONFIG_DIR_ENV: &str = "GUCLI_CONFIG_DIR";
+// in config_dir()
+pub const COMMANDS_FILE: &str = "commands.toml";
+pub const LOG_FILE: &str = "gucli.log";
+pub const CACHE_FILE: &str = ".cache/gucli/commands.json";
+/// last commands.toml that loaded, offered when the current one is broken
+pub const BACKUP_FILE: &str = "commands.toml.bak";
+
+// [settings] of the last loaded/saved commands.toml
+static SETTINGS: LazyLock<RwLock<AppSettings>> = LazyLock::new(Default::default);
+
+/// current app settings
+pub fn settings() -> AppSettings {
+    SETTINGS.read().unwrap().clone()
 }
 
-// Configuration for TOML
-#[derive(Serialize, Deserialize)]
-pub struct CommandsConfig {
-    pub commands: Vec<TomlCommand>,
+fn set_settings_cache(settings: &AppSettings) {
+    *SETTINGS.write().unwrap() = settings.clone();
 }
 
 pub struct LineLimitedWriter {
@@ -70,15 +79,22 @@ impl Write for LineLimitedFile {
         }
         //content.push_str(&message);
 
-        // Truncating to max_lines
+        // Truncating to max_lines, newest entries are on top
         let lines: Vec<&str> = new_content.lines().collect();
         let truncated = if lines.len() > self.max_lines {
-            lines[lines.len() - self.max_lines..].join("\n")
+            lines[..self.max_lines].join("\n")
         } else {
             new_content
         };
 
-        fs::write(&self.path, truncated)?;
+        // commands and their output end up in here, 0600 like audit.log
+        private_file()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&self.path)?
+            .write_all(truncated.as_bytes())?;
+        make_private(&self.path);
         Ok(buf.len())
     }
 
@@ -87,33 +103,293 @@ impl Write for LineLimitedFile {
     }
 }
 
-/// return linux home dir
+/// timestamp for the log and history: `time_format` from [settings], else the locale's date & time
+pub fn format_timestamp(time: &DateTime<Local>) -> String {
+    use std::fmt::Write;
+    let format = settings().time_format;
+    let mut out = String::new();
+    // an invalid format string fails while writing, fall back to the locale
+    if !format.trim().is_empty() && write!(out, "{}", time.format(&format)).is_ok() {
+        return out;
+    }
+    time.format_localized("%x %X%.3f", system_locale()).to_string()
+}
+
+/// `output_file` path for a run now: strftime placeholders filled in, leading "~/" is the home dir
+pub fn output_path(pattern: &str, time: &DateTime<Local>) -> Result<PathBuf, String> {
+    use std::fmt::Write;
+    let mut path = String::new();
+    write!(path, "{}", time.format(pattern.trim()))
+        .map_err(|_| format!("Invalid strftime placeholder in output_file `{}`", pattern))?;
+    Ok(expand_home(&path))
+}
+
+/// append a run's stdout to its `output_file`
+pub fn write_output(pattern: &str, output: &str) -> Result<PathBuf, String> {
+    let path = output_path(pattern, &Local::now())?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    file.write_all(output.as_bytes())
+        .and_then(|_| if output.ends_with('\n') { Ok(()) } else { file.write_all(b"\n") })
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+/// full panic reports with backtraces in the state dir, gucli.log keeps only its last lines
+pub const CRASH_FILE: &str = "crash.log";
+
+/// whole outputs of notifications that were cut short in the state dir, one file per run
+pub const FULL_OUTPUT_DIR: &str = "output";
+// older files are removed
+const MAX_FULL_OUTPUTS: usize = 50;
+
+/// keep the text behind a truncated notification, <time>-<run id>.txt: a newer run doesn't replace it
+pub fn write_full_output(run_id: u64, text: &str) -> Result<PathBuf, String> {
+    let dir = state_dir()?.join(FULL_OUTPUT_DIR);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    // names start with the time, sorted oldest first
+    let mut files: Vec<PathBuf> = fs::read_dir(&dir)
+        .map(|entries| entries.filter_map(|e| e.ok().map(|e| e.path())).collect())
+        .unwrap_or_default();
+    files.sort();
+    for file in &files[..files.len().saturating_sub(MAX_FULL_OUTPUTS - 1)] {
+        let _ = fs::remove_file(file);
+    }
+    let path = dir.join(format!("{}-{}.txt", Local::now().format("%Y%m%d-%H%M%S"), run_id));
+    private_file()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(text.as_bytes()))
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    make_private(&path);
+    Ok(path)
+}
+
+/// UI language: `language` from [settings], else from the system locale
+pub fn language() -> &'static str {
+    let configured = settings().language;
+    if !configured.trim().is_empty() {
+        return gucli_shared::i18n::normalize(&configured);
+    }
+    let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .unwrap_or_default();
+    gucli_shared::i18n::normalize(&locale)
+}
+
+/// LC_ALL, LC_TIME or LANG without encoding, "de_DE.UTF-8" -> de_DE
+fn system_locale() -> Locale {
+    ["LC_ALL", "LC_TIME", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|value| {
+            let name = value.split(['.', '@']).next().unwrap_or_default().to_string();
+            Locale::try_from(name.as_str()).ok()
+        })
+        .unwrap_or(Locale::POSIX)
+}
+
+/// regular file with any execute bit
+#[cfg(unix)]
+pub fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path)
+        .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+/// file with one of the %PATHEXT% extensions, Windows has no execute bit
+#[cfg(windows)]
+pub fn is_executable(path: &Path) -> bool {
+    let extensions = std::env::var("PATHEXT").unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string());
+    path.is_file()
+        && path.extension().is_some_and(|ext| {
+            extensions
+                .split(';')
+                .any(|known| known.trim_start_matches('.').eq_ignore_ascii_case(&ext.to_string_lossy()))
+        })
+}
+
+/// options for a file only the user can read, 0600. The profile dir keeps it private on Windows
+pub fn private_file() -> OpenOptions {
+    #[cfg(unix)]
+    {
+        let mut options = OpenOptions::new();
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        options
+    }
+    #[cfg(not(unix))]
+    OpenOptions::new()
+}
+
+/// 0600 for a file created before it was private, the mode of `private_file` only applies to new ones
+pub fn make_private(path: &Path) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = fs::set_permissions(path, fs::Permissions::from_mode(0o600));
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+}
+
+/// return linux home dir: $HOME, else the passwd entry (services started with a bare environment)
 pub fn get_home_dir() -> Result<PathBuf, String> {
-    std::env::var("HOME")
-        .map(PathBuf::from)
-        .map_err(|_| "Failed to get $HOME".to_string())
+    dirs::home_dir().ok_or_else(|| "Home dir not found: $HOME is unset and the user has no passwd entry".to_string())
+}
+
+/// where commands.toml and the other gucli files live: $GUCLI_CONFIG_DIR or $XDG_CONFIG_HOME/gucli.
+/// ~/.config/gucli of older versions stays in use until the new dir exists
+pub fn config_dir() -> Result<PathBuf, String> {
+    if let Some(dir) = std::env::var_os(CONFIG_DIR_ENV).filter(|dir| !dir.is_empty()) {
+        return Ok(expand_home(&dir.to_string_lossy()));
+    }
+    let dir = dirs::config_dir()
+        .map(|dir| dir.join("gucli"))
+        .ok_or_else(|| "Config dir not found: neither $XDG_CONFIG_HOME nor $HOME is set".to_string())?;
+    if !dir.exists()
+        && let Ok(home) = get_home_dir()
+        && home.join(".config/gucli").is_dir()
+    {
+        return Ok(home.join(".config/gucli"));
+    }
+    Ok(dir)
+}
+
+/// where crash.log, audit.log and cut outputs live: $XDG_STATE_HOME/gucli, the local data dir on macOS and Windows.
+/// ~/.local/state/gucli of older versions stays in use until the new dir exists
+pub fn state_dir() -> Result<PathBuf, String> {
+    let old = get_home_dir()?.join(".local/state/gucli");
+    let dir = dirs::state_dir()
+        .or_else(dirs::data_local_dir)
+        .map(|dir| dir.join("gucli"))
+        .unwrap_or_else(|| old.clone());
+    if !dir.exists() && old.is_dir() {
+        return Ok(old);
+    }
+    Ok(dir)
+}
+
+/// path from a config field, leading "~/" is the home dir
+pub fn expand_home(path: &str) -> PathBuf {
+    match (path.trim().strip_prefix("~"), get_home_dir()) {
+        (Some(rest), Ok(home)) => home.join(rest.trim_start_matches('/')),
+        _ => PathBuf::from(path.trim()),
+    }
+}
+
+/// existing directory from a `cwd` field, leading "~/" is the home dir
+pub fn working_dir(cwd: &str) -> Result<PathBuf, String> {
+    let path = expand_home(cwd);
+    if path.is_dir() {
+        Ok(path)
+    } else {
+        Err(format!("Working directory `{}` does not exist", path.display()))
+    }
 }
 
 /// return full path COMMANDS_FILE
-pub fn full_path_commands() -> PathBuf {
-    get_home_dir()
-        .expect("Home dir not found")
-        .join(COMMANDS_FILE)
+pub fn full_path_commands() -> Result<PathBuf, String> {
+    Ok(config_dir()?.join(COMMANDS_FILE))
 }
 
-/// return full path LOG_FILE
-pub fn full_path_log() -> PathBuf {
-    get_home_dir().expect("Home dir not found").join(LOG_FILE)
+/// text of commands.toml, from commands.toml.gpg when that one exists
+pub fn read_config() -> Result<String, String> {
+    if encryption::active() {
+        return encryption::decrypt();
+    }
+    fs::read_to_string(full_path_commands()?).map_err(|e| e.to_string())
+}
+
+/// replace commands.toml, encrypted again when it is
+pub fn write_config(content: &str) -> Result<(), String> {
+    if encryption::active() {
+        return encryption::encrypt(content);
+    }
+    fs::write(full_path_commands()?, content).map_err(|e| e.to_string())
+}
+
+/// lines kept in the log file when `log_max_lines` is not set
+pub const DEFAULT_LOG_LINES: usize = 100;
+
+/// return full path of the log file: `log_file` from [settings] or LOG_FILE
+pub fn full_path_log() -> Result<PathBuf, String> {
+    log_path(&settings())
+}
+
+/// log file for these settings, main() calls it before the settings cache is filled
+pub fn log_path(settings: &AppSettings) -> Result<PathBuf, String> {
+    if settings.log_file.trim().is_empty() {
+        Ok(config_dir()?.join(LOG_FILE))
+    } else {
+        Ok(expand_home(&settings.log_file))
+    }
+}
+
+/// one command run recorded in gucli.log
+pub struct LoggedRun {
+    pub time: String,
+    pub command: String,
+    pub success: bool,
+    /// missing in lines written by older versions
+    pub duration_ms: Option<u64>,
+}
+
+/// command runs found in gucli.log, newest first
+pub fn read_logged_runs() -> Vec<LoggedRun> {
+    let Ok(content) = full_path_log().and_then(|path| fs::read_to_string(path).map_err(|e| e.to_string())) else {
+        return Vec::new();
+    };
+    // <timestamp in any format>  INFO Command `id` executed in 12 ms, Result: ...
+    let line_regex = regex::Regex::new(
+        r"^(.*?)\s+(?:TRACE|DEBUG|INFO|WARN|ERROR)\s+Command `(.+?)` (executed|failed)(?: in (\d+) ms)?",
+    )
+    .unwrap();
+    content
+        .lines()
+        .filter_map(|line| line_regex.captures(line))
+        .map(|caps| LoggedRun {
+            time: caps[1].to_string(),
+            command: caps[2].to_string(),
+            success: &caps[3] == "executed",
+            duration_ms: caps.get(4).and_then(|m| m.as_str().parse().ok()),
+        })
+        .collect()
+}
+
+/// [settings] straight from commands.toml, before logging is set up; defaults on any error
+pub fn read_settings() -> AppSettings {
+    #[derive(Deserialize)]
+    struct SettingsOnly {
+        #[serde(default)]
+        settings: AppSettings,
+    }
+    read_config()
+        .ok()
+        .and_then(|content| toml::from_str::<SettingsOnly>(&content).ok())
+        .map(|config| config.settings)
+        .unwrap_or_default()
 }
 
 /// set commands.toml on install app, check on run & reset
 pub fn set_config(reset: Option<bool>) -> io::Result<String> {
     let reset = reset.unwrap_or(false);
-    let commands_path = full_path_commands();
+    let commands_path = full_path_commands().map_err(io::Error::other)?;
 
-    if !commands_path.exists() || reset {
+    if (!commands_path.exists() && !encryption::active()) || reset {
         fs::create_dir_all(commands_path.parent().unwrap())?;
-        fs::write(&commands_path, COMMENT.to_string() + EXAMPLE_COMMANDS)?;
+        write_config(&(COMMENT.to_string() + EXAMPLE_COMMANDS)).map_err(io::Error::other)?;
 
         Ok("File commands.toml created".to_string())
     } else {
@@ -123,96 +399,601 @@ pub fn set_config(reset: Option<bool>) -> io::Result<String> {
 
 /// read commands.toml + add id
 pub fn load_commands() -> Result<crate::AppCommandsConfig, Box<dyn std::error::Error>> {
-    let content = fs::read_to_string(full_path_commands())?;
-    let toml_config: CommandsConfig = toml::from_str(&content)
+    let content = read_config()?;
+    let hash = source_hash(&content);
+    // the backup and the cache are plain text, an encrypted file goes without them
+    let plain = !encryption::active();
+    let (toml_config, fixes) = match read_cache(&hash).filter(|_| plain) {
+        // parsed before, but shells may have been uninstalled since
+        Some(config) => {
+            validate(&config)?;
+            (config, Vec::new())
+        }
+        None => {
+            let (config, fixes) = parse_commands(&content)?;
+            if plain {
+                write_backup(&content);
+            }
+            // repaired configs are parsed again, so the fixes are reported until saved
+            if plain && fixes.is_empty() {
+                write_cache(&hash, &config);
+            }
+            (config, fixes)
+        }
+    };
+
+    let commands_with_id = toml_config
+        .commands
+        .into_iter()
+        .map(|toml_cmd| toml_cmd.with_id(Uuid::new_v4().to_string()))
+        .collect();
+
+    set_settings_cache(&toml_config.settings);
+    Ok(crate::AppCommandsConfig {
+        settings: toml_config.settings,
+        commands: commands_with_id,
+        fixes,
+    })
+}
+
+/// parse, repair and validate commands.toml content
+fn parse_commands(content: &str) -> Result<(CommandsConfig, Vec<String>), Box<dyn std::error::Error>> {
+    let mut table: toml::Table = toml::from_str(content)
         .map_err(|e| {
             error!("TOML parsing error: {}", e);
             format!("Invalid TOML syntax: {}", e)
         })?;
+    let fixes = migration::migrate(&mut table);
+    let toml_config: CommandsConfig = toml::Value::Table(table)
+        .try_into()
+        .map_err(|e| {
+            error!("TOML schema error: {}", e);
+            format!("Invalid commands.toml: {}", e)
+        })?;
+    validate(&toml_config)?;
+    Ok((toml_config, fixes))
+}
+
+/// checks of parsed content, also for a cached parse: they depend on the installed shells and files
+fn validate(toml_config: &CommandsConfig) -> Result<(), Box<dyn std::error::Error>> {
+    // custom shells must point to an executable
+    for shell in &toml_config.settings.shells {
+        if !is_executable(Path::new(&shell.path)) {
+            error!("Shell '{}' path '{}' is not an executable file", shell.name, shell.path);
+            return Err(format!("Shell `{}`: `{}` is not an executable file", shell.name, shell.path).into());
+        }
+    }
+    let valid_shells = valid_shells(&toml_config.settings);
+
+    let left_click = toml_config.settings.left_click.as_str();
+    if !left_click.is_empty() && !gucli_shared::LEFT_CLICK_ACTIONS.contains(&left_click) {
+        error!("Invalid left_click '{}'", left_click);
+        return Err(format!("Invalid left_click. Available values: {:?}", gucli_shared::LEFT_CLICK_ACTIONS).into());
+    }
+    let log_target = toml_config.settings.log_target.as_str();
+    if !log_target.is_empty() && !gucli_shared::LOG_TARGETS.contains(&log_target) {
+        error!("Invalid log_target '{}'", log_target);
+        return Err(format!("Invalid log_target. Available values: {:?}", gucli_shared::LOG_TARGETS).into());
+    }
+    let log_level = toml_config.settings.log_level.as_str();
+    if !log_level.is_empty() && !gucli_shared::LOG_LEVELS.contains(&log_level) {
+        error!("Invalid log_level '{}'", log_level);
+        return Err(format!("Invalid log_level. Available values: {:?}", gucli_shared::LOG_LEVELS).into());
+    }
+    let autostart = toml_config.settings.autostart.as_str();
+    if !autostart.is_empty() && !gucli_shared::AUTOSTART_BACKENDS.contains(&autostart) {
+        error!("Invalid autostart '{}'", autostart);
+        return Err(format!("Invalid autostart. Available values: {:?}", gucli_shared::AUTOSTART_BACKENDS).into());
+    }
+    if let Some(problem) = command_problems(&toml_config.commands, &valid_shells).into_iter().next() {
+        error!("Command at index {}, field `{}`: {}", problem.row, problem.field, problem.message);
+        return Err(format!("Command {}: {}", problem.row + 1, problem.message).into());
+    }
+    Ok(())
+}
+
+/// SHELLS and the custom shells of [settings]
+pub fn valid_shells(settings: &AppSettings) -> Vec<&str> {
+    let mut shells: Vec<&str> = gucli_shared::SHELLS.to_vec();
+    shells.extend(settings.shells.iter().map(|s| s.name.as_str()));
+    shells
+}
 
+/// every problem of every command, parse_commands stops at the first one
+pub fn command_problems(commands: &[TomlCommand], valid_shells: &[&str]) -> Vec<ConfigProblem> {
+    let mut problems = Vec::new();
     let mut unique_commands = HashSet::new();
+    let mut has_default = false;
+
+    for (index, cmd) in commands.iter().enumerate() {
+        let mut problem = |field: &str, message: String| problems.push(ConfigProblem::new(index, field, message));
 
-    for (index, cmd) in toml_config.commands.iter().enumerate() {
-        // check empty command
         if cmd.command.trim().is_empty() {
-            error!("Command at index {} is empty", index);
-            return Err("Command cannot be empty".into());
+            problem("command", "Command cannot be empty".into());
+        } else if !unique_commands.insert(&cmd.command) {
+            problem("command", "Command is not unique".into());
+        }
+        if cmd.default && std::mem::replace(&mut has_default, true) {
+            problem("default", "Only one command can be the default".into());
         }
 
-        // check unique command
-        if !unique_commands.insert(&cmd.command) {
-            error!("Command '{}' at index {} is not unique", cmd.command, index);
-            return Err("Command is not unique".into());
+        // image icon: existing PNG/SVG file, text icon: <= 8 chars
+        if crate::icons::is_image(&cmd.icon) {
+            if let Err(e) = crate::icons::validate(&cmd.icon) {
+                problem("icon", e);
+            }
+        } else if cmd.icon.chars().count() > 8 {
+            problem("icon", "Icon exceeds 8 characters limit".into());
         }
 
-        // check len icon (<= 8 char))
-        if cmd.icon.chars().count() > 8 {
-            error!(
-                "Icon '{}' at index {} exceeds 8 characters limit",
-                cmd.icon, index
-            );
-            return Err("Icon exceeds 8 characters limit".into());
+        if !cmd.output_file.trim().is_empty()
+            && let Err(e) = output_path(&cmd.output_file, &Local::now())
+        {
+            problem("output_file", e);
+        }
+        if !cmd.on_busy.is_empty() && !gucli_shared::ON_BUSY_POLICIES.contains(&cmd.on_busy.as_str()) {
+            problem("on_busy", format!("Invalid on_busy. Available values: {:?}", gucli_shared::ON_BUSY_POLICIES));
+        }
+        if cmd.mnemonic.trim().chars().count() > 1 {
+            problem("mnemonic", "Mnemonic must be a single character".into());
+        }
+
+        // health checks run unattended through execute_command: nobody confirms, answers a dialog or sees a terminal
+        if cmd.health_interval.is_some() {
+            let conflict = if cmd.confirm {
+                Some("confirm".to_string())
+            } else if cmd.in_terminal {
+                Some("in_terminal".to_string())
+            } else if crate::prompt::wants_password(&cmd.command) {
+                Some("{password:…}".to_string())
+            } else if crate::prompt::wants_stdin(&cmd.command) || crate::prompt::wants_path(&cmd.command) {
+                Some("{stdin}, {file} or {dir}".to_string())
+            } else {
+                gucli_shared::lint::dangerous(&cmd.command).map(|reason| format!("a destructive command ({})", reason))
+            };
+            if let Some(conflict) = conflict {
+                problem("health_interval", format!("health_interval cannot be combined with {}", conflict));
+            }
+        }
+
+        let executor = if cmd.executor.is_empty() { "local" } else { cmd.executor.as_str() };
+        if !crate::executor::EXECUTORS.contains(&executor) {
+            problem("executor", format!("Invalid executor. Available values: {:?}", crate::executor::EXECUTORS));
+        } else if (executor == "ssh" && cmd.host.trim().is_empty())
+            || (executor == "container" && cmd.container.trim().is_empty())
+        {
+            let field = if executor == "ssh" { "host" } else { "container" };
+            problem(field, format!("Executor `{}` requires the `{}` field", executor, field));
+        }
+        // ssh and podman take them as the next argument, `-oProxyCommand=…` would be an option
+        if cmd.host.trim().starts_with('-') {
+            problem("host", "Host cannot start with `-`, ssh would read it as an option".into());
+        }
+        if cmd.container.trim().starts_with('-') {
+            problem("container", "Container cannot start with `-`, podman/docker would read it as an option".into());
+        }
+        // the value is in the local ssh/podman process only, the remote side would expand it empty
+        if crate::secrets::wants_secret(&cmd.command) && (executor != "local" || !cmd.host.trim().is_empty()) {
+            problem("command", "{secret:…} works only for local commands, not over ssh or in a container".into());
         }
 
-        // validate shell field
-        let valid_shells = ["sh", "bash", "zsh", "fish"];
         if !valid_shells.contains(&cmd.shell.as_str()) {
-            error!(
-                "Invalid shell '{}' at index {}. Available values: {:?}",
-                cmd.shell, index, valid_shells
+            problem("shell", format!("Invalid shell. Available values: {:?}", valid_shells));
+        } else if (crate::prompt::wants_password(&cmd.command) || crate::secrets::wants_secret(&cmd.command))
+            && !crate::executor::expands_variables(&cmd.shell)
+        {
+            problem(
+                "shell",
+                format!("{{password:…}} and {{secret:…}} need a POSIX shell or fish, `{}` doesn't expand \"$VAR\"", cmd.shell),
             );
-            return Err(format!("Invalid shell. Available values: {:?}", valid_shells).into());
         }
     }
+    problems
+}
 
-    let commands_with_id = toml_config
-        .commands
-        .into_iter()
-        .map(|toml_cmd| crate::UserCommand {
-            id: Uuid::new_v4().to_string(),
-            shell: toml_cmd.shell,
-            command: toml_cmd.command,
-            icon: toml_cmd.icon,
-            sn: toml_cmd.sn,
+/// `sh -n` of every command as warnings: one shell run per command, so only for the settings window,
+/// and a command the local shell can't parse may still be right for its ssh host or container
+pub fn syntax_problems(commands: &[TomlCommand]) -> Vec<ConfigProblem> {
+    commands
+        .iter()
+        .enumerate()
+        .filter(|(_, cmd)| !cmd.command.trim().is_empty())
+        .filter_map(|(index, cmd)| {
+            let e = crate::executor::syntax_error(&cmd.shell, &cmd.command)?;
+            Some(ConfigProblem {
+                line: error_line(&e),
+                warning: true,
+                ..ConfigProblem::new(index, "command", format!("Syntax error: {}", e))
+            })
         })
-        .collect();
+        .collect()
+}
 
-    Ok(crate::AppCommandsConfig {
-        commands: commands_with_id,
-    })
+// "bash: -c: line 2: ...", "sh: 2: ...", "fish: ... (line 2)"
+fn error_line(stderr: &str) -> Option<usize> {
+    static LINE: LazyLock<regex::Regex> =
+        LazyLock::new(|| regex::Regex::new(r"(?:line |^\w+: )(\d+)").unwrap());
+    LINE.captures(stderr)?.get(1)?.as_str().parse().ok()
+}
+
+// best effort like the cache, only valid files get here
+fn write_backup(content: &str) {
+    let result =
+        config_dir().and_then(|dir| fs::write(dir.join(BACKUP_FILE), content).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        warn!("Backup of commands.toml not written: {}", e);
+    }
+}
+
+/// replace commands.toml with the backup, if that one still loads
+pub fn restore_backup() -> Result<String, String> {
+    let backup = config_dir()?.join(BACKUP_FILE);
+    let content = fs::read_to_string(&backup).map_err(|_| "No backup of commands.toml".to_string())?;
+    parse_commands(&content).map_err(|e| format!("Backup is invalid too: {}", e))?;
+    write_config(&content)?;
+    Ok("commands.toml restored from the backup".to_string())
+}
+
+/// validated commands.toml, valid while the hash of the source matches
+#[derive(Serialize, Deserialize)]
+struct ConfigCache {
+    hash: String,
+    config: CommandsConfig,
+}
+
+// app version is hashed too: validation rules change between releases.
+// SHA-256, DefaultHasher may change with the Rust release that builds gucli
+fn source_hash(content: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(env!("CARGO_PKG_VERSION"));
+    hasher.update([0]);
+    hasher.update(content);
+    format!("{:x}", hasher.finalize())
+}
+
+fn read_cache(hash: &str) -> Option<CommandsConfig> {
+    let path = get_home_dir().ok()?.join(CACHE_FILE);
+    let cache: ConfigCache = serde_json::from_slice(&fs::read(path).ok()?).ok()?;
+    (cache.hash == hash).then_some(cache.config)
+}
+
+// best effort, a missing cache only costs a full parse
+fn write_cache(hash: &str, config: &CommandsConfig) {
+    let Ok(path) = get_home_dir().map(|home| home.join(CACHE_FILE)) else {
+        return;
+    };
+    let cache = ConfigCache {
+        hash: hash.to_string(),
+        config: config.clone(),
+    };
+    let result = fs::create_dir_all(path.parent().unwrap())
+        .map_err(|e| e.to_string())
+        .and_then(|_| serde_json::to_vec(&cache).map_err(|e| e.to_string()))
+        .and_then(|json| fs::write(&path, json).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        warn!("Config cache not written: {}", e);
+    }
+}
+
+/// upgrades of older commands.toml layouts, applied on the parsed table before validation
+mod migration {
+    use toml::{Table, Value};
+    use tracing::{info, warn};
+
+    /// layout written by this version, bump it with a new step in STEPS
+    pub const SCHEMA_VERSION: u32 = 1;
+
+    /// STEPS[n] upgrades version n to n + 1
+    const STEPS: [fn(&mut Table, &mut Vec<String>); SCHEMA_VERSION as usize] = [v0_to_v1];
+
+    /// upgrade to SCHEMA_VERSION and repair hand edits, returns what was fixed
+    pub fn migrate(table: &mut Table) -> Vec<String> {
+        let mut fixes = Vec::new();
+        let version = table
+            .get("version")
+            .and_then(Value::as_integer)
+            .and_then(|v| u32::try_from(v).ok())
+            .unwrap_or(0);
+
+        if version > SCHEMA_VERSION {
+            warn!("commands.toml: version {} is newer than {}, read as is", version, SCHEMA_VERSION);
+        } else {
+            for (step, upgrade) in STEPS.iter().enumerate().skip(version as usize) {
+                let before = fixes.len();
+                upgrade(table, &mut fixes);
+                info!("commands.toml: migrated version {} to {}", step, step + 1);
+                for fix in &fixes[before..] {
+                    info!("commands.toml: {}", fix);
+                }
+            }
+            // only the stamp, not a fix to tell about: it is written with the next save
+            if version < SCHEMA_VERSION {
+                table.insert("version".into(), Value::Integer(SCHEMA_VERSION.into()));
+            }
+        }
+
+        let before = fixes.len();
+        repair(table, &mut fixes);
+        for fix in &fixes[before..] {
+            warn!("commands.toml: {}", fix);
+        }
+        fixes
+    }
+
+    /// pre-versioning layout: numeric ids
+    fn v0_to_v1(table: &mut Table, fixes: &mut Vec<String>) {
+        for (index, cmd) in commands(table) {
+            // ids are generated on every load (uuid), numeric ids of old versions mean nothing
+            if cmd.remove("id").is_some() {
+                fixes.push(format!("command {}: legacy `id` removed", index));
+            }
+        }
+    }
+
+    /// mistakes of hand-edited configs, whatever their version
+    fn repair(table: &mut Table, fixes: &mut Vec<String>) {
+        if !table.get("commands").is_some_and(Value::is_array) {
+            table.insert("commands".into(), Value::Array(Vec::new()));
+            fixes.push("missing [[commands]], starting with an empty list".to_string());
+        }
+        for (index, cmd) in commands(table) {
+            match cmd.get("shell") {
+                Some(Value::String(_)) => {}
+                Some(other) => {
+                    fixes.push(format!("command {}: `shell` is {} `{}`, not a string, set to \"sh\"", index, other.type_str(), other));
+                    cmd.insert("shell".into(), Value::String("sh".into()));
+                }
+                None => {
+                    cmd.insert("shell".into(), Value::String("sh".into()));
+                    fixes.push(format!("command {}: missing `shell`, set to \"sh\"", index));
+                }
+            }
+            if !cmd.contains_key("icon") {
+                cmd.insert("icon".into(), Value::String(String::new()));
+                fixes.push(format!("command {}: missing `icon`, set to empty", index));
+            }
+            if !cmd.contains_key("sn") {
+                cmd.insert("sn".into(), Value::Boolean(true));
+                fixes.push(format!("command {}: missing `sn`, set to true", index));
+            }
+            // "Bash " -> "bash", custom shell names are left as they are
+            if let Some(shell) = cmd.get("shell").and_then(Value::as_str)
+                && shell != shell.trim().to_lowercase()
+                && gucli_shared::SHELLS.contains(&shell.trim().to_lowercase().as_str())
+            {
+                let normalized = shell.trim().to_lowercase();
+                fixes.push(format!("command {}: shell `{}` normalized to `{}`", index, shell, normalized));
+                cmd.insert("shell".into(), Value::String(normalized));
+            }
+            // sn = "true" (quoted) was accepted by hand-edited configs
+            if let Some(Value::String(s)) = cmd.get("sn") {
+                let sn = s.trim() != "false";
+                fixes.push(format!("command {}: `sn` set to {}", index, sn));
+                cmd.insert("sn".into(), Value::Boolean(sn));
+            }
+        }
+    }
+
+    fn commands(table: &mut Table) -> impl Iterator<Item = (usize, &mut Table)> {
+        table
+            .get_mut("commands")
+            .and_then(Value::as_array_mut)
+            .into_iter()
+            .flatten()
+            .enumerate()
+            .filter_map(|(index, entry)| entry.as_table_mut().map(|cmd| (index, cmd)))
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn table(text: &str) -> Table {
+            toml::from_str(text).unwrap()
+        }
+
+        #[test]
+        fn upgrades_v0_and_stamps_the_version() {
+            let mut config = table("[[commands]]\nid = 3\nshell = \"sh\"\ncommand = \"ls\"\nicon = \"\"\nsn = true\n");
+            let fixes = migrate(&mut config);
+            assert_eq!(fixes, ["command 0: legacy `id` removed"]);
+            assert_eq!(config["version"].as_integer(), Some(SCHEMA_VERSION.into()));
+            assert!(!config["commands"][0].as_table().unwrap().contains_key("id"));
+        }
+
+        #[test]
+        fn current_version_needs_no_fixes() {
+            let mut config = table("version = 1\n[[commands]]\nshell = \"bash\"\ncommand = \"ls\"\nicon = \"\"\nsn = false\n");
+            let before = config.clone();
+            assert!(migrate(&mut config).is_empty());
+            assert_eq!(config, before);
+        }
+
+        #[test]
+        fn repairs_hand_edits() {
+            let mut config = table("version = 1\n[[commands]]\ncommand = \"ls\"\n[[commands]]\nshell = \"Bash \"\ncommand = \"ls\"\nicon = \"\"\nsn = \"false\"\n");
+            let fixes = migrate(&mut config);
+            assert_eq!(
+                fixes,
+                [
+                    "command 0: missing `shell`, set to \"sh\"",
+                    "command 0: missing `icon`, set to empty",
+                    "command 0: missing `sn`, set to true",
+                    "command 1: shell `Bash ` normalized to `bash`",
+                    "command 1: `sn` set to false",
+                ]
+            );
+            assert_eq!(config["commands"][1]["shell"].as_str(), Some("bash"));
+            assert_eq!(config["commands"][1]["sn"].as_bool(), Some(false));
+        }
+
+        #[test]
+        fn reports_the_type_of_a_bad_shell() {
+            let mut config = table("version = 1\n[[commands]]\nshell = 5\ncommand = \"ls\"\nicon = \"\"\nsn = true\n");
+            assert_eq!(migrate(&mut config), ["command 0: `shell` is integer `5`, not a string, set to \"sh\""]);
+            assert_eq!(config["commands"][0]["shell"].as_str(), Some("sh"));
+        }
+
+        #[test]
+        fn missing_commands_become_an_empty_list() {
+            let mut config = table("version = 1\n");
+            assert_eq!(migrate(&mut config), ["missing [[commands]], starting with an empty list"]);
+            assert_eq!(config["commands"].as_array().map(Vec::len), Some(0));
+        }
+
+        #[test]
+        fn newer_versions_are_read_as_is() {
+            let mut config = table("version = 99\n[[commands]]\nid = 3\nshell = \"sh\"\ncommand = \"ls\"\nicon = \"\"\nsn = true\n");
+            assert!(migrate(&mut config).is_empty());
+            assert_eq!(config["version"].as_integer(), Some(99));
+        }
+    }
 }
 
 /// write commands.toml + remove id
 pub fn save_commands(config: &crate::AppCommandsConfig) -> Result<(), Box<dyn std::error::Error>> {
-    let toml_commands: Vec<TomlCommand> = config
-        .commands
-        .iter()
-        .map(|cmd| TomlCommand {
-            shell: cmd.shell.clone(),
-            command: cmd.command.clone(),
-            icon: cmd.icon.clone(),
-            sn: cmd.sn,
-        })
-        .collect();
+    let rendered = render_commands(config)?;
+    let content = match read_config() {
+        Ok(old) => with_comments(&old, &rendered),
+        Err(_) => rendered,
+    };
+    write_config(&content)?;
+    set_settings_cache(&config.settings);
+    Ok(())
+}
+
+/// rendered content with the comments of the old file, rendered as is when the old one is not TOML
+fn with_comments(old: &str, rendered: &str) -> String {
+    let (Ok(old_doc), Ok(mut doc)) = (old.parse::<DocumentMut>(), rendered.parse::<DocumentMut>()) else {
+        return rendered.to_string();
+    };
+    // leading comment block: the header of the version that wrote the file, replaced by the current one
+    let header: String = old.lines().take_while(|line| line.starts_with('#')).map(|line| format!("{}\n", line)).collect();
+    keep_comments(old_doc.as_table(), doc.as_table_mut(), &header);
+    doc.set_trailing(old_doc.trailing().as_str().unwrap_or_default());
+    doc.to_string()
+}
+
+/// copy comments and blank lines around tables, keys and values that are in both tables
+fn keep_comments(old: &toml_edit::Table, new: &mut toml_edit::Table, header: &str) {
+    use toml_edit::Item;
+    copy_decor(old.decor(), new.decor_mut(), header);
+    for (mut key, item) in new.iter_mut() {
+        let (Some(old_key), Some(old_item)) = (old.key(key.get()), old.get(key.get())) else {
+            continue;
+        };
+        copy_decor(old_key.leaf_decor(), key.leaf_decor_mut(), header);
+        match (old_item, item) {
+            (Item::Value(old_value), Item::Value(value)) => copy_decor(old_value.decor(), value.decor_mut(), header),
+            (Item::Table(old_table), Item::Table(table)) => keep_comments(old_table, table, header),
+            (Item::ArrayOfTables(old_tables), Item::ArrayOfTables(tables)) => {
+                let by_command = key.get() == "commands";
+                for (index, table) in tables.iter_mut().enumerate() {
+                    // commands are reordered and removed in the UI, `command` is unique
+                    let old_table = if by_command {
+                        let command = table.get("command").and_then(Item::as_str).map(str::to_string);
+                        old_tables
+                            .iter()
+                            .find(|old| old.get("command").and_then(Item::as_str) == command.as_deref())
+                    } else {
+                        old_tables.get(index)
+                    };
+                    if let Some(old_table) = old_table {
+                        keep_comments(old_table, table, header);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn copy_decor(old: &toml_edit::Decor, new: &mut toml_edit::Decor, header: &str) {
+    let prefix = old.prefix().and_then(|p| p.as_str()).unwrap_or_default();
+    match prefix.strip_prefix(header) {
+        Some(rest) if !header.is_empty() => {
+            let current = new.prefix().and_then(|p| p.as_str()).unwrap_or_default();
+            new.set_prefix(format!("{}{}", current, rest));
+        }
+        _ => new.set_prefix(prefix),
+    }
+    if let Some(suffix) = old.suffix().and_then(|s| s.as_str()) {
+        new.set_suffix(suffix);
+    }
+}
+
+/// commands.toml content for config (without id)
+pub fn render_commands(config: &crate::AppCommandsConfig) -> Result<String, Box<dyn std::error::Error>> {
+    let toml_commands: Vec<TomlCommand> = config.commands.iter().map(TomlCommand::from).collect();
 
     let toml_config = CommandsConfig {
+        version: migration::SCHEMA_VERSION,
+        settings: config.settings.clone(),
         commands: toml_commands,
     };
-    let _ = fs::write(
-        full_path_commands(),
-        COMMENT.to_string() + &toml::to_string(&toml_config)?,
-    );
-    Ok(())
+    Ok(COMMENT.to_string() + &toml::to_string(&toml_config)?)
 }
 
-static COMMENT: &str = r#"# The application requires at least one command to function.
+static COMMENT: &str = r##"# The application requires at least one command to function.
+# Comments you add next to commands and settings are kept when the app saves this file.
 # Please follow the field structure:
+# version - integer, layout of this file, written by the app. Files of older versions are upgraded on load
 # [[commands]] - defines one element in the commands collection. Required for each command.
 # shell - string (default: "sh"), available values: [sh, bash, zsh, fish]. Required when using shell aliases or functions
 # command - string (unique), can include arguments and shell-specific syntax
-# icon - string (max 8 characters), UTF-8 symbols, text or empty - displays in system tray menu
+# icon - string (max 8 characters), UTF-8 symbols, text or empty - displays in system tray menu. Or a path to a .png (max 256x256) / .svg file, e.g. "~/icons/vpn.svg"
 # sn - boolean (default: true, write without quotes), send command result to system notification
-"#;
+# sandbox - boolean (optional, default: false), run through bwrap/firejail: no network, read-only home, private /tmp
+# executor - string (optional, default: "local"), available values: [local, ssh, container]
+# host - string, ssh destination, e.g. "user@server". Runs the command over ssh (BatchMode, no password prompts) even without executor = "ssh"
+# container - string, podman/docker container name for executor = "container"
+# interactive - boolean (optional, default: false), run as `shell -i -c` so aliases and functions from rc files resolve. Ignored for custom shells from [[settings.shells]], they always run with their own flag
+# confirm - boolean (optional, default: false), ask "Run now / Skip" in a notification before running from the tray, skipped after 60 s
+# in_terminal - boolean (optional, default: false), open the command in a terminal emulator instead of capturing its output (for interactive commands)
+# cwd - string (optional), working directory of local and in_terminal commands, "~/" is expanded. The tray offers a terminal there
+# tray_position - integer (optional), place in the tray menu, independent of the order here. Commands without it follow in file order
+# pinned - boolean (optional, default: false), show the command at the top of the tray menu, above a separator. Pinned commands are also actions of the app launcher (~/.local/share/applications/gucli.desktop), e.g. on right click in GNOME/KDE
+# gui - boolean (optional, default: false), command changes the desktop (monitor layout etc.): a screenshot is saved to ~/.config/gucli/screenshots when it fails
+# label - string (optional), text shown in the tray menu instead of the command
+# mnemonic - string (optional, 1 character), underlined key that triggers the command while the tray menu is open
+# hosts - array of strings (optional), e.g. ["laptop", "desktop"]: show the command only on machines with one of these hostnames
+# default - boolean (optional, default: false), the command run by a double click on the tray icon (or a single click, see left_click in [settings]). Only one command can be the default
+# health_interval - integer (optional, seconds, min 5), run the command in the background as a health check on this interval: while it fails the tray icon gets a red badge, a notification is sent once per outage. Not together with confirm, in_terminal, {password:…}, {stdin}, {file}, {dir} or a command flagged as destructive
+# {secret:name} - placeholder usable in command for the secret stored in the Secret Service keyring (service "gucli", username "name"): at execution time it becomes "$GUCLI_SECRET_1" (_2, _3... for more secrets, local commands in POSIX shells and fish only, not in Flatpak) and the value is only in the environment, e.g. command = "curl -H \"Authorization: Bearer {secret:github}\" ..."
+# ssh_key - string (optional), identity file for host, e.g. "~/.ssh/homelab". ssh config and agent are used when empty
+# output_file - string (optional), append the stdout of every successful run to this file, strftime placeholders allowed, e.g. "~/logs/df-%Y-%m-%d.log"
+# {stdin} - marker usable in command: a text dialog (zenity, kdialog or yad) asks for input, which is piped into the command, e.g. command = "wl-copy {stdin}"
+# {file} / {dir} - placeholders usable in command, a file or folder chooser opens before each run and the picked path is inserted (quoted), e.g. command = "convert {file} ~/out.pdf"
+# notify_cooldown - integer (optional, seconds), identical notifications of the command within this time are coalesced into one, e.g. for monitor commands that keep failing
+# on_busy - string (optional, default: "skip"), when the command is started again while still running: "skip" the new run, "queue" it until the running one ends, or run both in "parallel"
+# queue - string (optional), name of a run queue: commands with the same queue run one at a time, in the order they were started, e.g. "backup". The tray tooltip shows how many wait
+# notify_length - integer (optional, default: [settings] notify_length or 200), characters of output in the notification, a cut one gets a "View full output" button
+# long_running - boolean (optional, default: false), no timeout: the command is supervised in the background and a notification is sent when it finishes, failed or not
+# run_on_start - boolean (optional, default: false), run the command when gucli starts, after the tray is set up. Result logged and notified like a tray run, e.g. to start a sync client at login
+# {password:prompt} - placeholder usable in command: a masked dialog (zenity, kdialog, yad or $SSH_ASKPASS) asks before each run and the placeholder becomes "$GUCLI_PASSWORD", the value is only in the environment. Keep it out of argv: pipe it with the printf builtin, e.g. command = "printf %s\\n {password:sudo} | sudo -S systemctl restart nginx", or assign it, e.g. "PASSWD={password:smb} smbclient //nas/share -U me". Local commands in sh, bash, zsh, fish or another POSIX shell only
+#
+# [settings] - optional section with app settings
+# [[settings.shells]] - registers an extra interpreter for the shell field, e.g. nu, pwsh, python
+#   name - string, value used in the shell field; path - string, full path to the executable
+#   flag - string (optional, default: "-c"), placed before the command string
+# terminal - string (optional), terminal emulator for in_terminal commands, e.g. "kitty". Detected when empty
+# time_format - string (optional), strftime format of timestamps in the log and settings window, e.g. "%d.%m.%Y %H:%M:%S". Locale default when empty
+# language - string (optional), "en" or "ru", UI/tray/notification language. System locale when empty
+# left_click - string (optional, default: "menu"), tray icon left click: "menu", "settings" (open this window) or "default" (run the default command). Not reported by every desktop, e.g. AppIndicator trays only show the menu
+# autostart - string (optional, default: "desktop"), how the Autostart button registers the app: "desktop" (~/.config/autostart) or "systemd" (user unit gucli.service, for window managers without XDG autostart)
+# autostart_delay - integer (optional, default: 0), seconds to wait at login before starting, for tray hosts that are not ready yet. Set next to the Autostart button
+# start_hidden - boolean (optional, default: false), no window at startup, also not for a broken commands.toml, only the tray icon (same as `gucli --hidden`). `gucli --settings` still opens the settings
+# http_port - integer (optional), start an HTTP trigger on 127.0.0.1:<port>, e.g. 7700. Requests need the token from ~/.config/gucli/http_token
+# audit_syslog - boolean (optional, default: false), also send every audit log entry to syslog / the journal (facility authpriv, tag gucli-audit)
+# log_target - string (optional, default: "file"), app log destination: "file" (gucli.log), "journald" (`journalctl --user -t gucli`) or "both". Read at startup
+# log_level - string (optional, default: "info"), one of [error, warn, info, debug, trace]. The tray item "Debug logging" switches to debug until the next start
+# log_file - string (optional, default: "~/.config/gucli/gucli.log"), path of the app log
+# log_max_lines - integer (optional, default: 100), lines kept in the log file, newest first
+# notify_length - integer (optional, default: 200), characters of command output in notifications, the whole output opens from the notification
+# check_updates - boolean (optional, default: false), look for a newer release on GitHub at start and once a day, notify when there is one. Also shown in the About tab
+# on_quit - string (optional), sh command run when gucli quits (tray Quit, SIGTERM, logout), e.g. "nmcli connection down work-vpn". Killed after 5 seconds
+# [settings.custom_theme] - optional colors of the "custom" theme (F4 cycles to 🎨), edited in the About tab: accent, background, text - CSS colors, e.g. "#10b981"; font_size - integer, px
+"##;
 
 static EXAMPLE_COMMANDS: &str = r#"
 [[commands]]
@@ -0,0 +1,161 @@
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+use tracing::{debug, info};
+
+const PORTAL: &str = "org.freedesktop.portal.Desktop";
+const PORTAL_PATH: &str = "/org/freedesktop/portal/desktop";
+
+/// running inside a Flatpak sandbox: commands, shells and terminals live on the host
+pub fn active() -> bool {
+    static ACTIVE: OnceLock<bool> = OnceLock::new();
+    *ACTIVE.get_or_init(|| {
+        let active = Path::new("/.flatpak-info").exists();
+        if active {
+            info!("Running in Flatpak, commands go through flatpak-spawn --host");
+        }
+        active
+    })
+}
+
+/// the same process started on the host by `flatpak-spawn --host`, unchanged outside Flatpak.
+/// Needs `--talk-name=org.freedesktop.Flatpak` in the manifest
+pub fn host(process: Command) -> Command {
+    if !active() {
+        return process;
+    }
+    let mut spawn = Command::new("flatpak-spawn");
+    // --watch-bus: the host process ends with gucli instead of being left behind
+    spawn.args(["--host", "--watch-bus"]);
+    if let Some(dir) = process.get_current_dir() {
+        spawn.arg(format!("--directory={}", dir.display()));
+    }
+    // --env= is argv, readable by every local user: never pass passwords or secrets that way
+    for (key, value) in process.get_envs() {
+        let key = key.to_string_lossy();
+        if key.starts_with(crate::prompt::PASSWORD_ENV) || key.starts_with(crate::secrets::SECRET_ENV) {
+            continue;
+        }
+        if let Some(value) = value {
+            spawn.arg(format!("--env={}={}", key, value.to_string_lossy()));
+        }
+    }
+    spawn.arg(process.get_program()).args(process.get_args());
+    spawn
+}
+
+/// `command -v bin` on the host, the sandbox PATH has none of the user's tools
+pub fn host_which(bin: &str) -> Option<PathBuf> {
+    let output = Command::new("flatpak-spawn")
+        .args(["--host", "sh", "-c", "command -v \"$1\"", "sh", bin])
+        .stdin(Stdio::null())
+        .output()
+        .ok()?;
+    let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && path.starts_with('/')).then(|| PathBuf::from(path))
+}
+
+/// notification through the portal, the sandbox has no org.freedesktop.Notifications
+pub fn notify(summary: &str, body: &str) -> Result<(), String> {
+    show(&notification_id(), summary, body)
+}
+
+pub fn notification_id() -> String {
+    format!("gucli-{}", uuid::Uuid::new_v4())
+}
+
+/// portal notification under `id`, shown again under the same id it is replaced
+pub fn show(id: &str, summary: &str, body: &str) -> Result<(), String> {
+    let notification = format!("{{'title': <{}>, 'body': <{}>}}", gvariant_str(summary), gvariant_str(body));
+    portal_call("org.freedesktop.portal.Notification.AddNotification", &[id, &notification])
+}
+
+pub fn withdraw(id: &str) {
+    let _ = portal_call("org.freedesktop.portal.Notification.RemoveNotification", &[id]);
+}
+
+/// portal notification with buttons, the chosen action id or None when not answered within `timeout`
+pub fn choose(summary: &str, body: &str, actions: &[(&str, &str)], timeout: Duration) -> Result<Option<String>, String> {
+    let id = notification_id();
+    // listening before it is shown, a quick click must not be missed
+    let mut monitor = Command::new("gdbus")
+        .args(["monitor", "--session", "--dest", PORTAL, "--object-path", PORTAL_PATH])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("gdbus not available: {}", e))?;
+    let stdout = monitor.stdout.take().ok_or("gdbus monitor has no output")?;
+    let buttons: Vec<String> = actions
+        .iter()
+        .map(|(action, label)| format!("{{'label': <{}>, 'action': <{}>}}", gvariant_str(label), gvariant_str(action)))
+        .collect();
+    let notification = format!(
+        "{{'title': <{}>, 'body': <{}>, 'buttons': <[{}]>}}",
+        gvariant_str(summary),
+        gvariant_str(body),
+        buttons.join(", ")
+    );
+    if let Err(e) = portal_call("org.freedesktop.portal.Notification.AddNotification", &[&id, &notification]) {
+        let _ = monitor.kill();
+        let _ = monitor.wait();
+        return Err(e);
+    }
+
+    let (tx, rx) = mpsc::channel();
+    let invoked = format!("ActionInvoked ({}, '", gvariant_str(&id));
+    thread::spawn(move || {
+        // ...Notification.ActionInvoked ('gucli-…', 'run', @av [])
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            if let Some(rest) = line.split_once(&invoked).map(|(_, rest)| rest)
+                && let Some((action, _)) = rest.split_once('\'')
+            {
+                let _ = tx.send(action.to_string());
+                break;
+            }
+        }
+    });
+    let answer = rx.recv_timeout(timeout).ok();
+    // ends the reader thread too
+    let _ = monitor.kill();
+    let _ = monitor.wait();
+    withdraw(&id);
+    Ok(answer)
+}
+
+/// ask the Background portal to start (or no longer start) gucli at login
+pub fn request_autostart(enable: bool) -> Result<(), String> {
+    let options = format!(
+        "{{'reason': <'Show the command tray at login'>, 'autostart': <{}>, 'commandline': <['gucli', '--hidden']>}}",
+        enable
+    );
+    portal_call("org.freedesktop.portal.Background.RequestBackground", &["", &options])
+}
+
+/// entry the Background portal writes on the host, readable with `--filesystem=xdg-config/autostart:ro`
+pub fn autostart_entry(home: &Path) -> PathBuf {
+    let id = std::env::var("FLATPAK_ID").unwrap_or_else(|_| "site.jager.gucli".to_string());
+    home.join(".config/autostart").join(format!("{}.desktop", id))
+}
+
+fn portal_call(method: &str, args: &[&str]) -> Result<(), String> {
+    let output = Command::new("gdbus")
+        .args(["call", "--session", "--dest", PORTAL, "--object-path", PORTAL_PATH, "--method", method])
+        .args(args)
+        .output()
+        .map_err(|e| format!("gdbus not available: {}", e))?;
+    if output.status.success() {
+        debug!("Portal call {} done", method);
+        Ok(())
+    } else {
+        Err(format!("{} failed: {}", method, String::from_utf8_lossy(&output.stderr).trim()))
+    }
+}
+
+/// single-quoted GVariant string
+fn gvariant_str(s: &str) -> String {
+    format!("'{}'", s.replace('\\', "\\\\").replace('\'', "\\'"))
+}
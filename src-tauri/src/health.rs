@@ -0,0 +1,107 @@
+use crate::children::Children;
+use crate::{AppCommandsConfig, UserCommand, execute_command, hostname, send_notification, tooltip};
+use std::collections::BTreeMap;
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::Manager;
+use tauri::image::Image;
+use tracing::{debug, info, warn};
+
+/// shorter intervals are raised to this
+pub const MIN_INTERVAL: u64 = 5;
+const TICK: Duration = Duration::from_secs(1);
+
+/// run the `health_interval` commands of this host in the background
+pub fn start(app: tauri::AppHandle, config: &AppCommandsConfig) {
+    let host = hostname();
+    let checks: Vec<UserCommand> = config
+        .commands
+        .iter()
+        .filter(|c| c.health_interval.is_some() && c.available_on(&host))
+        .cloned()
+        .collect();
+    if checks.is_empty() {
+        return;
+    }
+    info!("{} health check(s) scheduled", checks.len());
+
+    thread::spawn(move || {
+        let mut due: Vec<Instant> = vec![Instant::now(); checks.len()];
+        // failing checks and their error, ordered for a stable tooltip
+        let mut failing: BTreeMap<String, String> = BTreeMap::new();
+        // one notification per outage, until every check passes again
+        let mut notified = false;
+
+        loop {
+            let mut changed = false;
+            for (cmd, next) in checks.iter().zip(due.iter_mut()) {
+                if Instant::now() < *next {
+                    continue;
+                }
+                let interval = cmd.health_interval.unwrap_or(MIN_INTERVAL).max(MIN_INTERVAL);
+                *next = Instant::now() + Duration::from_secs(interval);
+
+                let result = execute_command(cmd.clone(), &app.state::<Children>());
+                debug!("Health check `{}`: {:?}", cmd.command, result);
+                changed |= match result {
+                    Ok(_) => failing.remove(&cmd.command).is_some(),
+                    Err(err) => failing.insert(cmd.command.clone(), err).is_none(),
+                };
+            }
+
+            if changed {
+                set_badge(&app, &failing);
+                if failing.is_empty() {
+                    info!("Health checks passing again");
+                    notified = false;
+                } else if !notified {
+                    warn!("Health checks failing: {:?}", failing.keys().collect::<Vec<_>>());
+                    let body = failing
+                        .iter()
+                        .map(|(command, err)| format!("{}: {}", command, err.lines().next().unwrap_or("")))
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    send_notification("Err( Health check failed )", &body);
+                    notified = true;
+                }
+            }
+            thread::sleep(TICK);
+        }
+    });
+}
+
+/// red dot on the tray icon while checks fail, the plain icon otherwise
+fn set_badge(app: &tauri::AppHandle, failing: &BTreeMap<String, String>) {
+    tooltip::update(app, |status| status.failing = failing.keys().cloned().collect());
+    show_badge(app, !failing.is_empty());
+}
+
+/// badged or plain icon, also for a tray created anew
+pub fn show_badge(app: &tauri::AppHandle, badge: bool) {
+    let Some(tray) = app.tray_by_id("main") else {
+        return;
+    };
+    let Some(icon) = app.default_window_icon() else {
+        return;
+    };
+    let icon = if badge { badged(icon) } else { icon.clone() };
+    let _ = tray.set_icon(Some(icon));
+}
+
+/// copy of the icon with a filled circle in the bottom right quarter
+fn badged(icon: &Image<'_>) -> Image<'static> {
+    let (width, height) = (icon.width(), icon.height());
+    let mut rgba = icon.rgba().to_vec();
+    let radius = width.min(height) as f32 / 4.0;
+    let (cx, cy) = (width as f32 - radius, height as f32 - radius);
+    for y in 0..height {
+        for x in 0..width {
+            let (dx, dy) = (x as f32 + 0.5 - cx, y as f32 + 0.5 - cy);
+            if dx * dx + dy * dy <= radius * radius {
+                let i = ((y * width + x) * 4) as usize;
+                rgba[i..i + 4].copy_from_slice(&[220, 38, 38, 255]);
+            }
+        }
+    }
+    Image::new_owned(rgba, width, height)
+}
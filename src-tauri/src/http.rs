@@ -0,0 +1,224 @@
+use crate::AppCommandsConfig;
+use crate::files::{config_dir, private_file};
+use crate::instance;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+/// bearer token of the trigger server, created on first use, readable by the user only
+pub const TOKEN_FILE: &str = "http_token";
+
+// request line and headers, read before the token is checked
+const MAX_REQUEST: u64 = 8192;
+// for the whole request, not per read: a client trickling a byte at a time can't hold the listener
+const REQUEST_DEADLINE: Duration = Duration::from_secs(5);
+
+/// localhost listener for `[settings] http_port`, requests map to the socket intents:
+/// POST /run/<command or label>, POST /reload, GET or POST /settings
+pub fn start(app: tauri::AppHandle, config: &AppCommandsConfig) {
+    let Some(port) = config.settings.http_port else {
+        return;
+    };
+    let token = match token() {
+        Ok(token) => token,
+        Err(e) => {
+            error!("HTTP trigger disabled, no token: {}", e);
+            return;
+        }
+    };
+    let listener = match TcpListener::bind((Ipv4Addr::LOCALHOST, port)) {
+        Ok(listener) => listener,
+        Err(e) => {
+            error!("HTTP trigger not bound to 127.0.0.1:{}: {}", port, e);
+            return;
+        }
+    };
+    info!("HTTP trigger listening on 127.0.0.1:{}", port);
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    if let Err(e) = serve(&app, &token, stream) {
+                        warn!("HTTP trigger request failed: {}", e);
+                    }
+                }
+                Err(e) => error!("HTTP trigger error: {}", e),
+            }
+        }
+    });
+}
+
+fn serve(app: &tauri::AppHandle, token: &str, mut stream: TcpStream) -> Result<(), String> {
+    // one request at a time, a silent or slow client must not block the others
+    let deadline = Deadline { stream: &stream, until: Instant::now() + REQUEST_DEADLINE };
+    // an unauthenticated client can't make it buffer more than this
+    let mut reader = BufReader::new(deadline.take(MAX_REQUEST));
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).map_err(|e| e.to_string())?;
+    let mut authorized = false;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).map_err(|e| e.to_string())? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':')
+            && name.eq_ignore_ascii_case("authorization")
+            && value.trim().strip_prefix("Bearer ").is_some_and(|given| token_matches(given, token))
+        {
+            authorized = true;
+        }
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let (method, target) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    // bookmarks can't send headers: GET /run/x?token=...
+    authorized |= query
+        .split('&')
+        .any(|pair| pair.strip_prefix("token=").is_some_and(|given| token_matches(given, token)));
+
+    let intent = intent(path);
+    let (status, body) = if !matches!(method, "GET" | "POST") {
+        ("405 Method Not Allowed", "use POST, or GET for /settings".to_string())
+    } else if !authorized {
+        ("401 Unauthorized", format!("token required, see {} in the gucli config dir", TOKEN_FILE))
+    } else {
+        match intent {
+            // a link or a prefetch must not run anything, only opening the settings is fine for a bookmark
+            Some(intent) if method == "GET" && intent != "settings" => {
+                ("405 Method Not Allowed", "/run and /reload need POST".to_string())
+            }
+            Some(intent) => {
+                info!("HTTP trigger `{}`", intent);
+                match instance::handle(app, &intent) {
+                    Ok(reply) => ("200 OK", reply),
+                    Err(e) if e.starts_with("No command") => ("404 Not Found", e),
+                    Err(e) => ("400 Bad Request", e),
+                }
+            }
+            None => ("404 Not Found", "endpoints: POST /run/<command or label>, POST /reload, GET /settings".to_string()),
+        }
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// reads that time out at the request's deadline, however slowly the bytes come in
+struct Deadline<'a> {
+    stream: &'a TcpStream,
+    until: Instant,
+}
+
+impl Read for Deadline<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let left = self.until.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return Err(io::Error::new(io::ErrorKind::TimedOut, "request not complete in time"));
+        }
+        self.stream.set_read_timeout(Some(left))?;
+        let mut stream = self.stream;
+        stream.read(buf)
+    }
+}
+
+/// compared in constant time, the response time tells nothing about a guess
+fn token_matches(given: &str, token: &str) -> bool {
+    given.len() == token.len() && given.bytes().zip(token.bytes()).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+/// socket intent for a request path
+fn intent(path: &str) -> Option<String> {
+    match path.trim_end_matches('/') {
+        "/settings" => Some("settings".to_string()),
+        "/reload" => Some("reload".to_string()),
+        other => other
+            .strip_prefix("/run/")
+            .filter(|name| !name.is_empty())
+            .map(|name| format!("run {}", percent_decode(name))),
+    }
+}
+
+/// %XX escapes of a path, `+` is a plus there: only form data uses it for spaces
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 2;
+            }
+            (byte, _) => out.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+/// the stored token, a new random one on first use
+fn token() -> Result<String, String> {
+    let path = config_dir()?.join(TOKEN_FILE);
+    if let Ok(token) = fs::read_to_string(&path)
+        && !token.trim().is_empty()
+    {
+        return Ok(token.trim().to_string());
+    }
+    let token = Uuid::new_v4().simple().to_string();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    // an empty leftover; created 0600 below, never readable by others for a moment
+    let _ = fs::remove_file(&path);
+    private_file()
+        .write(true)
+        .create_new(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(token.as_bytes()))
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    info!("HTTP trigger token written to {}", path.display());
+    Ok(token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percent_decode_escapes() {
+        assert_eq!(percent_decode("Restart%20VPN"), "Restart VPN");
+        assert_eq!(percent_decode("%D0%BF%D1%80%D0%B8%D0%B2%D0%B5%D1%82"), "привет");
+        assert_eq!(percent_decode("c%2B%2B"), "c++");
+    }
+
+    #[test]
+    fn percent_decode_keeps_plus_and_broken_escapes() {
+        assert_eq!(percent_decode("a+b"), "a+b");
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("%zz%4"), "%zz%4");
+    }
+
+    #[test]
+    fn intents_of_paths() {
+        assert_eq!(intent("/run/Restart%20VPN").as_deref(), Some("run Restart VPN"));
+        assert_eq!(intent("/settings/").as_deref(), Some("settings"));
+        assert_eq!(intent("/reload").as_deref(), Some("reload"));
+        assert_eq!(intent("/run/"), None);
+        assert_eq!(intent("/"), None);
+    }
+}
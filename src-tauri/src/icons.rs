@@ -0,0 +1,69 @@
+use crate::files::expand_home;
+use std::fs;
+use tauri::image::Image;
+
+// rendered size of SVG icons, PNG files are used as they are
+const ICON_SIZE: u32 = 32;
+const MAX_FILE_SIZE: u64 = 512 * 1024;
+const MAX_PNG_SIDE: u32 = 256;
+
+/// icon field names an image file instead of text
+pub fn is_image(icon: &str) -> bool {
+    let icon = icon.trim().to_lowercase();
+    icon.ends_with(".png") || icon.ends_with(".svg")
+}
+
+/// existing PNG/SVG file of reasonable size, checked in load_commands
+pub fn validate(icon: &str) -> Result<(), String> {
+    let path = expand_home(icon);
+    let meta = fs::metadata(&path).map_err(|e| format!("Icon `{}`: {}", path.display(), e))?;
+    if meta.len() > MAX_FILE_SIZE {
+        return Err(format!("Icon `{}` is larger than {} KiB", path.display(), MAX_FILE_SIZE / 1024));
+    }
+    if icon.trim().to_lowercase().ends_with(".png") {
+        let (width, height) = png_size(&fs::read(&path).map_err(|e| e.to_string())?)
+            .ok_or_else(|| format!("Icon `{}` is not a PNG file", path.display()))?;
+        if width > MAX_PNG_SIDE || height > MAX_PNG_SIDE {
+            return Err(format!(
+                "Icon `{}` is {}x{}, max {}x{}",
+                path.display(),
+                width,
+                height,
+                MAX_PNG_SIDE,
+                MAX_PNG_SIDE
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// image for an IconMenuItem
+pub fn load(icon: &str) -> Result<Image<'static>, String> {
+    let path = expand_home(icon);
+    if icon.trim().to_lowercase().ends_with(".svg") {
+        let data = fs::read(&path).map_err(|e| format!("Icon `{}`: {}", path.display(), e))?;
+        return render_svg(&data).map_err(|e| format!("Icon `{}`: {}", path.display(), e));
+    }
+    Image::from_path(&path).map_err(|e| format!("Icon `{}`: {}", path.display(), e))
+}
+
+// width & height from the IHDR chunk
+fn png_size(data: &[u8]) -> Option<(u32, u32)> {
+    if data.len() < 24 || &data[..8] != b"\x89PNG\r\n\x1a\n" {
+        return None;
+    }
+    let width = u32::from_be_bytes(data[16..20].try_into().ok()?);
+    let height = u32::from_be_bytes(data[20..24].try_into().ok()?);
+    Some((width, height))
+}
+
+fn render_svg(data: &[u8]) -> Result<Image<'static>, String> {
+    use resvg::{tiny_skia, usvg};
+    let tree = usvg::Tree::from_data(data, &usvg::Options::default()).map_err(|e| e.to_string())?;
+    let mut pixmap = tiny_skia::Pixmap::new(ICON_SIZE, ICON_SIZE).ok_or("Failed to allocate icon")?;
+    // fit the longer side, keep the aspect ratio
+    let size = tree.size();
+    let scale = ICON_SIZE as f32 / size.width().max(size.height());
+    resvg::render(&tree, tiny_skia::Transform::from_scale(scale, scale), &mut pixmap.as_mut());
+    Ok(Image::new_owned(pixmap.take(), ICON_SIZE, ICON_SIZE))
+}
@@ -0,0 +1,260 @@
+use crate::{apply_config, commands, open_settings, spawn_command};
+use std::env;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+#[cfg(unix)]
+use std::os::unix::io::FromRawFd;
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener as Listener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::thread;
+#[cfg(unix)]
+use std::time::Duration;
+use tracing::{error, info, warn};
+#[cfg(windows)]
+use pipe::Listener;
+
+/// intents understood on the socket, one line per connection
+pub const USAGE: &str = "Usage: gucli [--settings | --hidden | --run <command or label> | --reload | --verify-audit]";
+
+/// $XDG_RUNTIME_DIR/gucli.sock, also the systemd socket unit's ListenStream=%t/gucli.sock
+#[cfg(unix)]
+pub fn socket_path() -> PathBuf {
+    match env::var_os("XDG_RUNTIME_DIR") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir).join("gucli.sock"),
+        _ => env::temp_dir().join(format!("gucli-{}.sock", unsafe { nix::libc::getuid() })),
+    }
+}
+
+/// named pipe of this user, Windows has no unix sockets to spare
+#[cfg(windows)]
+pub fn socket_path() -> PathBuf {
+    PathBuf::from(format!(r"\\.\pipe\gucli-{}", env::var("USERNAME").unwrap_or_default()))
+}
+
+// an intent is one short line, longer input is cut off here
+const MAX_INTENT: u64 = 4096;
+// a client that connects and never sends its line gives up its thread after this, pipes have no timeout
+#[cfg(unix)]
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[cfg(unix)]
+type Stream = UnixStream;
+#[cfg(windows)]
+type Stream = fs::File;
+
+#[cfg(unix)]
+fn connect(path: &Path) -> std::io::Result<Stream> {
+    UnixStream::connect(path)
+}
+
+#[cfg(windows)]
+fn connect(path: &Path) -> std::io::Result<Stream> {
+    fs::OpenOptions::new().read(true).write(true).open(path)
+}
+
+/// socket line for the command line arguments, None without arguments
+pub fn intent_from_args(mut args: impl Iterator<Item = String>) -> Result<Option<String>, String> {
+    let Some(flag) = args.next() else {
+        return Ok(None);
+    };
+    match flag.as_str() {
+        "--settings" => Ok(Some("settings".to_string())),
+        "--reload" => Ok(Some("reload".to_string())),
+        "--hidden" => Ok(Some("hidden".to_string())),
+        "--run" => {
+            let name: Vec<String> = args.collect();
+            if name.is_empty() {
+                return Err(USAGE.to_string());
+            }
+            Ok(Some(format!("run {}", name.join(" "))))
+        }
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+/// hand the intent to the running instance, returns its reply
+pub fn forward(intent: &str) -> Result<String, String> {
+    let path = socket_path();
+    let mut stream = connect(&path)
+        .map_err(|e| format!("Running instance not reachable at {}: {}", path.display(), e))?;
+    writeln!(stream, "{}", intent).map_err(|e| e.to_string())?;
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply).map_err(|e| e.to_string())?;
+    match reply.trim().strip_prefix("err ") {
+        Some(err) => Err(err.to_string()),
+        None => Ok(reply.trim().to_string()),
+    }
+}
+
+/// listening socket handed over by systemd socket activation (sd_listen_fds protocol)
+#[cfg(unix)]
+pub fn activation_socket() -> Option<Listener> {
+    let pid: u32 = env::var("LISTEN_PID").ok()?.parse().ok()?;
+    let fds: i32 = env::var("LISTEN_FDS").ok()?.parse().ok()?;
+    // don't leak the activation env into executed commands
+    unsafe {
+        env::remove_var("LISTEN_PID");
+        env::remove_var("LISTEN_FDS");
+        env::remove_var("LISTEN_FDNAMES");
+    }
+    if pid != std::process::id() || fds < 1 {
+        return None;
+    }
+    // first passed descriptor is always 3 (SD_LISTEN_FDS_START)
+    Some(unsafe { Listener::from_raw_fd(3) })
+}
+
+#[cfg(windows)]
+pub fn activation_socket() -> Option<Listener> {
+    None
+}
+
+/// own socket when not started by systemd, a leftover file of a crashed instance is replaced
+pub fn bind_socket() -> Option<Listener> {
+    let path = socket_path();
+    #[cfg(unix)]
+    if connect(&path).is_err() {
+        let _ = fs::remove_file(&path);
+    }
+    Listener::bind(&path)
+        .inspect_err(|e| warn!("Socket {} not bound: {}", path.display(), e))
+        .ok()
+}
+
+/// answer intents of second instances and activation triggers, a thread per connection
+/// so a client that never sends its line only blocks itself
+pub fn serve(app: tauri::AppHandle, listener: Listener) {
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    error!("Socket error: {}", e);
+                    continue;
+                }
+            };
+            let app = app.clone();
+            thread::spawn(move || answer(&app, stream));
+        }
+    });
+}
+
+fn answer(app: &tauri::AppHandle, mut stream: Stream) {
+    #[cfg(unix)]
+    let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+    let mut line = String::new();
+    let _ = BufReader::new((&stream).take(MAX_INTENT)).read_line(&mut line);
+    let reply = match handle(app, line.trim()) {
+        Ok(reply) => reply,
+        Err(e) => format!("err {}", e),
+    };
+    info!("Socket intent `{}`: {}", line.trim(), reply);
+    let _ = writeln!(stream, "{}", reply);
+    // FlushFileBuffers: the pipe's unread reply is dropped with the handle otherwise
+    #[cfg(windows)]
+    let _ = stream.sync_all();
+}
+
+pub(crate) fn handle(app: &tauri::AppHandle, intent: &str) -> Result<String, String> {
+    let (verb, arg) = intent.split_once(' ').unwrap_or((intent, ""));
+    match verb {
+        // a bare connection (activation trigger, plain launch) opens the settings
+        "" | "settings" => {
+            let handle = app.clone();
+            app.run_on_main_thread(move || open_settings(&handle))
+                .map_err(|e| e.to_string())?;
+            Ok("ok".to_string())
+        }
+        // tray only, a silent autostart must not pop up the running instance
+        "hidden" => Ok("ok".to_string()),
+        "run" => {
+            let cmd = commands(app)
+                .commands
+                .into_iter()
+                .find(|c| c.command == arg.trim() || c.label.trim() == arg.trim())
+                .ok_or_else(|| format!("No command `{}`", arg.trim()))?;
+            spawn_command(app, cmd);
+            Ok("ok".to_string())
+        }
+        // commands.toml edited by hand: new tray menu, as after Save
+        "reload" => {
+            apply_config(app)?;
+            Ok("ok".to_string())
+        }
+        other => Err(format!("Unknown intent `{}`. {}", other, USAGE)),
+    }
+}
+
+#[cfg(windows)]
+mod pipe {
+    use std::fs::File;
+    use std::io;
+    use std::os::windows::ffi::OsStrExt;
+    use std::os::windows::io::{AsRawHandle, FromRawHandle};
+    use std::path::Path;
+    use windows_sys::Win32::Foundation::{ERROR_PIPE_CONNECTED, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::Storage::FileSystem::{FILE_FLAG_FIRST_PIPE_INSTANCE, PIPE_ACCESS_DUPLEX};
+    use windows_sys::Win32::System::Pipes::{
+        ConnectNamedPipe, CreateNamedPipeW, PIPE_READMODE_BYTE, PIPE_REJECT_REMOTE_CLIENTS, PIPE_TYPE_BYTE,
+        PIPE_UNLIMITED_INSTANCES, PIPE_WAIT,
+    };
+
+    /// named pipe server, a new pipe instance for every connection
+    pub struct Listener {
+        name: Vec<u16>,
+        first: Option<File>,
+    }
+
+    impl Listener {
+        /// fails like bind() on a used socket when another instance already serves the pipe
+        pub fn bind(path: &Path) -> io::Result<Self> {
+            let name: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+            let first = create(&name, FILE_FLAG_FIRST_PIPE_INSTANCE)?;
+            Ok(Self { name, first: Some(first) })
+        }
+
+        pub fn incoming(mut self) -> impl Iterator<Item = io::Result<File>> {
+            std::iter::from_fn(move || {
+                let pipe = match self.first.take() {
+                    Some(pipe) => pipe,
+                    None => match create(&self.name, 0) {
+                        Ok(pipe) => pipe,
+                        Err(e) => return Some(Err(e)),
+                    },
+                };
+                Some(connect(pipe))
+            })
+        }
+    }
+
+    fn create(name: &[u16], flags: u32) -> io::Result<File> {
+        let handle = unsafe {
+            CreateNamedPipeW(
+                name.as_ptr(),
+                PIPE_ACCESS_DUPLEX | flags,
+                PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT | PIPE_REJECT_REMOTE_CLIENTS,
+                PIPE_UNLIMITED_INSTANCES,
+                4096,
+                4096,
+                0,
+                std::ptr::null(),
+            )
+        };
+        if handle == INVALID_HANDLE_VALUE {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(unsafe { File::from_raw_handle(handle as _) })
+    }
+
+    /// wait for a client; one that came between create and connect is already there
+    fn connect(pipe: File) -> io::Result<File> {
+        if unsafe { ConnectNamedPipe(pipe.as_raw_handle() as _, std::ptr::null_mut()) } == 0 {
+            let e = io::Error::last_os_error();
+            if e.raw_os_error() != Some(ERROR_PIPE_CONNECTED as i32) {
+                return Err(e);
+            }
+        }
+        Ok(pipe)
+    }
+}
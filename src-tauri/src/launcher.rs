@@ -0,0 +1,50 @@
+use crate::children::{self, Children};
+use crate::{UserCommand, open_settings, spawn_command};
+use std::thread;
+use tauri::Manager;
+use tracing::error;
+
+/// window label, also listed in capabilities/default.json
+pub const LABEL: &str = "launcher";
+
+/// commands as buttons, for desktops without a tray: shown when the tray icon can't be created.
+/// Closing it quits the app, there is no tray to quit from
+pub fn open(app: &tauri::AppHandle) {
+    if let Some(window) = app.get_webview_window(LABEL) {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+        return;
+    }
+    let window = match tauri::WebviewWindowBuilder::new(app, LABEL, tauri::WebviewUrl::App("/?view=launcher".into()))
+        .title("Gucli")
+        .inner_size(360.0, 480.0)
+        .build()
+    {
+        Ok(window) => window,
+        Err(e) => {
+            error!("Launcher window not opened: {}", e);
+            return;
+        }
+    };
+    let app = app.clone();
+    window.on_window_event(move |event| {
+        if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+            api.prevent_close();
+            // the guard may wait for an answer, keep the window responsive
+            let app = app.clone();
+            thread::spawn(move || children::quit_guarded(&app, &app.state::<Children>()));
+        }
+    });
+}
+
+/// run a command picked in the launcher, like a tray entry
+#[tauri::command]
+pub fn launch_command(app: tauri::AppHandle, cmd: UserCommand) {
+    spawn_command(&app, cmd);
+}
+
+#[tauri::command]
+pub fn show_settings(app: tauri::AppHandle) {
+    open_settings(&app);
+}
@@ -1,38 +1,102 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, RwLock};
 use std::{env, fs, process::Command};
 use tauri::{
-    Manager, Runtime,
-    menu::{MenuBuilder, MenuItem},
-    tray::TrayIconBuilder,
+    Emitter, Manager, Runtime,
+    menu::{CheckMenuItem, IconMenuItem, IsMenuItem, Menu, MenuBuilder, MenuItem, Submenu, SubmenuBuilder},
+    tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
 };
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
+pub mod audit;
+pub mod autostart;
+pub mod children;
+pub mod confinement;
+pub mod confirm;
+pub mod diagnostics;
+pub mod encryption;
+pub mod executor;
 pub mod files;
+pub mod flatpak;
+pub mod health;
+pub mod http;
+pub mod icons;
+pub mod instance;
+pub mod launcher;
+pub mod log_level;
+pub mod log_tail;
+pub mod progress;
+pub mod prompt;
+pub mod registry;
+pub mod screenshot;
+pub mod secrets;
+pub mod selftest;
+pub mod shutdown;
+pub mod terminal;
+pub mod tooltip;
+pub mod tray_watch;
+pub mod units;
+pub mod updates;
+pub mod window_state;
 use crate::files::*;
+use crate::children::Children;
+use crate::registry::ExecutionRegistry;
 use std::process::Stdio;
 use std::thread;
 use std::time::{Duration, Instant};
 use tauri_plugin_opener::OpenerExt;
 use notify_rust::Notification;
+pub use gucli_shared::{AproposEntry, CommandExecuted, Execution, RunStats, TestResult, UserCommand};
+use gucli_shared::i18n::tr;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct UserCommand {
-    pub id: String,
-    pub shell: String,
-    pub command: String,
-    pub icon: String,
-    pub sn: bool,
+/// commands.toml the tray, the socket and the HTTP trigger run from, replaced by apply_config
+pub struct Commands(pub RwLock<AppCommandsConfig>);
+
+/// copy of the config in use
+pub(crate) fn commands(app: &tauri::AppHandle) -> AppCommandsConfig {
+    app.state::<Commands>().0.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// load the saved commands.toml and rebuild the tray menu from it, no restart needed
+pub(crate) fn apply_config(app: &tauri::AppHandle) -> Result<(), String> {
+    let config = load_commands().map_err(|e| e.to_string())?;
+    // ids are new with every load: the menu is built and the handlers look up from the same config
+    *app.state::<Commands>().0.write().unwrap_or_else(|e| e.into_inner()) = config.clone();
+    if let Some(tray) = app.tray_by_id("main") {
+        let menu = build_tray_menu(app, &config).map_err(|e| e.to_string())?;
+        tray.set_menu(Some(menu)).map_err(|e| e.to_string())?;
+        let left_click = config.settings.left_click.as_str();
+        let _ = tray.set_show_menu_on_left_click(matches!(left_click, "" | "menu"));
+    }
+    let host = hostname();
+    tooltip::update(app, |status| {
+        status.commands = config.commands.iter().filter(|c| c.available_on(&host)).count();
+    });
+    if let Err(e) = autostart::write_launcher(&config.commands) {
+        error!("Launcher not updated: {}", e);
+    }
+    info!("commands.toml applied, {} command(s)", config.commands.len());
+    Ok(())
 }
 
-#[derive(Default, Debug, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct AppCommandsConfig {
+    pub settings: AppSettings,
     pub commands: Vec<UserCommand>,
+    /// schema drift repaired while loading commands.toml
+    #[serde(skip)]
+    pub fixes: Vec<String>,
 }
 
 #[tauri::command]
 async fn open_file(name:&str, app: tauri::AppHandle) -> Result<String, String>{
     let path = match name {
-        "log" => full_path_log(),
-        "commands" => full_path_commands(),
+        "log" => full_path_log()?,
+        "commands" if encryption::active() => {
+            return Err("commands.toml is encrypted, decrypt it to edit the file".into());
+        }
+        "commands" => full_path_commands()?,
         _ => return Err("invalid name, use \"log\" or \"commands\"".into()),
     };
     let opener = app.opener();
@@ -42,12 +106,41 @@ async fn open_file(name:&str, app: tauri::AppHandle) -> Result<String, String>{
     }
 }
 
+const MAN_CACHE_TTL: Duration = Duration::from_secs(30 * 60); // cached help is reused for 30 minutes
+
+/// successful help lookups keyed by the query
+#[derive(Default)]
+pub struct ManCache(Mutex<HashMap<String, (Instant, String)>>);
+
 #[tauri::command]
-fn get_man(cmd: &str) -> Result<String, String> {
-    if cmd.trim().is_empty() {
+fn get_man(cmd: &str, refresh: bool, cache: tauri::State<'_, ManCache>) -> Result<String, String> {
+    let cmd = cmd.trim();
+    if cmd.is_empty() {
         return Err("Enter the command to search for help".to_string());
     }
 
+    if !refresh
+        && let Some((stored, output)) = cache.0.lock().unwrap().get(cmd)
+        && stored.elapsed() < MAN_CACHE_TTL
+    {
+        debug!("get_man cache hit: {}", cmd);
+        return Ok(output.clone());
+    }
+
+    match lookup_man(cmd)? {
+        Some(output) => {
+            cache
+                .0
+                .lock()
+                .unwrap()
+                .insert(cmd.to_string(), (Instant::now(), output.clone()));
+            Ok(output)
+        }
+        None => Ok(format!("No valid help found for '{}'", cmd)),
+    }
+}
+
+fn lookup_man(cmd: &str) -> Result<Option<String>, String> {
     const MIN_HELP_LENGTH: usize = 50; // Minimum length for valid help output (short outputs are considered errors)
 
     // Flags that should be executed as-is (with their original formatting)
@@ -56,7 +149,13 @@ fn get_man(cmd: &str) -> Result<String, String> {
     // Read & return exactly as entered when help flags are present
     if help_flags.iter().any(|&flag| cmd.contains(flag)) {
         let output = read_man(cmd)?;
-        return Ok(process_man_output(output));
+        return Ok(Some(process_man_output(output)));
+    }
+
+    // `3 printf` - man page from the given section only
+    if let Some((section, name)) = split_man_section(cmd) {
+        let output = read_man(&format!("MANPAGER=cat man {} {}", section, name))?;
+        return Ok((output.len() >= MIN_HELP_LENGTH).then(|| process_man_output(output)));
     }
 
     // find varians when help flags are not present
@@ -69,13 +168,220 @@ fn get_man(cmd: &str) -> Result<String, String> {
     for variant in &variants {
         match read_man(variant) {
             Ok(output) if output.len() >= MIN_HELP_LENGTH => {
-                return Ok(process_man_output(output));
+                let sections = man_sections(cmd);
+                let output = if sections.len() > 1 {
+                    format!(
+                        "Available man sections: {} (search e.g. `{} {}`)\n\n{}",
+                        sections.join(", "),
+                        sections[1],
+                        cmd,
+                        output
+                    )
+                } else {
+                    output
+                };
+                return Ok(Some(process_man_output(output)));
             }
             _ => continue, // next variant
         }
     }
 
-    Ok(format!("No valid help found for '{}'", cmd))
+    Ok(None)
+}
+
+/// split `3 printf` into section and page name
+fn split_man_section(cmd: &str) -> Option<(&str, &str)> {
+    let (section, name) = cmd.split_once(char::is_whitespace)?;
+    let name = name.trim();
+    let section_regex = regex::Regex::new(r"^([0-9][a-zA-Z]*|n|l)$").unwrap();
+    (section_regex.is_match(section) && !name.is_empty() && !name.contains(char::is_whitespace))
+        .then_some((section, name))
+}
+
+/// man sections that have a page for the command, e.g. ["1", "3"]
+fn man_sections(cmd: &str) -> Vec<String> {
+    if cmd.contains(char::is_whitespace) {
+        return Vec::new();
+    }
+    let Ok(output) = Command::new("whatis").arg("--").arg(cmd).output() else {
+        return Vec::new();
+    };
+    // printf (3)           - formatted output conversion
+    let line_regex = regex::Regex::new(r"^\S+\s+\(([^)]+)\)").unwrap();
+    let mut sections: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line_regex.captures(line).map(|caps| caps[1].to_string()))
+        .collect();
+    sections.dedup();
+    sections
+}
+
+#[tauri::command]
+async fn list_executables() -> Vec<String> {
+    path_executables()
+}
+
+const COMPLETIONS_TTL: Duration = Duration::from_secs(60); // new binaries and runs show up within a minute
+
+/// scanned at, logged commands, $PATH binaries
+type CompletionSources = (Instant, Vec<String>, Vec<String>);
+
+/// for suggest_completions, called on every keystroke
+static COMPLETIONS: Mutex<Option<CompletionSources>> = Mutex::new(None);
+
+/// completions for the command editor: binaries from $PATH and previously run commands
+#[tauri::command]
+async fn suggest_completions(prefix: String) -> Vec<String> {
+    const MAX_SUGGESTIONS: usize = 30;
+    let prefix = prefix.trim_start();
+    if prefix.is_empty() {
+        return Vec::new();
+    }
+
+    let mut cache = COMPLETIONS.lock().unwrap();
+    if cache.as_ref().is_none_or(|(scanned, _, _)| scanned.elapsed() >= COMPLETIONS_TTL) {
+        let mut runs: Vec<String> = Vec::new();
+        for run in read_logged_runs() {
+            if !runs.contains(&run.command) {
+                runs.push(run.command);
+            }
+        }
+        *cache = Some((Instant::now(), runs, path_executables()));
+    }
+    let Some((_, runs, executables)) = cache.as_ref() else {
+        return Vec::new();
+    };
+
+    let mut suggestions: Vec<String> = runs.iter().filter(|command| command.starts_with(prefix)).cloned().collect();
+    // binaries only complete the first word
+    if !prefix.contains(char::is_whitespace) {
+        suggestions.extend(executables.iter().filter(|name| name.starts_with(prefix)).cloned());
+    }
+    suggestions.truncate(MAX_SUGGESTIONS);
+    suggestions
+}
+
+/// run count and last failure per command, for sorting the settings table
+#[tauri::command]
+fn get_run_stats() -> Vec<RunStats> {
+    run_stats()
+}
+
+/// dashboard rows, flakiest commands first
+#[tauri::command]
+fn get_stats() -> Vec<RunStats> {
+    let mut stats = run_stats();
+    stats.sort_by(|a, b| {
+        b.failure_rate()
+            .total_cmp(&a.failure_rate())
+            .then(b.runs.cmp(&a.runs))
+    });
+    stats
+}
+
+/// per-command aggregate of gucli.log, in order of the newest run
+fn run_stats() -> Vec<RunStats> {
+    let mut stats: Vec<RunStats> = Vec::new();
+    // sum and count of logged durations per entry
+    let mut durations: Vec<(u64, u64)> = Vec::new();
+    // newest first: the first failure seen is the most recent one
+    for (rank, run) in read_logged_runs().into_iter().enumerate() {
+        let pos = match stats.iter().position(|s| s.command == run.command) {
+            Some(pos) => pos,
+            None => {
+                stats.push(RunStats {
+                    command: run.command.clone(),
+                    ..Default::default()
+                });
+                durations.push((0, 0));
+                stats.len() - 1
+            }
+        };
+        let entry = &mut stats[pos];
+        entry.runs += 1;
+        if !run.success {
+            entry.failures += 1;
+            if entry.failed_rank.is_none() {
+                entry.last_failed = run.time;
+                entry.failed_rank = Some(rank);
+            }
+        }
+        if let Some(ms) = run.duration_ms {
+            durations[pos].0 += ms;
+            durations[pos].1 += 1;
+        }
+    }
+    for (entry, (sum, count)) in stats.iter_mut().zip(durations) {
+        entry.avg_ms = (count > 0).then(|| sum / count);
+    }
+    stats
+}
+
+#[tauri::command]
+fn get_language() -> String {
+    language().to_string()
+}
+
+/// store the language in [settings], the tray menu is rebuilt in it
+#[tauri::command]
+async fn set_language(app: tauri::AppHandle, lang: String) -> Result<String, String> {
+    let mut config = load_commands().map_err(|e| e.to_string())?;
+    config.settings.language = gucli_shared::i18n::normalize(&lang).to_string();
+    save_commands(&config).map_err(|e| e.to_string())?;
+    apply_config(&app)?;
+    Ok(config.settings.language)
+}
+
+#[tauri::command]
+fn get_settings() -> AppSettings {
+    settings()
+}
+
+/// replace [settings] in commands.toml, the tray menu is rebuilt; ports, logging and health checks need a restart
+#[tauri::command]
+async fn set_settings(app: tauri::AppHandle, settings: AppSettings) -> Result<String, String> {
+    let mut config = load_commands().map_err(|e| e.to_string())?;
+    config.settings = settings;
+    save_commands(&config).map_err(|e| e.to_string())?;
+    apply_config(&app)?;
+    Ok("Settings saved".to_string())
+}
+
+/// `time_format` from [settings], empty = locale default
+#[tauri::command]
+fn get_time_format() -> String {
+    settings().time_format
+}
+
+/// keyword search over man page names and descriptions
+#[tauri::command]
+fn get_apropos(keyword: &str) -> Result<Vec<AproposEntry>, String> {
+    const MAX_ENTRIES: usize = 200;
+    let keyword = keyword.trim();
+    if keyword.is_empty() {
+        return Err("Enter a keyword to search for".to_string());
+    }
+
+    let output = Command::new("apropos")
+        .arg("--")
+        .arg(keyword)
+        .output()
+        .map_err(|e| format!("Failed to run apropos: {}", e))?;
+
+    // printf (1)           - format and print data
+    let line_regex = regex::Regex::new(r"^(\S+)\s+\(([^)]+)\)\s+-\s+(.*)$").unwrap();
+    let entries = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line_regex.captures(line))
+        .map(|caps| AproposEntry {
+            name: caps[1].to_string(),
+            section: caps[2].to_string(),
+            description: caps[3].trim().to_string(),
+        })
+        .take(MAX_ENTRIES)
+        .collect();
+
+    Ok(entries)
 }
 
 #[tauri::command]
@@ -98,257 +404,1170 @@ async fn get_commands() -> Result<Vec<UserCommand>, String> {
 }
 
 #[tauri::command]
-async fn set_commands(commands: Vec<UserCommand>) -> Result<String, String> {
-    let config = AppCommandsConfig { commands };
+async fn set_commands(app: tauri::AppHandle, commands: Vec<UserCommand>) -> Result<String, String> {
+    // a broken command would otherwise stop the next start
+    let toml_commands: Vec<TomlCommand> = commands.iter().map(TomlCommand::from).collect();
+    let errors: Vec<String> = command_problems(&toml_commands, &valid_shells(&settings()))
+        .into_iter()
+        .map(|p| format!("Row {}: {}", p.row + 1, p.message))
+        .collect();
+    if !errors.is_empty() {
+        return Err(format!("Not saved:\n{}", errors.join("\n")));
+    }
+    let config = AppCommandsConfig {
+        settings: settings(),
+        commands,
+        ..Default::default()
+    };
     save_commands(&config).map_err(|e| e.to_string())?;
+    apply_config(&app).map_err(|e| format!("Saved, tray not updated: {}", e))?;
     Ok("Commands saved".to_string())
 }
 
+/// all problems of the edited commands by row, the settings window shows them inline
+#[tauri::command]
+fn validate_config(commands: Vec<UserCommand>, shells: tauri::State<'_, AvailableShells>) -> Vec<ConfigProblem> {
+    let settings = settings();
+    let valid_shells = valid_shells(&settings);
+    let toml_commands: Vec<TomlCommand> = commands.iter().map(TomlCommand::from).collect();
+    let mut problems = command_problems(&toml_commands, &valid_shells);
+    problems.extend(syntax_problems(&toml_commands));
+    // valid in the file, but the tray can't run it here
+    for (row, cmd) in commands.iter().enumerate() {
+        if cmd.interactive && settings.custom_shell(&cmd.shell).is_some() {
+            problems.push(ConfigProblem {
+                warning: true,
+                ..ConfigProblem::new(row, "interactive", format!("`{}` runs with its own flag, interactive is ignored", cmd.shell))
+            });
+        }
+        if valid_shells.contains(&cmd.shell.as_str()) && !shells.0.contains(&cmd.shell) {
+            problems.push(ConfigProblem {
+                warning: true,
+                ..ConfigProblem::new(row, "shell", format!("Shell `{}` is not installed", cmd.shell))
+            });
+        }
+    }
+    problems.sort_by_key(|p| p.row);
+    problems
+}
+
 #[tauri::command]
 async fn reset_commands() -> Result<String, String> {
     set_config(Some(true)).map_err(|e| e.to_string())?;
     Ok("Settings reset to default".to_string())
 }
 
+/// why commands.toml was not loaded at startup, the app then runs without commands
+pub struct ConfigError(pub Option<String>);
+
+#[tauri::command]
+fn get_config_error(error: tauri::State<'_, ConfigError>) -> Option<String> {
+    error.0.clone()
+}
+
+#[tauri::command]
+async fn restore_commands() -> Result<String, String> {
+    restore_backup()
+}
+
 #[tauri::command]
 async fn request_restart(app: tauri::AppHandle) {
     app.restart();
 }
 
 #[tauri::command]
-async fn run_test(cmd: UserCommand) -> Result<String, String> {
-    match run_command(cmd) {
-        Ok(success) => Ok(success),
-        Err(error) => Ok(error),
+async fn run_test(app: tauri::AppHandle, cmd: UserCommand) -> TestResult {
+    // the window must not wait for it, the result comes as a notification
+    if cmd.long_running {
+        thread::spawn(move || {
+            let _ = run_command(&app, cmd);
+        });
+        return TestResult::note("started in the background, a notification follows");
     }
+    run_command(&app, cmd)
 }
 
+/// the exact invocation of a command, without running it
 #[tauri::command]
-fn get_app_info() -> Vec<String> {
-    let mut result = Vec::new();
-    result.push(format!("Version: {}", env!("CARGO_PKG_VERSION")));
-    result.push(format!("Authors: {}", env!("CARGO_PKG_AUTHORS")));
-    result.push(format!("License: {}", env!("CARGO_PKG_LICENSE")));
-    result.push(env!("CARGO_PKG_REPOSITORY").to_string());
+fn preview_command(cmd: UserCommand) -> Result<String, String> {
+    executor::preview(&cmd)
+}
 
-    result
+/// shells from SHELLS found on the system at startup, plus the custom ones from [settings]
+pub struct AvailableShells(pub Vec<String>);
+
+impl AvailableShells {
+    pub fn detect(settings: &AppSettings) -> Self {
+        AvailableShells(
+            gucli_shared::SHELLS
+                .iter()
+                .filter(|shell| find_in_path(shell).is_some())
+                .map(|shell| shell.to_string())
+                .chain(
+                    settings
+                        .shells
+                        .iter()
+                        .filter(|shell| is_executable(std::path::Path::new(&shell.path)))
+                        .map(|shell| shell.name.clone()),
+                )
+                .collect(),
+        )
+    }
 }
 
 #[tauri::command]
-async fn autostart_toggle() -> Result<String, String> {
-    let enabled = autostart_status().await.map_err(|e| {
-        error!(%e, "cannot get autostart status");
-        e
-    })?;
-    let home = get_home_dir().map_err(|e| e.to_string())?;
-    let desktop_path: std::path::PathBuf = home.join(".config/autostart/gucli.desktop");
-    if enabled {
-        // remove
-        let _ = fs::remove_file(&desktop_path);
-        Ok("autostart disabled".into())
-    } else {
-        // add
-        let exec_path = env::current_exe().map_err(|e| e.to_string())?;
-
-        if let Some(dir) = desktop_path.parent() {
-            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
-        }
-
-        let desktop_file = format!(
-            "[Desktop Entry]\n\
-            Name=Gucli\n\
-            Type=Application\n\
-            Categories=Utility\n\
-            StartupNotify=true\n\
-            Exec={}\n\
-            X-KDE-autostart-after=panel\n\
-            X-LXQt-Need-Tray=true\n\
-            X-GNOME-Autostart-enabled=true\n",
-            exec_path.display()
-        );
+fn get_available_shells(shells: tauri::State<'_, AvailableShells>) -> Vec<String> {
+    shells.0.clone()
+}
+
+/// SHELLS of this platform, the UI's own copy is built for wasm
+#[tauri::command]
+fn get_builtin_shells() -> Vec<String> {
+    gucli_shared::SHELLS.iter().map(|s| s.to_string()).collect()
+}
+
+/// DEFAULT_SHELL of this platform for a new row, the UI's own copy is built for wasm
+#[tauri::command]
+fn get_default_shell() -> String {
+    gucli_shared::DEFAULT_SHELL.to_string()
+}
 
-        fs::write(&desktop_path, desktop_file).map_err(|e| e.to_string())?;
-        Ok("autostart enabled".into())
+/// desktop color-scheme preference from the XDG settings portal: "dark", "light" or None
+#[tauri::command]
+async fn get_color_scheme() -> Option<String> {
+    let output = Command::new("gdbus")
+        .args([
+            "call",
+            "--session",
+            "--dest",
+            "org.freedesktop.portal.Desktop",
+            "--object-path",
+            "/org/freedesktop/portal/desktop",
+            "--method",
+            "org.freedesktop.portal.Settings.Read",
+            "org.freedesktop.appearance",
+            "color-scheme",
+        ])
+        .output()
+        .ok()?;
+    color_scheme(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// (<<uint32 1>>,) - 0: no preference, 1: prefer dark, 2: prefer light
+fn color_scheme(reply: &str) -> Option<String> {
+    let value_regex = regex::Regex::new(r"uint32 (\d)").unwrap();
+    match value_regex.captures(reply).map(|caps| caps[1].to_string()).as_deref() {
+        Some("1") => Some("dark".to_string()),
+        Some("2") => Some("light".to_string()),
+        _ => None,
     }
 }
 
+/// emitted with the new scheme (or null for no preference) when the desktop switches
+const COLOR_SCHEME_EVENT: &str = "color-scheme";
+
+/// follow SettingChanged of the portal, so an open window flips with the desktop at once
+fn watch_color_scheme(app: tauri::AppHandle) {
+    use std::io::BufRead;
+    #[cfg(target_os = "linux")]
+    use std::os::unix::process::CommandExt;
+    thread::spawn(move || {
+        let mut monitor = Command::new("gdbus");
+        monitor
+            .args([
+                "monitor",
+                "--session",
+                "--dest",
+                "org.freedesktop.portal.Desktop",
+                "--object-path",
+                "/org/freedesktop/portal/desktop",
+            ])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null());
+        // ends with this thread, not left behind after quit; PR_SET_PDEATHSIG is Linux only
+        #[cfg(target_os = "linux")]
+        unsafe {
+            monitor.pre_exec(|| {
+                nix::libc::prctl(nix::libc::PR_SET_PDEATHSIG, nix::libc::SIGTERM);
+                Ok(())
+            });
+        }
+        let mut child = match monitor.spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                debug!("Color scheme not followed, gdbus: {}", e);
+                return;
+            }
+        };
+        let Some(stdout) = child.stdout.take() else {
+            return;
+        };
+        for line in std::io::BufReader::new(stdout).lines().map_while(Result::ok) {
+            if line.contains("'org.freedesktop.appearance', 'color-scheme'") {
+                let scheme = color_scheme(&line);
+                debug!("Desktop color scheme changed: {:?}", scheme);
+                if let Err(e) = app.emit(COLOR_SCHEME_EVENT, scheme) {
+                    debug!("Color scheme event not sent: {}", e);
+                }
+            }
+        }
+        let _ = child.wait();
+    });
+}
+
 #[tauri::command]
-async fn autostart_status() -> Result<bool, String> {
-    let autostart_file = get_home_dir()
-        .map_err(|e| e.to_string())?
-        .join(".config/autostart/gucli.desktop");
+fn get_app_info() -> Vec<String> {
+    let mut result = Vec::new();
+    result.push(format!("Version: {}", env!("CARGO_PKG_VERSION")));
+    result.push(format!("Authors: {}", env!("CARGO_PKG_AUTHORS")));
+    result.push(format!("License: {}", env!("CARGO_PKG_LICENSE")));
+    result.push(env!("CARGO_PKG_REPOSITORY").to_string());
 
-    Ok(autostart_file.exists())
+    result
 }
 
 pub fn run() {
+    let listener = match instance::activation_socket() {
+        Some(listener) => {
+            info!("Started by systemd socket activation");
+            Some(listener)
+        }
+        None => instance::bind_socket(),
+    };
+    // `gucli --settings` etc. when no instance was running yet
+    let startup_intent = instance::intent_from_args(env::args().skip(1)).unwrap_or_else(|usage| {
+        eprintln!("{}", usage);
+        std::process::exit(2);
+    });
+
     if let Err(e) = set_config(None) {
         error!("Failed to init config: {}", e);
         std::process::exit(1);
     }
-    let commands_config = load_commands().unwrap_or_else(|err| {
-        error!("Failed to load commands: {}", err);
-        std::process::exit(1);
-    });
+    // a broken file is shown in the settings, the tray starts without commands
+    let (commands_config, config_error) = match load_commands() {
+        Ok(config) => (config, None),
+        Err(err) => {
+            error!("Failed to load commands: {}", err);
+            send_notification(
+                "Err( commands.toml not loaded )",
+                &format!("{}\nFix it or restore the backup in the settings", err),
+            );
+            (AppCommandsConfig::default(), Some(err.to_string()))
+        }
+    };
+
+    if !commands_config.fixes.is_empty() {
+        send_notification(
+            "Warn( commands.toml repaired )",
+            &format!("{}\nSave the settings to write the fixes", commands_config.fixes.join("\n")),
+        );
+    }
+
+    let shells = AvailableShells::detect(&commands_config.settings);
+    for cmd in &commands_config.commands {
+        if !shells.0.contains(&cmd.shell) {
+            error!("Shell '{}' of command `{}` is not installed", cmd.shell, cmd.command);
+        }
+    }
 
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
-        .setup(|app| {
-
-            // tray menu
-            let settings = MenuItem::with_id(app, "settings", "⚙️   Settings", true, None::<&str>)?;
-            let restart = MenuItem::with_id(app, "restart", "🔃   Restart", true, None::<&str>)?;
-            let quit = MenuItem::with_id(app, "quit", "✝️   Quit", true, None::<&str>)?;
+        .plugin(tauri_plugin_dialog::init())
+        .manage(ManCache::default())
+        .manage(ExecutionRegistry::default())
+        .manage(Children::default())
+        .manage(log_tail::LogFollow::default())
+        .manage(shells)
+        .manage(ConfigError(config_error))
+        .manage(Commands(RwLock::new(commands_config.clone())))
+        .setup(move |app| {
+            // --hidden, or start_hidden for a plain launch: an explicit --settings still opens the window
+            let hidden = match startup_intent.as_deref() {
+                Some(intent) => intent == "hidden",
+                None => commands_config.settings.start_hidden,
+            };
+            // the notification about it was sent already
+            if app.state::<ConfigError>().0.is_some() && !hidden {
+                open_settings(app.handle());
+            }
 
-            let mut menu_items = Vec::new();
-            for cmd in &commands_config.commands {
-                let item = MenuItem::with_id(
-                    app,
-                    format!("cmd_{}", cmd.id),
-                    cmd.icon.clone() + &String::from("    ") + &cmd.command,
-                    true,
-                    None::<&str>,
-                )?;
-                menu_items.push(item);
-            }
-
-            let mut builder = MenuBuilder::new(app);
-            for item in menu_items {
-                builder = builder.item(&item);
-            }
-            let menu = builder
-                .separator()
-                .item(&settings)
-                .item(&restart)
-                .item(&quit)
-                .build()?;
-
-            TrayIconBuilder::with_id("main")
-                .icon(app.default_window_icon().unwrap().clone())
-                .menu(&menu)
-                .on_menu_event(move |app, event| match event.id.as_ref() {
-                    "settings" => open_settings(app),
-                    "restart" => app.restart(),
-                    "quit" => app.exit(0),
-                    id if id.starts_with("cmd_") => {
-                        let cmd_id = id.replace("cmd_", "");
-                        if let Some(cmd) = commands_config
-                            .commands
-                            .iter()
-                            .find(|c| c.id == cmd_id)
-                        {
-                            let _ = run_command(cmd.clone());
-                        }
+            if let Some(listener) = listener {
+                instance::serve(app.handle().clone(), listener);
+            }
+            shutdown::watch_signals(app.handle().clone());
+            if hidden {
+                info!("Started hidden, tray only");
+            }
+            match startup_intent.as_deref() {
+                Some("settings") => open_settings(app.handle()),
+                Some(intent) => {
+                    if let Some(name) = intent.strip_prefix("run ")
+                        && let Some(cmd) = commands_config.commands.iter().find(|c| c.command == name || c.label == name)
+                    {
+                        spawn_command(app.handle(), cmd.clone());
                     }
-                    _ => {}
-                })
-                .build(app)?;
+                }
+                None => {}
+            }
+
+            // no tray to show the commands in: a window with them instead
+            match create_tray(app.handle(), &commands_config) {
+                Ok(()) if tray_watch::host_missing() => {
+                    warn!("No StatusNotifier host, opening the launcher window");
+                    launcher::open(app.handle());
+                }
+                Ok(()) => {}
+                Err(e) => {
+                    error!("Tray icon not created: {}", e);
+                    launcher::open(app.handle());
+                }
+            }
+
+            // version, commands and the last run from gucli.log until the first run here
+            let host = hostname();
+            let last = read_logged_runs().into_iter().next().map(|run| {
+                let name = commands_config
+                    .commands
+                    .iter()
+                    .find(|c| c.command == run.command)
+                    .map_or(run.command.clone(), |c| c.display_name().to_string());
+                (name, run.success, short_time(&run.time))
+            });
+            tooltip::update(app.handle(), |status| {
+                status.commands = commands_config.commands.iter().filter(|c| c.available_on(&host)).count();
+                status.last = last;
+            });
+
+            // `run_on_start`: only once the tray can show their notifications and badge
+            for cmd in commands_config.commands.iter().filter(|c| c.run_on_start && c.available_on(&host)) {
+                info!("Running `{}` on start", cmd.command);
+                spawn_command(app.handle(), cmd.clone());
+            }
+
+            // after the tray exists, failing checks badge its icon
+            health::start(app.handle().clone(), &commands_config);
+            http::start(app.handle().clone(), &commands_config);
+            watch_color_scheme(app.handle().clone());
+            tray_watch::start(app.handle().clone());
+            updates::start(&commands_config);
+            autostart::repair();
+            if let Err(e) = autostart::write_launcher(&commands_config.commands) {
+                error!("Launcher not updated: {}", e);
+            }
 
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             get_commands,
             set_commands,
+            validate_config,
             reset_commands,
+            get_config_error,
+            restore_commands,
             run_test,
+            preview_command,
+            units::export_unit,
+            encryption::is_commands_encrypted,
+            encryption::set_commands_encrypted,
             request_restart,
             ctrl_window,
-            autostart_toggle,
-            autostart_status,
+            autostart::autostart_toggle,
+            autostart::autostart_status,
+            autostart::get_autostart_backend,
+            autostart::set_autostart_backend,
+            autostart::get_autostart_delay,
+            autostart::set_autostart_delay,
+            updates::check_update,
+            diagnostics::collect_diagnostics,
+            launcher::launch_command,
+            log_tail::read_log,
+            log_tail::follow_log,
+            launcher::show_settings,
             get_man,
+            get_apropos,
+            list_executables,
+            suggest_completions,
+            get_run_stats,
+            get_stats,
+            get_time_format,
+            get_language,
+            get_settings,
+            set_settings,
+            set_language,
+            get_hostname,
             get_app_info,
+            get_available_shells,
+            get_builtin_shells,
+            get_default_shell,
+            get_color_scheme,
+            selftest::run_self_test,
+            registry::get_running,
+            children::get_processes,
+            children::kill_process,
+            registry::get_last_result,
             open_file
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| match event {
+            // no webview lives at idle: closing the last window must not stop the tray core
+            tauri::RunEvent::ExitRequested { code: None, api, .. } => api.prevent_exit(),
+            // tray Quit, the quit guard and signals all end up here
+            tauri::RunEvent::Exit => shutdown::run_hook(&commands(app).settings),
+            _ => {}
+        });
 }
 
-fn open_settings<R: Runtime>(app: &tauri::AppHandle<R>) {
-    // Closing the window if it is open
+/// the "main" tray icon with the menu of config, again after the tray host restarted
+pub(crate) fn create_tray(app: &tauri::AppHandle, config: &AppCommandsConfig) -> tauri::Result<()> {
+    let menu = build_tray_menu(app, config)?;
+    let left_click = config.settings.left_click.as_str();
+
+    // handlers look the commands up in Commands, saving replaces them there
+    TrayIconBuilder::with_id("main")
+        .icon(app.default_window_icon().unwrap().clone())
+        .menu(&menu)
+        .show_menu_on_left_click(matches!(left_click, "" | "menu"))
+        .on_tray_icon_event(move |tray, event| {
+            let app = tray.app_handle();
+            let left_click = commands(app).settings.left_click;
+            match event {
+                TrayIconEvent::Click {
+                    button: MouseButton::Left,
+                    button_state: MouseButtonState::Up,
+                    ..
+                } => match left_click.as_str() {
+                    "settings" => open_settings(app),
+                    "default" => run_default(app, &commands(app)),
+                    _ => {}
+                },
+                // a single click already runs it with left_click = "default"
+                TrayIconEvent::DoubleClick {
+                    button: MouseButton::Left,
+                    ..
+                } if left_click != "default" => run_default(app, &commands(app)),
+                _ => {}
+            }
+        })
+        .on_menu_event(move |app, event| match event.id.as_ref() {
+            "settings" => open_settings(app),
+            "restart" => app.restart(),
+            "debug_log" => match log_level::toggle_debug() {
+                Ok(level) => send_notification("Ok( Logging )", &format!("Log level: {}", level)),
+                Err(e) => error!("Log level not changed: {}", e),
+            },
+            "quit" => {
+                // the guard may wait for an answer, keep the menu responsive
+                let app = app.clone();
+                thread::spawn(move || children::quit_guarded(&app, &app.state::<Children>()));
+            }
+            id if id.starts_with("cmd_") => {
+                let cmd_id = id.replace("cmd_", "");
+                if let Some(cmd) = commands(app).commands.into_iter().find(|c| c.id == cmd_id) {
+                    spawn_command(app, cmd);
+                }
+            }
+            id if id.starts_with("recent_") => {
+                if let Ok(run_id) = id["recent_".len()..].parse::<u64>()
+                    && let Some(run) = app.state::<ExecutionRegistry>().run(run_id)
+                {
+                    open_output(app, &run);
+                }
+            }
+            id if id.starts_with("term_") => {
+                let cmd_id = id.replace("term_", "");
+                if let Some(cmd) = commands(app).commands.into_iter().find(|c| c.id == cmd_id)
+                    && let Err(e) = terminal::open_terminal_at(&cmd.cwd)
+                {
+                    error!("Terminal for `{}` failed: {}", cmd.command, e);
+                    send_notification("Err( Terminal not opened )", &e);
+                }
+            }
+            _ => {}
+        })
+        .build(app)?;
+    Ok(())
+}
+
+/// tray menu: user commands, separator, app items
+pub(crate) fn build_tray_menu<R: Runtime, M: Manager<R>>(
+    app: &M,
+    config: &AppCommandsConfig,
+) -> tauri::Result<Menu<R>> {
+    let lang = language();
+    let settings = MenuItem::with_id(app, "settings", tr(lang, "tray.settings"), true, None::<&str>)?;
+    let restart = MenuItem::with_id(app, "restart", tr(lang, "tray.restart"), true, None::<&str>)?;
+    let quit = MenuItem::with_id(app, "quit", tr(lang, "tray.quit"), true, None::<&str>)?;
+    let debug_log = CheckMenuItem::with_id(
+        app,
+        "debug_log",
+        tr(lang, "tray.debug_log"),
+        true,
+        log_level::is_debug(&log_level::current()),
+        None::<&str>,
+    )?;
+
+    let mut menu_items: Vec<Box<dyn IsMenuItem<R>>> = Vec::new();
+    // one synced commands.toml for several machines: skip other hosts' commands
+    let host = hostname();
+    let ordered: Vec<&UserCommand> = gucli_shared::tray_order(&config.commands)
+        .into_iter()
+        .filter(|c| c.available_on(&host))
+        .collect();
+    let pinned = ordered.iter().filter(|c| c.pinned).count();
+    // entries show the last result, refreshed by run_command
+    app.manage(TrayLabels::default());
+    let labels = app.state::<TrayLabels>();
+    let mut labels = labels.0.lock().unwrap_or_else(|e| e.into_inner());
+    labels.clear();
+    let mut last_runs: HashMap<String, (bool, String)> = HashMap::new();
+    for run in read_logged_runs() {
+        last_runs.entry(run.command).or_insert_with(|| (run.success, short_time(&run.time)));
+    }
+    for cmd in &ordered {
+        let id = format!("cmd_{}", cmd.id);
+        let suffix = last_runs
+            .get(&cmd.command)
+            .map(|(success, time)| status_suffix(*success, time))
+            .unwrap_or_default();
+        if icons::is_image(&cmd.icon) {
+            match icons::load(&cmd.icon) {
+                Ok(image) => {
+                    let text = menu_text(cmd);
+                    let item = IconMenuItem::with_id(app, id, text.clone() + &suffix, true, Some(image), None::<&str>)?;
+                    let relabel = item.clone();
+                    let setter: Relabel = Box::new(move |text: &str| relabel.set_text(text).is_ok());
+                    labels.insert(cmd.id.clone(), (text, setter));
+                    menu_items.push(Box::new(item));
+                    continue;
+                }
+                // the file may be gone since validation, keep the entry without icon
+                Err(e) => error!("{}", e),
+            }
+        }
+        let text = if icons::is_image(&cmd.icon) {
+            menu_text(cmd)
+        } else {
+            cmd.icon.clone() + &String::from("    ") + &menu_text(cmd)
+        };
+        let item = MenuItem::with_id(app, id, text.clone() + &suffix, true, None::<&str>)?;
+        let relabel = item.clone();
+        let setter: Relabel = Box::new(move |text: &str| relabel.set_text(text).is_ok());
+        labels.insert(cmd.id.clone(), (text, setter));
+        menu_items.push(Box::new(item));
+    }
+    drop(labels);
+
+    let mut builder = MenuBuilder::new(app);
+    for (n, item) in menu_items.iter().enumerate() {
+        if n == pinned && n > 0 {
+            builder = builder.separator();
+        }
+        builder = builder.item(item.as_ref());
+    }
+
+    // last runs, refilled by run_command
+    let recent = SubmenuBuilder::with_id(app, "recent", tr(lang, "tray.recent")).build()?;
+    let finished = app.try_state::<ExecutionRegistry>().map(|runs| runs.finished()).unwrap_or_default();
+    fill_recent(&recent, &finished)?;
+    let submenu = recent.clone();
+    let refill: Refill = Box::new(move |runs: &[Execution]| fill_recent(&submenu, runs).is_ok());
+    app.manage(RecentMenu::default());
+    *app.state::<RecentMenu>().0.lock().unwrap_or_else(|e| e.into_inner()) = Some(refill);
+    builder = builder.separator().item(&recent);
+
+    // terminals at the working directories, to follow up on a command manually
+    let with_cwd: Vec<&UserCommand> = ordered.iter().copied().filter(|c| !c.cwd.trim().is_empty()).collect();
+    if !with_cwd.is_empty() {
+        let mut terminals = SubmenuBuilder::with_id(app, "terminals", tr(lang, "tray.terminal_at"));
+        for cmd in with_cwd {
+            terminals = terminals.text(format!("term_{}", cmd.id), format!("{}   ({})", cmd.cwd, cmd.display_name()));
+        }
+        builder = builder.separator().item(&terminals.build()?);
+    }
+
+    builder
+        .separator()
+        .item(&settings)
+        .item(&debug_log)
+        .item(&restart)
+        .item(&quit)
+        .build()
+}
+
+/// entries in the "Recent results" submenu
+const RECENT_RUNS: usize = 5;
+
+type Refill = Box<dyn Fn(&[Execution]) -> bool + Send + Sync>;
+
+/// the tray's "Recent results" submenu, refilled after each run
+#[derive(Default)]
+pub struct RecentMenu(Mutex<Option<Refill>>);
+
+/// newest finished runs as `recent_<run id>` entries, a disabled placeholder before the first one
+fn fill_recent<R: Runtime>(menu: &Submenu<R>, runs: &[Execution]) -> tauri::Result<()> {
+    for item in menu.items()? {
+        menu.remove(&item)?;
+    }
+    if runs.is_empty() {
+        let text = tr(language(), "tray.recent_none");
+        return menu.append(&MenuItem::with_id(menu.app_handle(), "recent_none", text, false, None::<&str>)?);
+    }
+    for run in runs.iter().take(RECENT_RUNS) {
+        let mut command: String = run.command.chars().take(40).collect();
+        if command.len() < run.command.len() {
+            command.push('…');
+        }
+        let text = format!(
+            "{} {}   {}",
+            if run.success { "✓" } else { "✗" },
+            command.replace('&', "&&"),
+            short_time(&run.finished)
+        );
+        let item = MenuItem::with_id(menu.app_handle(), format!("recent_{}", run.run_id), text, true, None::<&str>)?;
+        menu.append(&item)?;
+    }
+    Ok(())
+}
+
+fn refill_recent(app: &tauri::AppHandle) {
+    if let Some(recent) = app.try_state::<RecentMenu>()
+        && let Some(refill) = recent.0.lock().unwrap_or_else(|e| e.into_inner()).as_ref()
+        && !refill(&app.state::<ExecutionRegistry>().finished())
+    {
+        debug!("Recent results not refilled");
+    }
+}
+
+/// a finished run's whole output in the default text viewer
+fn open_output(app: &tauri::AppHandle, run: &Execution) {
+    let text = format!(
+        "{}\n{} - {}, {}\n\n{}",
+        run.command,
+        run.started,
+        run.finished,
+        if run.success { "Ok" } else { "Err" },
+        run.output
+    );
+    let opened = write_full_output(run.run_id, &text)
+        .and_then(|path| app.opener().open_path(path.to_string_lossy(), None::<&str>).map_err(|e| e.to_string()));
+    if let Err(e) = opened {
+        error!("Output of `{}` not opened: {}", run.command, e);
+        send_notification("Err( Output not opened )", &e);
+    }
+}
+
+type Relabel = Box<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// tray entries of the commands by id: label without status and a setter for the full text
+#[derive(Default)]
+pub struct TrayLabels(Mutex<HashMap<String, (String, Relabel)>>);
+
+/// "✓ 14:02" / "✗ 14:02" after a tray entry
+fn status_suffix(success: bool, time: &str) -> String {
+    format!("   {} {}", if success { "✓" } else { "✗" }, time)
+}
+
+/// hours and minutes out of a log timestamp in any `time_format`, the whole stamp if there are none
+fn short_time(stamp: &str) -> String {
+    let time_regex = regex::Regex::new(r"\b(\d{1,2}:\d{2})").unwrap();
+    time_regex
+        .captures(stamp)
+        .map(|caps| caps[1].to_string())
+        .unwrap_or_else(|| stamp.trim().to_string())
+}
+
+/// mark the command's tray entry and the tooltip with the result of the run that just ended
+fn set_tray_status(app: &tauri::AppHandle, cmd: &UserCommand, success: bool) {
+    let time = chrono::Local::now().format("%H:%M").to_string();
+    tooltip::update(app, |status| status.last = Some((cmd.display_name().to_string(), success, time.clone())));
+    let Some(labels) = app.try_state::<TrayLabels>() else {
+        return;
+    };
+    let labels = labels.0.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((text, relabel)) = labels.get(&cmd.id)
+        && !relabel(&(text.clone() + &status_suffix(success, &time)))
+    {
+        debug!("Tray entry of `{}` not relabelled", cmd.command);
+    }
+}
+
+/// menu label with `&` before the mnemonic (underlined where supported), literal `&` doubled
+fn menu_text(cmd: &UserCommand) -> String {
+    let text = cmd.display_name().replace('&', "&&");
+    let Some(key) = cmd.mnemonic.trim().chars().next() else {
+        return text;
+    };
+    match text.char_indices().find(|(_, c)| c.to_lowercase().eq(key.to_lowercase())) {
+        Some((pos, _)) if key != '&' => format!("{}&{}", &text[..pos], &text[pos..]),
+        // key not in the text: prefix it, e.g. "&v  nmcli ..."
+        _ => format!("&{}  {}", key, text),
+    }
+}
+
+pub(crate) fn open_settings<R: Runtime>(app: &tauri::AppHandle<R>) {
+    // an open window is brought to front, it is closed only from its own titlebar
     if let Some(window) = app.get_webview_window("settings") {
-        window.close().unwrap();
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
     } else {
-        // Creating a new window
+        // Creating a new window with the geometry it had when last closed
+        let state = window_state::load();
         let _window =
             tauri::WebviewWindowBuilder::new(app, "settings", tauri::WebviewUrl::App("/".into()))
                 .title("Gucli settings")
-                .inner_size(800.0, 600.0)
+                .inner_size(state.width, state.height)
                 .transparent(true)
                 .decorations(false)
-                .visible(true)
+                .visible(false)
                 .build()
                 .unwrap();
+        // a monitor may have been unplugged since, then the WM places the window
+        if let Some((x, y)) = window_state::visible_position(&_window, &state) {
+            let _ = _window.set_position(tauri::LogicalPosition::new(x, y));
+        }
+        if state.maximized {
+            let _ = _window.maximize();
+        }
+        let window = _window.clone();
+        _window.on_window_event(move |event| {
+            if let tauri::WindowEvent::CloseRequested { .. } = event {
+                window_state::save(&window);
+            }
+        });
+        _window.show().unwrap();
         _window.set_focus().unwrap();
     }
 }
 
-fn run_command(cmd: UserCommand) -> Result<String, String> {
+/// run a command picked in the tray off the UI thread: confirmation and execution may take a while
+pub(crate) fn spawn_command(app: &tauri::AppHandle, cmd: UserCommand) {
+    let app = app.clone();
+    thread::spawn(move || {
+        if !cmd.confirm || confirm::ask(&cmd.command) {
+            let _ = run_command(&app, cmd);
+        }
+    });
+}
+
+/// the command marked `default = true`
+fn run_default(app: &tauri::AppHandle, config: &AppCommandsConfig) {
+    match config.commands.iter().find(|c| c.default) {
+        Some(cmd) => spawn_command(app, cmd.clone()),
+        None => send_notification("Warn( No default command )", "Mark a command as default in the settings"),
+    }
+}
+
+fn run_command(app: &tauri::AppHandle, cmd: UserCommand) -> TestResult {
     debug!("Executing command: {}", &cmd.command);
-    let result = execute_command(cmd.clone());
+    let runs = app.state::<ExecutionRegistry>();
+    // a double click must not start the same command twice
+    let lane = runs.lane(&cmd.command);
+    let _in_flight = match cmd.on_busy.as_str() {
+        "parallel" => None,
+        "queue" => Some(lane.lock().unwrap_or_else(|e| e.into_inner())),
+        _ => match lane.try_lock() {
+            Ok(guard) => Some(guard),
+            Err(std::sync::TryLockError::Poisoned(e)) => Some(e.into_inner()),
+            Err(std::sync::TryLockError::WouldBlock) => {
+                info!("Command `{}` skipped, already running", cmd.command);
+                return TestResult::note("skipped, already running");
+            }
+        },
+    };
+    // every path into a run (tray, test button, socket, HTTP) passes here
+    if let Some(reason) = gucli_shared::lint::dangerous(&cmd.command)
+        && !confirm::ask_dangerous(&cmd.command, reason)
+    {
+        return TestResult::note("skipped, not confirmed");
+    }
+    // a terminal has its own stdin, the marker is just dropped there
+    let input = if prompt::wants_stdin(&cmd.command) && !cmd.in_terminal {
+        match prompt::ask_text(&cmd.command) {
+            Ok(Some(text)) => Some(text),
+            Ok(None) => {
+                info!("Command `{}` skipped, no input", cmd.command);
+                return TestResult::note("skipped, no input");
+            }
+            Err(e) => {
+                send_notification("Err( No input dialog )", &e);
+                return TestResult { stderr: e, ..Default::default() };
+            }
+        }
+    } else {
+        None
+    };
+    // cmd keeps the placeholders: it names the run in the log, stats and registry
+    let mut exec_cmd = cmd.clone();
+    if prompt::wants_path(&cmd.command) {
+        match prompt::fill_paths(app, &cmd.command) {
+            Ok(Some(command)) => exec_cmd.command = command,
+            Ok(None) => {
+                info!("Command `{}` skipped, no path chosen", cmd.command);
+                return TestResult::note("skipped, no path chosen");
+            }
+            Err(e) => return TestResult { stderr: e, ..Default::default() },
+        }
+    }
+    // values only in the environment of the run, the command text gets "$GUCLI_PASSWORD"
+    let mut passwords = Vec::new();
+    if prompt::wants_password(&cmd.command) {
+        // the variable only exists in the local process: not over ssh, in a container or through flatpak-spawn
+        let local = matches!(cmd.executor.as_str(), "" | "local") && cmd.host.trim().is_empty();
+        if cmd.in_terminal || !local || flatpak::active() {
+            return TestResult {
+                stderr: "{password:…} works only for local commands outside in_terminal and Flatpak".to_string(),
+                ..Default::default()
+            };
+        }
+        match prompt::fill_passwords(&exec_cmd.command) {
+            Ok(Some((command, values))) => {
+                exec_cmd.command = command;
+                passwords = values;
+            }
+            Ok(None) => {
+                info!("Command `{}` skipped, no password entered", cmd.command);
+                return TestResult::note("skipped, no password entered");
+            }
+            Err(e) => {
+                send_notification("Err( No password dialog )", &e);
+                return TestResult { stderr: e, ..Default::default() };
+            }
+        }
+    }
+    // one at a time per queue, the wait shows in the tray tooltip
+    let _turn = (!cmd.queue.trim().is_empty()).then(|| {
+        let group = cmd.queue.trim();
+        show_queue(app, runs.queued() + 1);
+        let turn = runs.enter_queue(group);
+        show_queue(app, runs.queued());
+        turn
+    });
+    let started = Instant::now();
+    let execution = runs.start(&cmd);
+    registry::notify(app, &execution);
+    // a terminal window is feedback enough
+    let progress = (!cmd.in_terminal).then(|| progress::Progress::start(&cmd.command));
+
+    let (result, exit_code, stderr) = if cmd.in_terminal {
+        // the exit code stays in the terminal window
+        audit::record(&exec_cmd.command, "outcome=terminal");
+        (terminal::run_in_terminal(&exec_cmd), None, String::new())
+    } else {
+        match execute_output(exec_cmd, input, passwords, &app.state::<Children>()) {
+            Ok(finished) => (finished.result(&cmd), finished.status.code(), finished.stderr),
+            Err(e) => (Err(e), None, String::new()),
+        }
+    };
+    let result = result.map_err(|err| match confinement::explain(&err) {
+        // hint first, so it survives the notification length limit
+        Some(hint) => format!("Hint: {}\n{}", hint, err),
+        None => err,
+    })
+    // "it ran but my screens are wrong": keep what the desktop looked like
+    .map_err(|err| match cmd.gui.then(|| screenshot::capture(&cmd.command)) {
+        Some(Ok(path)) => format!("{}\nScreenshot: {}", err, path.display()),
+        Some(Err(e)) => format!("{}\n{}", err, e),
+        None => err,
+    });
+    if let Some(finished) = runs.finish(execution.run_id, &result) {
+        registry::notify(app, &finished);
+    }
+    if let Ok(output) = &result
+        && !cmd.in_terminal
+        && !cmd.output_file.trim().is_empty()
+        && let Err(e) = write_output(&cmd.output_file, output)
+    {
+        error!("Output of `{}` not written: {}", cmd.command, e);
+    }
 
+    // Ok(/Err( markers stay untranslated, the settings window colors the status by them
+    let lang = language();
     let (is_success, message) = match &result {
         Ok(output) => (
             true,
-            format!("Ok( Command `{}` executed ), Result:\n {}", &cmd.command, &output),
+            format!(
+                "Ok( {} ), {}:\n {}",
+                tr(lang, "notify.executed").replace("{}", &cmd.command),
+                tr(lang, "notify.result"),
+                &output
+            ),
         ),
         Err(err) => (
             false,
-            format!("Err( Command `{}` failed ), Error:\n {}", &cmd.command, &err),
+            format!(
+                "Err( {} ), {}:\n {}",
+                if executor::is_connection_error(err) {
+                    tr(lang, "notify.unreachable").replace("{}", &cmd.host)
+                } else {
+                    tr(lang, "notify.failed").replace("{}", &cmd.command)
+                },
+                tr(lang, "notify.error"),
+                &err
+            ),
         ),
     };
 
-    // push to log
-    match result {
+    // push to log, the duration feeds the statistics tab
+    let elapsed = started.elapsed().as_millis();
+    match &result {
         Ok(val) => info!(
-            "Command `{}` executed, Result: {}",&cmd.command,val.replace("\n", " ")
+            "Command `{}` executed in {} ms, Result: {}",&cmd.command,elapsed,val.replace("\n", " ")
         ),
-        Err(err) => error!("Command `{}` failed, Error: {}", &cmd.command, err),
+        Err(err) => error!("Command `{}` failed in {} ms, Error: {}", &cmd.command, elapsed, err),
     }
+    set_tray_status(app, &cmd, is_success);
+    refill_recent(app);
 
-    // send notification if fail or enable sn
-    if !is_success || cmd.sn {
+    // send notification if fail or enable sn, long running commands always report
+    if !is_success || cmd.sn || cmd.long_running {
         let (summary, body) = message.split_at(message.find('\n').unwrap_or(message.len()));
-        let limited_body = if body.chars().count() > 200 {
-            format!("{}...", body.chars().take(200).collect::<String>())
+        let limit = cmd.notify_length.or(settings().notify_length).unwrap_or(NOTIFY_LENGTH).max(1);
+        // icon tells apart results of different commands in a busy notification list
+        let summary = if cmd.icon.trim().is_empty() || icons::is_image(&cmd.icon) {
+            summary.to_string()
         } else {
-            body.to_string()
+            format!("{} {}", cmd.icon.trim(), summary)
         };
-        send_notification(summary, &limited_body);
+        match cmd.notify_cooldown {
+            // coalesce repeats of the same text, e.g. a monitor command failing every minute
+            Some(secs) => match runs.should_notify(&cmd.command, &message, Duration::from_secs(secs)) {
+                Some(0) => send_output_notification(app, execution.run_id, progress.as_ref(), &summary, body, limit, ""),
+                Some(held) => send_output_notification(
+                    app,
+                    execution.run_id,
+                    progress.as_ref(),
+                    &summary,
+                    body,
+                    limit,
+                    &format!("\n(+{} same within {} s)", held, secs),
+                ),
+                None => debug!("Notification of `{}` held back by cooldown", cmd.command),
+            },
+            None => send_output_notification(app, execution.run_id, progress.as_ref(), &summary, body, limit, ""),
+        }
+    }
+
+    let (stdout, stderr) = match result {
+        Ok(stdout) => (stdout, stderr),
+        Err(err) => (String::new(), err),
+    };
+    let result = TestResult {
+        success: is_success,
+        exit_code,
+        stdout,
+        stderr,
+        duration_ms: elapsed as u64,
+        note: String::new(),
+    };
+    // an open settings window shows tray runs in its status too
+    let executed = CommandExecuted { command: cmd.command.clone(), result: result.clone() };
+    if let Err(e) = app.emit(COMMAND_EXECUTED_EVENT, executed) {
+        debug!("Command event not sent: {}", e);
+    }
+    result
+}
+
+/// event emitted with a CommandExecuted when a run has ended
+pub const COMMAND_EXECUTED_EVENT: &str = "command-executed";
+
+/// characters of output in a notification when `notify_length` is not set
+const NOTIFY_LENGTH: usize = 200;
+
+// threads parked in wait_for_action for a "view" click; more cut notifications get the path in the body
+const MAX_WAITING_ACTIONS: usize = 8;
+static WAITING_ACTIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// result notification cut at `limit` characters, a cut one offers to open the whole output.
+/// Replaces the run's "running…" notification when one is shown
+fn send_output_notification(
+    app: &tauri::AppHandle,
+    run_id: u64,
+    progress: Option<&progress::Progress>,
+    summary: &str,
+    body: &str,
+    limit: usize,
+    note: &str,
+) {
+    let truncated = body.chars().count() > limit;
+    let text = if truncated {
+        format!("{}…{}", body.chars().take(limit).collect::<String>(), note)
+    } else {
+        format!("{}{}", body, note)
+    };
+    let path = match truncated.then(|| write_full_output(run_id, body.trim_start())) {
+        Some(Ok(path)) => Some(path),
+        Some(Err(e)) => {
+            error!("Full output not saved: {}", e);
+            None
+        }
+        None => None,
+    };
+    // the portal has no action callback here: the path of the full output goes in the body
+    if flatpak::active() {
+        let text = match &path {
+            Some(path) => format!("{}\n{}", text, path.display()),
+            None => text,
+        };
+        // withdraws the "running…" notification
+        if let Some(progress) = progress {
+            progress.take();
+        }
+        if let Err(e) = flatpak::notify(summary, &text) {
+            error!("Notification failed: {} - {}. Error: {}", summary, text, e);
+        }
+        return;
+    }
+    let waiting = path.is_some()
+        && WAITING_ACTIONS
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| (n < MAX_WAITING_ACTIONS).then_some(n + 1))
+            .is_ok();
+    let text = match &path {
+        Some(path) if !waiting => format!("{}\n{}", text, path.display()),
+        _ => text,
+    };
+    let mut notification = Notification::new();
+    notification
+        .summary(summary)
+        .body(&text)
+        .appname("gucli-notification")
+        .icon("system");
+    if let Some(id) = progress.and_then(|p| p.take()) {
+        notification.id(id);
+    }
+    if waiting {
+        notification.action("view", tr(language(), "notify.view_full"));
+    }
+    match notification.show() {
+        // waits until the notification is clicked or closed
+        Ok(handle) => {
+            if let Some(path) = path.filter(|_| waiting) {
+                let app = app.clone();
+                thread::spawn(move || {
+                    handle.wait_for_action(|action| {
+                        if action == "view"
+                            && let Err(e) = app.opener().open_path(path.to_string_lossy(), None::<&str>)
+                        {
+                            error!("Full output not opened: {}", e);
+                        }
+                    });
+                    WAITING_ACTIONS.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+        }
+        Err(e) => {
+            if waiting {
+                WAITING_ACTIONS.fetch_sub(1, Ordering::SeqCst);
+            }
+            error!("Notification failed: {} - {}. Error: {}", summary, text, e);
+        }
+    }
+}
+
+/// waiting queued runs in the tray tooltip
+fn show_queue(app: &tauri::AppHandle, queued: u64) {
+    tooltip::update(app, |status| status.queued = queued);
+}
+
+pub(crate) fn execute_command(cmd: UserCommand, children: &Children) -> Result<String, String> {
+    execute_with_input(cmd, None, children)
+}
+
+/// run with `input` piped into stdin, for {stdin} commands
+pub(crate) fn execute_with_input(cmd: UserCommand, input: Option<String>, children: &Children) -> Result<String, String> {
+    let failed = cmd.clone();
+    execute_output(cmd, input, Vec::new(), children)?.result(&failed)
+}
+
+/// a process that ran to its end
+pub(crate) struct Finished {
+    pub status: std::process::ExitStatus,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl Finished {
+    fn new(output: std::process::Output) -> Self {
+        Self {
+            status: output.status,
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        }
     }
 
-    Ok(message)
+    /// stdout of a successful run, the failure message otherwise
+    pub fn result(&self, cmd: &UserCommand) -> Result<String, String> {
+        if self.status.success() {
+            Ok(self.stdout.clone())
+        } else {
+            Err(executor::failure_message(cmd, self.status, self.stderr.clone()))
+        }
+    }
 }
 
-fn execute_command(cmd: UserCommand) -> Result<String, String> {
-    let timeout_secs = 0.5; // Hard limit of 500 ms
+/// run and keep exit status, stdout and stderr. Err when it could not run or timed out.
+/// `env` is added to the environment of the process, e.g. entered passwords
+pub(crate) fn execute_output(
+    cmd: UserCommand,
+    input: Option<String>,
+    env: Vec<(String, String)>,
+    children: &Children,
+) -> Result<Finished, String> {
+    use std::io::Write;
+    #[cfg(unix)]
+    use std::os::unix::process::CommandExt;
+    // the placeholder text is what shows up in the quit guard's list
+    let name = cmd.command.clone();
+    let (mut cmd, secrets) = secrets::resolve(cmd)?;
+    cmd.command = prompt::strip_marker(&cmd.command);
     let check_interval = Duration::from_millis(100); // Check every 100 ms
-    let mut child = Command::new(&cmd.shell)
-        .arg("-c")
-        .arg(cmd.command)
+    let mut process = executor::select(&cmd)?.prepare(&cmd)?;
+    process.envs(env).envs(secrets);
+    let mut process = flatpak::host(process);
+    // interactive shells must not wait for a terminal
+    process
+        .stdin(if input.is_some() { Stdio::piped() } else { Stdio::null() })
         .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
+        .stderr(Stdio::piped());
+    // own group, so jobs left by `cmd &` can be found on quit; a Job Object on Windows
+    #[cfg(unix)]
+    process.process_group(0);
+    let mut child = process
         .spawn()
-        .map_err(|e| format!("Failed to spawn process: {}", e))?;
+        .map_err(|e| {
+            audit::record(&name, "outcome=spawn-failed");
+            format!("Failed to spawn process: {}", e)
+        })?;
+    children.add(&child, &name);
+    if let (Some(text), Some(mut stdin)) = (input, child.stdin.take()) {
+        // written aside, a large input must not block the timeout; EOF when dropped
+        thread::spawn(move || {
+            let _ = stdin.write_all(text.as_bytes());
+        });
+    }
+
+    if cmd.long_running {
+        return supervise(&name, child);
+    }
 
     let start = Instant::now();
-    let timeout = Duration::from_secs_f64(timeout_secs);
+    let timeout = executor::timeout(&cmd);
 
     // Execution time monitoring with periodic check
     while start.elapsed() < timeout {
         match child.try_wait() {
             Ok(Some(status)) => {
                 // Process completed
+                audit::record(&name, &match status.code() {
+                    Some(code) => format!("exit={}", code),
+                    None => "outcome=killed".to_string(),
+                });
                 let output = child
                     .wait_with_output()
                     .map_err(|e| format!("Failed to get output: {}", e))?;
-
-                return if status.success() {
-                    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-                    Ok(stdout)
-                } else {
-                    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-                    Err(stderr)
-                };
+                return Ok(Finished::new(output));
             }
             Ok(None) => {
                 // process is still running, we are waiting a bit
@@ -364,19 +1583,107 @@ fn execute_command(cmd: UserCommand) -> Result<String, String> {
     // Give the process some time to finish correctly
     thread::sleep(Duration::from_millis(100));
     let _ = child.wait();
+    audit::record(&name, "outcome=timeout");
 
-    Err(format!("Command timed out after {} seconds", timeout_secs))
+    Err(format!("Command timed out after {} seconds", timeout.as_secs_f64()))
 }
 
-fn send_notification(summary: &str, body: &str) {
-    if let Err(e) = Notification::new()
-        .summary(summary)
-        .body(body)
-        .appname("gucli-notification")
-        .icon("system")
-        .show()
+/// wait for a `long_running` command without a timeout, its pipes are read meanwhile so a chatty one can't stall
+fn supervise(name: &str, child: std::process::Child) -> Result<Finished, String> {
+    info!("Command `{}` supervised in the background", name);
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("Failed to get output: {}", e))?;
+    audit::record(name, &match output.status.code() {
+        Some(code) => format!("exit={}", code),
+        None => "outcome=killed".to_string(),
+    });
+    Ok(Finished::new(output))
+}
+
+/// name of this machine, empty if unknown
+#[cfg(unix)]
+pub fn hostname() -> String {
+    let mut name = [0u8; 256];
+    if unsafe { nix::libc::gethostname(name.as_mut_ptr().cast(), name.len()) } != 0 {
+        return String::new();
+    }
+    let len = name.iter().position(|&b| b == 0).unwrap_or(name.len());
+    String::from_utf8_lossy(&name[..len]).trim().to_string()
+}
+
+#[cfg(windows)]
+pub fn hostname() -> String {
+    env::var("COMPUTERNAME").unwrap_or_default()
+}
+
+#[tauri::command]
+fn get_hostname() -> String {
+    hostname()
+}
+
+/// return full path of an executable found in $PATH
+pub fn find_in_path(bin: &str) -> Option<std::path::PathBuf> {
+    if flatpak::active() {
+        return flatpak::host_which(bin);
+    }
+    env::var_os("PATH").and_then(|paths| {
+        env::split_paths(&paths)
+            .map(|dir| dir.join(bin))
+            .flat_map(|path| {
+                // `cmd` is cmd.exe on Windows, EXE_SUFFIX is empty elsewhere
+                let mut exe = path.clone().into_os_string();
+                exe.push(env::consts::EXE_SUFFIX);
+                [std::path::PathBuf::from(exe), path]
+            })
+            .find(|path| path.is_file())
+    })
+}
+
+/// names of all executables in $PATH, sorted and unique
+pub fn path_executables() -> Vec<String> {
+    let mut names: Vec<String> = env::var_os("PATH")
+        .map(|paths| env::split_paths(&paths).collect::<Vec<_>>())
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flatten()
+        .flatten()
+        .filter(|entry| is_executable(&entry.path()))
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+pub(crate) fn send_notification(summary: &str, body: &str) {
+    // Notification Center, without the bundle a notify-rust notification needs
+    #[cfg(target_os = "macos")]
     {
-        error!("Notification failed: {} - {}. Error: {}", summary, body, e);
+        let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+        let script = format!("display notification {} with title {}", quote(body), quote(summary));
+        if let Err(e) = Command::new("osascript").args(["-e", &script]).output() {
+            error!("Notification failed: {} - {}. Error: {}", summary, body, e);
+        }
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        if flatpak::active() {
+            if let Err(e) = flatpak::notify(summary, body) {
+                error!("Notification failed: {} - {}. Error: {}", summary, body, e);
+            }
+            return;
+        }
+        if let Err(e) = Notification::new()
+            .summary(summary)
+            .body(body)
+            .appname("gucli-notification")
+            .icon("system")
+            .show()
+        {
+            error!("Notification failed: {} - {}. Error: {}", summary, body, e);
+        }
     }
 }
 
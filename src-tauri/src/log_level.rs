@@ -0,0 +1,54 @@
+use crate::files::settings;
+use std::sync::{Mutex, OnceLock};
+use tracing::info;
+
+type Reload = Box<dyn Fn(&str) -> Result<(), String> + Send + Sync>;
+
+// set by main() with the subscriber's reload handle
+static RELOAD: OnceLock<Reload> = OnceLock::new();
+static CURRENT: Mutex<String> = Mutex::new(String::new());
+
+/// hook up the filter reload of the subscriber built in main()
+pub fn install(reload: impl Fn(&str) -> Result<(), String> + Send + Sync + 'static, initial: &str) {
+    *CURRENT.lock().unwrap_or_else(|e| e.into_inner()) = initial.to_string();
+    let _ = RELOAD.set(Box::new(reload));
+}
+
+/// `log_level` from [settings], "info" when empty
+pub fn configured() -> String {
+    match settings().log_level.as_str() {
+        "" => "info".to_string(),
+        level => level.to_string(),
+    }
+}
+
+pub fn current() -> String {
+    CURRENT.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// switch the filter at runtime, until the next start
+pub fn set(level: &str) -> Result<(), String> {
+    let reload = RELOAD.get().ok_or("Logging not initialized")?;
+    reload(level)?;
+    *CURRENT.lock().unwrap_or_else(|e| e.into_inner()) = level.to_string();
+    info!("Log level set to {}", level);
+    Ok(())
+}
+
+/// tray debug toggle: debug on, or back to the configured level
+pub fn toggle_debug() -> Result<String, String> {
+    let level = if is_debug(&current()) {
+        match configured() {
+            configured if is_debug(&configured) => "info".to_string(),
+            configured => configured,
+        }
+    } else {
+        "debug".to_string()
+    };
+    set(&level)?;
+    Ok(level)
+}
+
+pub fn is_debug(level: &str) -> bool {
+    matches!(level, "debug" | "trace")
+}
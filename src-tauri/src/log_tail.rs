@@ -0,0 +1,65 @@
+use crate::files::full_path_log;
+use std::fs;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::Duration;
+use tauri::{Emitter, State};
+use tracing::debug;
+
+/// event emitted with the new log lines, oldest first
+pub const LOG_EVENT: &str = "log-lines";
+const POLL: Duration = Duration::from_millis(500);
+
+/// generation of the watcher, odd while the log tab follows the log. Every on and off
+/// starts a new one, a watcher thread ends as soon as the generation is no longer its own
+#[derive(Default)]
+pub struct LogFollow(Arc<AtomicU64>);
+
+/// the last `lines` lines of gucli.log, oldest first. The file has the newest on top
+#[tauri::command]
+pub fn read_log(lines: usize) -> Vec<String> {
+    let content = full_path_log().and_then(|path| fs::read_to_string(path).map_err(|e| e.to_string()));
+    let content = content.unwrap_or_default();
+    let mut newest: Vec<String> = content.lines().take(lines).map(str::to_string).collect();
+    newest.reverse();
+    newest
+}
+
+/// start or stop sending new lines of gucli.log as LOG_EVENT
+#[tauri::command]
+pub fn follow_log(app: tauri::AppHandle, on: bool, follow: State<'_, LogFollow>) {
+    // already in the asked state: one watcher for any number of "on"
+    let Ok(previous) = follow.0.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |generation| {
+        let following = generation % 2 == 1;
+        (following != on).then_some(generation + 1)
+    }) else {
+        return;
+    };
+    if !on {
+        return;
+    }
+    let generation = previous + 1;
+    let current = follow.0.clone();
+    thread::spawn(move || {
+        // the file is rewritten on every entry, new lines are the ones above the last seen top line
+        let read = || full_path_log().ok().and_then(|path| fs::read_to_string(path).ok());
+        let mut top = read().and_then(|content| content.lines().next().map(str::to_string));
+        // an off and on within POLL has a newer watcher already
+        while current.load(Ordering::SeqCst) == generation {
+            thread::sleep(POLL);
+            let Some(content) = read() else {
+                continue;
+            };
+            let mut fresh: Vec<&str> = content.lines().take_while(|line| Some(*line) != top.as_deref()).collect();
+            if fresh.is_empty() {
+                continue;
+            }
+            top = Some(fresh[0].to_string());
+            fresh.reverse();
+            if let Err(e) = app.emit(LOG_EVENT, fresh) {
+                debug!("Log event not sent: {}", e);
+            }
+        }
+    });
+}
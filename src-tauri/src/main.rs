@@ -1,67 +1,190 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use chrono::Local;
-use gucli_lib::files::LineLimitedWriter;
-use nix::libc;
-use std::fs::{File, OpenOptions};
-use std::os::unix::io::AsRawFd;
+use gucli_lib::files::{CRASH_FILE, DEFAULT_LOG_LINES, LineLimitedWriter, format_timestamp, log_path, state_dir};
+use gucli_lib::instance;
+use std::env;
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::Write;
+use std::path::PathBuf;
 use tracing_subscriber::fmt::format::Writer;
 use tracing_subscriber::fmt::time::FormatTime;
-use tracing_subscriber::{EnvFilter, fmt};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, fmt, reload};
 
 struct LogTime;
 
 impl FormatTime for LogTime {
     fn format_time(&self, w: &mut Writer<'_>) -> std::fmt::Result {
-        write!(w, "{}", Local::now().format("%Y-%m-%d %H:%M:%S%.3f"))
+        write!(w, "{}", format_timestamp(&Local::now()))
     }
 }
 
 fn init_tracing() {
-    let log_path = gucli_lib::files::full_path_log();
-    let file_writer = LineLimitedWriter::new(log_path, 100);
+    // `log_target` in [settings]: gucli.log, the journal (`journalctl --user -t gucli`) or both
+    let settings = gucli_lib::files::read_settings();
+    let target = settings.log_target.clone();
+    #[cfg(unix)]
+    let journald = match target.as_str() {
+        "journald" | "both" => tracing_journald::layer()
+            .map(|layer| layer.with_syslog_identifier("gucli".to_string()))
+            .inspect_err(|e| eprintln!("Journal not available, logging to file: {e}"))
+            .ok(),
+        _ => None,
+    };
+    // no journal on Windows, the file is the fallback as below
+    #[cfg(windows)]
+    let journald = None::<tracing_subscriber::layer::Identity>;
+    // the file stays the fallback when the journal is missing
+    let file = (target != "journald" || journald.is_none())
+        // `log_file` / `log_max_lines` in [settings]
+        .then(|| log_path(&settings))
+        .and_then(|path| path.inspect_err(|e| eprintln!("No log file: {e}")).ok())
+        .map(|path| {
+            if let Some(dir) = path.parent() {
+                let _ = fs::create_dir_all(dir);
+            }
+            let max_lines = settings.log_max_lines.unwrap_or(DEFAULT_LOG_LINES).max(1);
+            let format = fmt::format()
+                .with_timer(LogTime)
+                .with_level(true)
+                .with_target(false)
+                .with_ansi(false)
+                .compact();
+            fmt::layer()
+                .event_format(format)
+                .with_writer(LineLimitedWriter::new(path, max_lines))
+        });
+
+    // `log_level` in [settings], switched at runtime from the tray
+    let level = if settings.log_level.is_empty() { "info".to_string() } else { settings.log_level.clone() };
+    let (filter, handle) = reload::Layer::new(EnvFilter::new(&level));
+    gucli_lib::log_level::install(
+        move |level| handle.reload(EnvFilter::new(level)).map_err(|e| e.to_string()),
+        &level,
+    );
 
-    let format = fmt::format()
-        .with_timer(LogTime)
-        .with_level(true)
-        .with_target(false)
-        .with_ansi(false)
-        .compact();
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(file)
+        .with(journald)
+        .try_init()
+        .expect("Failed to init logger");
+}
+
+/// a tray icon that just vanishes tells nothing: log the panic, keep the backtrace, notify
+fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let thread = std::thread::current();
+        let message = format!("Panic in thread `{}`: {}", thread.name().unwrap_or("unnamed"), info);
+        let report = format!(
+            "{} {}\n{}\n",
+            format_timestamp(&Local::now()),
+            message,
+            std::backtrace::Backtrace::force_capture()
+        );
+        // appended, never truncated
+        let crash_file = state_dir().map(|dir| dir.join(CRASH_FILE));
+        if let Ok(path) = &crash_file {
+            if let Some(dir) = path.parent() {
+                let _ = fs::create_dir_all(dir);
+            }
+            if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(path) {
+                let _ = file.write_all(report.as_bytes());
+            }
+        }
+        tracing::error!("{}", message.replace('\n', " "));
+        let _ = notify_rust::Notification::new()
+            .summary("Err( Gucli crashed )")
+            .body(&match &crash_file {
+                Ok(path) => format!("{}\nDetails: {}", message, path.display()),
+                Err(_) => message.clone(),
+            })
+            .appname("gucli-notification")
+            .icon("dialog-error")
+            .show();
+        default_hook(info);
+    }));
+}
 
-    let subscriber = fmt::Subscriber::builder()
-        .with_env_filter(EnvFilter::new("info"))
-        .event_format(format)
-        .with_writer(file_writer)
-        .finish();
+// lock - single instance per user, in $XDG_RUNTIME_DIR (not shared, cleared at logout)
+#[cfg(unix)]
+fn lock_path() -> PathBuf {
+    match env::var_os("XDG_RUNTIME_DIR") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir).join("gucli.lock"),
+        _ => env::temp_dir().join(format!("gucli-{}.lock", unsafe { nix::libc::getuid() })),
+    }
+}
 
-    tracing::subscriber::set_global_default(subscriber).expect("Failed to init logger");
+// %TEMP% is per user on Windows
+#[cfg(windows)]
+fn lock_path() -> PathBuf {
+    env::temp_dir().join("gucli.lock")
 }
 
-// lock - single instance
+/// the lock file, held for the life of the process. The lock (flock, LockFileEx on Windows) is
+/// released when its holder exits, so a lock that can't be taken always belongs to a running instance
 fn enforce_single_instance() -> Result<File, String> {
-    let file = OpenOptions::new()
+    let path = lock_path();
+    let mut file = OpenOptions::new()
+        .read(true)
         .write(true)
         .create(true)
-        .truncate(true)
-        .open("/tmp/gucli.lock")
-        .map_err(|e| format!("Failed to open lock file: {e}"))?;
+        .truncate(false)
+        .open(&path)
+        .map_err(|e| format!("Failed to open lock file {}: {e}", path.display()))?;
 
-    unsafe {
-        if libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) != 0 {
-            return Err("Another instance is already running".to_string());
+    match file.try_lock() {
+        Ok(()) => {}
+        // the pid is only for the message: the holder may not have written it yet
+        Err(TryLockError::WouldBlock) => {
+            return Err(match fs::read_to_string(&path).ok().and_then(|pid| pid.trim().parse::<i32>().ok()) {
+                Some(pid) => format!("Another instance is already running (pid {pid})"),
+                None => "Another instance is already running".to_string(),
+            });
         }
+        Err(TryLockError::Error(e)) => return Err(format!("Failed to lock {}: {e}", path.display())),
     }
 
+    let _ = file.set_len(0);
+    let _ = write!(file, "{}", std::process::id());
     Ok(file)
 }
 
 fn main() {
     init_tracing();
+    install_panic_hook();
+    // only reads the audit log, no instance involved
+    if env::args().nth(1).as_deref() == Some("--verify-audit") {
+        match gucli_lib::audit::verify() {
+            Ok(message) => println!("{message}"),
+            Err(e) => {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+        }
+        std::process::exit(0);
+    }
     let _lock = match enforce_single_instance() {
         Ok(file) => file,
         Err(e) => {
-            eprintln!("{e}");
-            std::process::exit(1);
+            // second instance: pass the intent on, a plain launch opens the settings
+            let intent = match instance::intent_from_args(env::args().skip(1)) {
+                Ok(intent) => intent.unwrap_or_else(|| "settings".to_string()),
+                Err(usage) => {
+                    eprintln!("{usage}");
+                    std::process::exit(2);
+                }
+            };
+            match instance::forward(&intent) {
+                Ok(_) => std::process::exit(0),
+                Err(forward_err) => {
+                    eprintln!("{e}\n{forward_err}");
+                    std::process::exit(1);
+                }
+            }
         }
     };
     gucli_lib::run();
@@ -0,0 +1,95 @@
+use crate::files::language;
+use crate::flatpak;
+use gucli_shared::i18n::tr;
+#[cfg(all(unix, not(target_os = "macos")))]
+use notify_rust::{Notification, NotificationHandle, Timeout};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tracing::error;
+
+/// runs taking longer than this get a "running…" notification
+pub const THRESHOLD: Duration = Duration::from_secs(2);
+
+enum State {
+    Waiting,
+    // a handle with id() and close() only exists for the XDG notification server
+    #[cfg(all(unix, not(target_os = "macos")))]
+    Shown(Box<NotificationHandle>),
+    // Flatpak: a portal notification, by its id
+    Portal(String),
+    Done,
+}
+
+/// "running…" notification of one run, so a slow command shows the click registered.
+/// The result notification replaces it, dropping closes it
+pub struct Progress(Arc<Mutex<State>>);
+
+impl Progress {
+    pub fn start(command: &str) -> Self {
+        let state = Arc::new(Mutex::new(State::Waiting));
+        let shared = Arc::clone(&state);
+        let command = command.to_string();
+        thread::spawn(move || {
+            thread::sleep(THRESHOLD);
+            // locked while showing, so a run ending right now can't miss the handle
+            let mut state = shared.lock().unwrap_or_else(|e| e.into_inner());
+            if !matches!(*state, State::Waiting) {
+                return;
+            }
+            let summary = tr(language(), "notify.running").replace("{}", &command);
+            if flatpak::active() {
+                let id = flatpak::notification_id();
+                match flatpak::show(&id, &summary, "") {
+                    Ok(()) => *state = State::Portal(id),
+                    Err(e) => error!("Progress notification of `{}` failed: {}", command, e),
+                }
+                return;
+            }
+            #[cfg(all(unix, not(target_os = "macos")))]
+            match Notification::new()
+                .summary(&summary)
+                .appname("gucli-notification")
+                .icon("system")
+                .timeout(Timeout::Never)
+                .show()
+            {
+                Ok(handle) => *state = State::Shown(Box::new(handle)),
+                Err(e) => error!("Progress notification of `{}` failed: {}", command, e),
+            }
+            // nothing could replace or close it later: only the plain result notification is shown
+            #[cfg(not(all(unix, not(target_os = "macos"))))]
+            {
+                *state = State::Done;
+            }
+        });
+        Progress(state)
+    }
+
+    /// id of the shown notification for the result to reuse, None when the run was quick.
+    /// A portal notification is withdrawn, the result is a notification of its own
+    pub fn take(&self) -> Option<u32> {
+        let mut state = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        match std::mem::replace(&mut *state, State::Done) {
+            #[cfg(all(unix, not(target_os = "macos")))]
+            State::Shown(handle) => Some(handle.id()),
+            State::Portal(id) => {
+                flatpak::withdraw(&id);
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        let mut state = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        match std::mem::replace(&mut *state, State::Done) {
+            #[cfg(all(unix, not(target_os = "macos")))]
+            State::Shown(handle) => handle.close(),
+            State::Portal(id) => flatpak::withdraw(&id),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use crate::executor::{shell_quote, variable_reference};
+use crate::{find_in_path, flatpak};
+use std::env;
+use std::process::Command;
+use tauri_plugin_dialog::DialogExt;
+
+/// placeholder in a command: ask for text and pipe it into the process's stdin
+pub const STDIN_MARKER: &str = "{stdin}";
+/// placeholders replaced by a path picked in a file chooser before each run
+pub const FILE_MARKER: &str = "{file}";
+pub const DIR_MARKER: &str = "{dir}";
+/// `{password:prompt}`: asked in a masked dialog before each run, never stored
+const PASSWORD_PREFIX: &str = "{password:";
+/// the entered password is in this variable, the next ones in _2, _3...
+pub const PASSWORD_ENV: &str = "GUCLI_PASSWORD";
+
+pub fn wants_stdin(command: &str) -> bool {
+    command.contains(STDIN_MARKER)
+}
+
+/// the command as passed to the shell, without the marker
+pub fn strip_marker(command: &str) -> String {
+    command.replace(STDIN_MARKER, "").trim().to_string()
+}
+
+pub fn wants_path(command: &str) -> bool {
+    command.contains(FILE_MARKER) || command.contains(DIR_MARKER)
+}
+
+/// every {file} / {dir} replaced by a chosen, shell-quoted path, None when a chooser is cancelled.
+/// Blocks until answered, never call it on the main thread
+pub fn fill_paths(app: &tauri::AppHandle, command: &str) -> Result<Option<String>, String> {
+    let mut filled = command.to_string();
+    while let Some((pos, marker)) = [FILE_MARKER, DIR_MARKER]
+        .into_iter()
+        .filter_map(|marker| filled.find(marker).map(|pos| (pos, marker)))
+        .min()
+    {
+        let dialog = app.dialog().file().set_title(command);
+        let picked = if marker == FILE_MARKER {
+            dialog.blocking_pick_file()
+        } else {
+            dialog.blocking_pick_folder()
+        };
+        let Some(picked) = picked else {
+            return Ok(None);
+        };
+        let path = picked.into_path().map_err(|e| e.to_string())?;
+        filled.replace_range(pos..pos + marker.len(), &shell_quote(&path.to_string_lossy()));
+    }
+    Ok(Some(filled))
+}
+
+pub fn wants_password(command: &str) -> bool {
+    command.contains(PASSWORD_PREFIX)
+}
+
+/// command with the variable references and the (variable, password) pairs for its environment
+type Filled = (String, Vec<(String, String)>);
+
+/// every {password:prompt} replaced by a quoted variable reference and the values for the environment,
+/// so they reach the shell without passing argv. None when a dialog is cancelled. Blocks until answered
+pub fn fill_passwords(command: &str) -> Result<Option<Filled>, String> {
+    let mut filled = String::new();
+    // (prompt, variable, value), the same prompt is asked once
+    let mut asked: Vec<(String, String, String)> = Vec::new();
+    let mut rest = command;
+    while let Some(start) = rest.find(PASSWORD_PREFIX)
+        && let Some(len) = rest[start..].find('}')
+    {
+        let prompt = &rest[start + PASSWORD_PREFIX.len()..start + len];
+        let name = match asked.iter().find(|(p, _, _)| p == prompt) {
+            Some((_, name, _)) => name.clone(),
+            None => {
+                let Some(password) = ask_password(prompt)? else {
+                    return Ok(None);
+                };
+                let name = match asked.len() {
+                    0 => PASSWORD_ENV.to_string(),
+                    n => format!("{}_{}", PASSWORD_ENV, n + 1),
+                };
+                asked.push((prompt.to_string(), name.clone(), password));
+                name
+            }
+        };
+        filled.push_str(&rest[..start]);
+        let reference = variable_reference(&filled, &name);
+        filled.push_str(&reference);
+        rest = &rest[start + len + 1..];
+    }
+    filled.push_str(rest);
+    Ok(Some((filled, asked.into_iter().map(|(_, name, value)| (name, value)).collect())))
+}
+
+/// masked one-line dialog (zenity, kdialog, yad or $SSH_ASKPASS), None when cancelled
+pub fn ask_password(prompt: &str) -> Result<Option<String>, String> {
+    let process = if let Some(zenity) = find_in_path("zenity") {
+        let mut process = Command::new(zenity);
+        process.args(["--password", "--title"]).arg(prompt);
+        process
+    } else if let Some(kdialog) = find_in_path("kdialog") {
+        let mut process = Command::new(kdialog);
+        process.arg("--password").arg(prompt);
+        process
+    } else if let Some(yad) = find_in_path("yad") {
+        let mut process = Command::new(yad);
+        process.args(["--entry", "--hide-text", "--text"]).arg(prompt);
+        process
+    } else if let Some(askpass) = env::var_os("SSH_ASKPASS").filter(|a| !a.is_empty()) {
+        let mut process = Command::new(askpass);
+        process.arg(prompt);
+        process
+    } else {
+        return Err("A {password:…} command needs zenity, kdialog, yad or $SSH_ASKPASS for its password dialog".to_string());
+    };
+    // in Flatpak the dialog found by host_which is a host program
+    let output = flatpak::host(process)
+        .stdin(std::process::Stdio::null())
+        .output()
+        .map_err(|e| format!("Password dialog failed: {}", e))?;
+    if !output.status.success() {
+        return Ok(None);
+    }
+    let password = String::from_utf8_lossy(&output.stdout);
+    Ok(Some(password.strip_suffix('\n').unwrap_or(&password).to_string()))
+}
+
+/// multi-line text dialog (zenity, kdialog or yad), None when cancelled
+pub fn ask_text(title: &str) -> Result<Option<String>, String> {
+    let process = if let Some(zenity) = find_in_path("zenity") {
+        let mut process = Command::new(zenity);
+        process.args(["--text-info", "--editable", "--width=600", "--height=400", "--title"]).arg(title);
+        process
+    } else if let Some(kdialog) = find_in_path("kdialog") {
+        let mut process = Command::new(kdialog);
+        process.arg("--title").arg(title).args(["--textinputbox", "Input"]);
+        process
+    } else if let Some(yad) = find_in_path("yad") {
+        let mut process = Command::new(yad);
+        process.args(["--text-info", "--editable", "--width=600", "--height=400", "--title"]).arg(title);
+        process
+    } else {
+        return Err("A {stdin} command needs zenity, kdialog or yad for its input dialog".to_string());
+    };
+    // text-info reads its initial content from stdin
+    let output = flatpak::host(process)
+        .stdin(std::process::Stdio::null())
+        .output()
+        .map_err(|e| format!("Input dialog failed: {}", e))?;
+    if !output.status.success() {
+        return Ok(None);
+    }
+    Ok(Some(String::from_utf8_lossy(&output.stdout).to_string()))
+}
@@ -0,0 +1,182 @@
+use crate::UserCommand;
+use crate::files::format_timestamp;
+use chrono::Local;
+use gucli_shared::Execution;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Runtime, State};
+use tracing::debug;
+
+/// event emitted with an Execution when a run starts and when it finishes
+pub const EXECUTION_EVENT: &str = "execution";
+// finished runs kept in memory, older ones are only in the log
+const MAX_FINISHED: usize = 100;
+
+/// every running and recently finished command execution, shared by the tray and the UI
+#[derive(Default)]
+pub struct ExecutionRegistry {
+    inner: Mutex<Runs>,
+    // one lock per command text, held while it runs (see `on_busy`)
+    lanes: Mutex<HashMap<String, Arc<Mutex<()>>>>,
+    // `queue` groups: (next ticket, ticket being served), served in ticket order
+    queues: Mutex<HashMap<String, (u64, u64)>>,
+    queue_turn: Condvar,
+}
+
+/// place in a `queue` group, the next run goes when this is dropped
+pub struct QueueTicket<'a> {
+    registry: &'a ExecutionRegistry,
+    group: String,
+}
+
+impl Drop for QueueTicket<'_> {
+    fn drop(&mut self) {
+        let mut queues = self.registry.queues.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((_, serving)) = queues.get_mut(&self.group) {
+            *serving += 1;
+        }
+        self.registry.queue_turn.notify_all();
+    }
+}
+
+#[derive(Default)]
+struct Runs {
+    next_id: u64,
+    // oldest first
+    list: VecDeque<Execution>,
+    // per command: last notification text, when it was shown, repeats held back since
+    notified: HashMap<String, (String, Instant, u32)>,
+}
+
+impl ExecutionRegistry {
+    pub fn start(&self, cmd: &UserCommand) -> Execution {
+        let mut runs = self.inner.lock().unwrap();
+        runs.next_id += 1;
+        let execution = Execution {
+            run_id: runs.next_id,
+            command_id: cmd.id.clone(),
+            command: cmd.command.clone(),
+            started: format_timestamp(&Local::now()),
+            running: true,
+            ..Default::default()
+        };
+        runs.list.push_back(execution.clone());
+        execution
+    }
+
+    pub fn finish(&self, run_id: u64, result: &Result<String, String>) -> Option<Execution> {
+        let mut runs = self.inner.lock().unwrap();
+        let execution = runs.list.iter_mut().find(|e| e.run_id == run_id)?;
+        execution.running = false;
+        execution.finished = format_timestamp(&Local::now());
+        (execution.success, execution.output) = match result {
+            Ok(output) => (true, output.clone()),
+            Err(err) => (false, err.clone()),
+        };
+        let finished = execution.clone();
+
+        while runs.list.iter().filter(|e| !e.running).count() > MAX_FINISHED {
+            if let Some(oldest) = runs.list.iter().position(|e| !e.running) {
+                runs.list.remove(oldest);
+            }
+        }
+        Some(finished)
+    }
+
+    /// None while the same notification of the command is within its cooldown,
+    /// else the number of repeats held back since the last one shown
+    pub fn should_notify(&self, command: &str, text: &str, cooldown: Duration) -> Option<u32> {
+        let mut runs = self.inner.lock().unwrap();
+        let now = Instant::now();
+        if let Some((last_text, shown, held)) = runs.notified.get_mut(command)
+            && last_text == text
+            && now.duration_since(*shown) < cooldown
+        {
+            *held += 1;
+            return None;
+        }
+        let held = match runs.notified.insert(command.to_string(), (text.to_string(), now, 0)) {
+            Some((last_text, _, held)) if last_text == text => held,
+            _ => 0,
+        };
+        Some(held)
+    }
+
+    /// the in-flight lock of a command, shared by all of its runs
+    pub fn lane(&self, command: &str) -> Arc<Mutex<()>> {
+        self.lanes
+            .lock()
+            .unwrap()
+            .entry(command.to_string())
+            .or_default()
+            .clone()
+    }
+
+    /// wait for the turn in a `queue` group, first come first served
+    pub fn enter_queue(&self, group: &str) -> QueueTicket<'_> {
+        let mut queues = self.queues.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = queues.entry(group.to_string()).or_default();
+        let ticket = entry.0;
+        entry.0 += 1;
+        while queues.get(group).is_some_and(|(_, serving)| *serving != ticket) {
+            queues = self.queue_turn.wait(queues).unwrap_or_else(|e| e.into_inner());
+        }
+        QueueTicket {
+            registry: self,
+            group: group.to_string(),
+        }
+    }
+
+    /// runs waiting in all queues, the ones being served not counted
+    pub fn queued(&self) -> u64 {
+        let queues = self.queues.lock().unwrap_or_else(|e| e.into_inner());
+        queues
+            .values()
+            .map(|(next, serving)| next.saturating_sub(*serving).saturating_sub(1))
+            .sum()
+    }
+
+    pub fn running(&self) -> Vec<Execution> {
+        let runs = self.inner.lock().unwrap();
+        runs.list.iter().filter(|e| e.running).cloned().collect()
+    }
+
+    /// newest finished run of a command, by id or command text (ids change on every config load)
+    pub fn last_result(&self, id: &str) -> Option<Execution> {
+        let runs = self.inner.lock().unwrap();
+        runs.list
+            .iter()
+            .rev()
+            .find(|e| !e.running && (e.command_id == id || e.command == id))
+            .cloned()
+    }
+
+    pub fn run(&self, run_id: u64) -> Option<Execution> {
+        let runs = self.inner.lock().unwrap();
+        runs.list.iter().find(|e| e.run_id == run_id).cloned()
+    }
+
+    /// finished runs, newest first
+    pub fn finished(&self) -> Vec<Execution> {
+        let runs = self.inner.lock().unwrap();
+        runs.list.iter().rev().filter(|e| !e.running).cloned().collect()
+    }
+}
+
+/// push a start/finish to open windows
+pub fn notify<R: Runtime>(app: &AppHandle<R>, execution: &Execution) {
+    if let Err(e) = app.emit(EXECUTION_EVENT, execution) {
+        debug!("Execution event not sent: {}", e);
+    }
+}
+
+#[tauri::command]
+pub fn get_running(registry: State<'_, ExecutionRegistry>) -> Vec<Execution> {
+    registry.running()
+}
+
+#[tauri::command]
+pub fn get_last_result(id: String, registry: State<'_, ExecutionRegistry>) -> Option<Execution> {
+    registry.last_result(&id)
+}
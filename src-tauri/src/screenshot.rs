@@ -0,0 +1,75 @@
+use crate::files::config_dir;
+use crate::{find_in_path, flatpak};
+use chrono::Local;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+pub const SCREENSHOTS_DIR: &str = "screenshots";
+// older screenshots are deleted
+const MAX_SCREENSHOTS: usize = 20;
+
+/// save a screenshot of the whole desktop, named after the failed command
+pub fn capture(command: &str) -> Result<PathBuf, String> {
+    let dir = config_dir()?.join(SCREENSHOTS_DIR);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+    let name: String = command
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .take(40)
+        .collect();
+    let path = dir.join(format!("{}-{}.png", Local::now().format("%Y%m%d-%H%M%S"), name));
+
+    let mut process = screenshot_command(&path)?;
+    let status = process
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|e| format!("Screenshot failed: {}", e))?;
+    if !status.success() || !path.exists() {
+        return Err(format!("Screenshot failed: {}", status));
+    }
+
+    prune(&dir);
+    Ok(path)
+}
+
+/// first installed tool: wayland (grim, gnome, kde), then X11
+fn screenshot_command(path: &Path) -> Result<Command, String> {
+    let wayland = std::env::var("WAYLAND_DISPLAY").is_ok();
+    let tools: [(&str, &[&str], bool); 6] = [
+        ("grim", &[], true),
+        ("gnome-screenshot", &["-f"], false),
+        ("spectacle", &["-b", "-n", "-f", "-o"], false),
+        ("maim", &[], false),
+        ("scrot", &["-o"], false),
+        ("import", &["-window", "root"], false),
+    ];
+    tools
+        .iter()
+        .filter(|(_, _, wayland_only)| wayland || !wayland_only)
+        .find_map(|(tool, args, _)| {
+            find_in_path(tool).map(|bin| {
+                let mut process = Command::new(bin);
+                process.args(*args).arg(path);
+                // a host tool in Flatpak, as found by host_which
+                flatpak::host(process)
+            })
+        })
+        .ok_or_else(|| "No screenshot tool found (grim, gnome-screenshot, spectacle, maim, scrot, import)".to_string())
+}
+
+fn prune(dir: &Path) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut files: Vec<PathBuf> = entries.filter_map(|e| e.ok().map(|e| e.path())).collect();
+    // names start with the timestamp
+    files.sort();
+    let excess = files.len().saturating_sub(MAX_SCREENSHOTS);
+    for file in &files[..excess] {
+        let _ = fs::remove_file(file);
+    }
+}
@@ -0,0 +1,65 @@
+use crate::UserCommand;
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// keyring service of the stored secrets, the placeholder name is the username
+pub const SERVICE: &str = "gucli";
+
+static PLACEHOLDER: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\{secret:([A-Za-z0-9_.-]+)\}").unwrap());
+
+/// variable of the first secret, the next ones get _2, _3...
+pub const SECRET_ENV: &str = "GUCLI_SECRET";
+
+pub fn wants_secret(command: &str) -> bool {
+    PLACEHOLDER.is_match(command)
+}
+
+/// `{secret:name}` replaced by `"$GUCLI_SECRET_<n>"`, right before execution. The secret from the
+/// Secret Service goes into that variable, never into the command line: pass it with Command::envs
+pub fn resolve(mut cmd: UserCommand) -> Result<(UserCommand, Vec<(String, String)>), String> {
+    if !PLACEHOLDER.is_match(&cmd.command) {
+        return Ok((cmd, Vec::new()));
+    }
+    // the variable is set on the local ssh/podman process, the remote shell would expand it empty
+    if !matches!(cmd.executor.as_str(), "" | "local") || !cmd.host.trim().is_empty() {
+        return Err("{secret:…} works only for local commands, not over ssh or in a container".to_string());
+    }
+    // flatpak-spawn only takes the environment as --env=K=V arguments, readable in /proc/*/cmdline
+    if crate::flatpak::active() {
+        return Err("{secret:…} doesn't work in Flatpak: the value would be on flatpak-spawn's command line".to_string());
+    }
+    let mut resolved = String::with_capacity(cmd.command.len());
+    // (secret name, variable, value), the same secret is looked up once
+    let mut found: Vec<(String, String, String)> = Vec::new();
+    let mut last = 0;
+    for caps in PLACEHOLDER.captures_iter(&cmd.command) {
+        let whole = caps.get(0).unwrap();
+        resolved.push_str(&cmd.command[last..whole.start()]);
+        let variable = match found.iter().find(|(name, _, _)| name == &caps[1]) {
+            Some((_, variable, _)) => variable.clone(),
+            None => {
+                let variable = format!("{}_{}", SECRET_ENV, found.len() + 1);
+                found.push((caps[1].to_string(), variable.clone(), lookup(&caps[1])?));
+                variable
+            }
+        };
+        let reference = crate::executor::variable_reference(&resolved, &variable);
+        resolved.push_str(&reference);
+        last = whole.end();
+    }
+    resolved.push_str(&cmd.command[last..]);
+    cmd.command = resolved;
+    Ok((cmd, found.into_iter().map(|(_, variable, value)| (variable, value)).collect()))
+}
+
+fn lookup(name: &str) -> Result<String, String> {
+    keyring::Entry::new(SERVICE, name)
+        .and_then(|entry| entry.get_password())
+        .map_err(|e| match e {
+            keyring::Error::NoEntry => format!(
+                "Secret `{}` not found, store it with: secret-tool store --label=gucli service {} username {}",
+                name, SERVICE, name
+            ),
+            other => format!("Secret `{}` not available: {}", name, other),
+        })
+}
@@ -0,0 +1,119 @@
+use crate::files::*;
+use crate::children::Children;
+use crate::{AvailableShells, UserCommand, build_tray_menu, execute_command};
+use tauri::Manager;
+use gucli_shared::SelfTestItem;
+use notify_rust::Notification;
+use std::time::{Duration, Instant};
+use tracing::{error, info};
+
+type Check = fn(&tauri::AppHandle) -> Result<String, String>;
+
+/// exercise core subsystems and report pass/fail per item
+#[tauri::command]
+pub async fn run_self_test(app: tauri::AppHandle) -> Vec<SelfTestItem> {
+    let checks: [(&str, Check); 5] = [
+        ("Config round-trip", config_roundtrip),
+        ("Notification delivery", notification_delivery),
+        ("Shell availability", shell_availability),
+        ("Timeout kill", timeout_kill),
+        ("Tray rebuild", tray_rebuild),
+    ];
+
+    checks
+        .iter()
+        .map(|(name, check)| {
+            let (passed, detail) = match check(&app) {
+                Ok(detail) => (true, detail),
+                Err(detail) => (false, detail),
+            };
+            if passed {
+                info!("Self-test `{}` passed: {}", name, detail);
+            } else {
+                error!("Self-test `{}` failed: {}", name, detail);
+            }
+            SelfTestItem {
+                name: name.to_string(),
+                passed,
+                detail,
+            }
+        })
+        .collect()
+}
+
+// load commands.toml, render it back and parse the result again
+fn config_roundtrip(_app: &tauri::AppHandle) -> Result<String, String> {
+    let config = load_commands().map_err(|e| e.to_string())?;
+    let rendered = render_commands(&config).map_err(|e| e.to_string())?;
+    let parsed: CommandsConfig = toml::from_str(&rendered).map_err(|e| e.to_string())?;
+
+    let same = parsed.commands.len() == config.commands.len()
+        && parsed
+            .commands
+            .iter()
+            .zip(&config.commands)
+            .all(|(a, b)| a.command == b.command && a.shell == b.shell && a.icon == b.icon && a.sn == b.sn);
+    if same {
+        Ok(format!("{} commands survive save/load", config.commands.len()))
+    } else {
+        Err("Commands differ after save/load".to_string())
+    }
+}
+
+fn notification_delivery(_app: &tauri::AppHandle) -> Result<String, String> {
+    Notification::new()
+        .summary("Gucli self-test")
+        .body("If you see this, notifications work")
+        .appname("gucli-notification")
+        .icon("system")
+        .show()
+        .map(|_| "Notification sent".to_string())
+        .map_err(|e| e.to_string())
+}
+
+// every shell used in commands.toml must be installed
+fn shell_availability(_app: &tauri::AppHandle) -> Result<String, String> {
+    let config = load_commands().map_err(|e| e.to_string())?;
+    let mut shells: Vec<&str> = config.commands.iter().map(|c| c.shell.as_str()).collect();
+    shells.sort();
+    shells.dedup();
+
+    let available = AvailableShells::detect(&config.settings);
+    let missing: Vec<&str> = shells
+        .iter()
+        .copied()
+        .filter(|shell| !available.0.iter().any(|s| s == shell))
+        .collect();
+    if missing.is_empty() {
+        Ok(format!("Found: {}", shells.join(", ")))
+    } else {
+        Err(format!("Not installed: {}", missing.join(", ")))
+    }
+}
+
+// a sleeping command must be killed by the execution timeout
+fn timeout_kill(app: &tauri::AppHandle) -> Result<String, String> {
+    let cmd = UserCommand {
+        id: "self-test".to_string(),
+        shell: "sh".to_string(),
+        command: "sleep 5".to_string(),
+        ..Default::default()
+    };
+    let start = Instant::now();
+    match execute_command(cmd, &app.state::<Children>()) {
+        Err(e) if e.contains("timed out") && start.elapsed() < Duration::from_secs(2) => {
+            Ok(format!("Killed after {} ms", start.elapsed().as_millis()))
+        }
+        Err(e) => Err(e),
+        Ok(_) => Err("`sleep 5` was not interrupted".to_string()),
+    }
+}
+
+fn tray_rebuild(app: &tauri::AppHandle) -> Result<String, String> {
+    if app.tray_by_id("main").is_none() {
+        return Err("Tray icon is not registered".to_string());
+    }
+    let config = load_commands().map_err(|e| e.to_string())?;
+    build_tray_menu(app, &config).map_err(|e| e.to_string())?;
+    Ok(format!("Menu with {} commands built", config.commands.len()))
+}
@@ -0,0 +1,84 @@
+use crate::flatpak;
+use gucli_shared::AppSettings;
+#[cfg(unix)]
+use nix::libc;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+use tracing::{error, info, warn};
+
+// the session manager kills what is left after a few seconds, finish before that
+const TIMEOUT: Duration = Duration::from_secs(5);
+#[cfg(unix)]
+const POLL: Duration = Duration::from_millis(200);
+
+// set by the signal handler, nothing else is safe to do in there
+#[cfg(unix)]
+static SIGNALED: AtomicBool = AtomicBool::new(false);
+static RAN: AtomicBool = AtomicBool::new(false);
+
+#[cfg(unix)]
+extern "C" fn on_signal(_: libc::c_int) {
+    SIGNALED.store(true, Ordering::SeqCst);
+}
+
+/// quit through app.exit on SIGTERM, SIGHUP (logout) and SIGINT, so the `on_quit` hook runs
+#[cfg(unix)]
+pub fn watch_signals(app: AppHandle) {
+    for signal in [libc::SIGTERM, libc::SIGHUP, libc::SIGINT] {
+        unsafe {
+            libc::signal(signal, on_signal as extern "C" fn(libc::c_int) as libc::sighandler_t);
+        }
+    }
+    thread::spawn(move || {
+        while !SIGNALED.load(Ordering::SeqCst) {
+            thread::sleep(POLL);
+        }
+        info!("Termination signal received, quitting");
+        app.exit(0);
+    });
+}
+
+// no signals on Windows, logoff ends the event loop and RunEvent::Exit runs the hook
+#[cfg(windows)]
+pub fn watch_signals(_app: AppHandle) {}
+
+/// `on_quit` in [settings], once per process, waits at most TIMEOUT
+pub fn run_hook(settings: &AppSettings) {
+    let command = settings.on_quit.trim();
+    if command.is_empty() || RAN.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    info!("Running on_quit `{}`", command);
+    let (shell, flag) = if cfg!(windows) { ("cmd", "/C") } else { ("sh", "-c") };
+    let mut process = Command::new(shell);
+    process.arg(flag).arg(command);
+    // on the host like the commands, the sandbox has none of the user's tools
+    let mut child = match flatpak::host(process)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => {
+            error!("Failed to run on_quit `{}`: {}", command, e);
+            return;
+        }
+    };
+
+    let start = Instant::now();
+    while start.elapsed() < TIMEOUT {
+        match child.try_wait() {
+            Ok(Some(status)) if status.success() => return info!("on_quit finished"),
+            Ok(Some(status)) => return warn!("on_quit failed: {}", status),
+            Ok(None) => thread::sleep(Duration::from_millis(50)),
+            Err(e) => return error!("Error waiting for on_quit: {}", e),
+        }
+    }
+    warn!("on_quit `{}` still running after {}s, killed", command, TIMEOUT.as_secs());
+    let _ = child.kill();
+    let _ = child.wait();
+}
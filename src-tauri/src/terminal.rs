@@ -0,0 +1,96 @@
+use crate::UserCommand;
+use crate::executor::{shell_invocation, shell_quote};
+use crate::files::{settings, working_dir};
+use crate::find_in_path;
+use std::env;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+// probed in this order when no terminal is configured
+const KNOWN_TERMINALS: [&str; 10] = [
+    "x-terminal-emulator",
+    "alacritty",
+    "kitty",
+    "foot",
+    "wezterm",
+    "gnome-terminal",
+    "konsole",
+    "xfce4-terminal",
+    "ptyxis",
+    "xterm",
+];
+
+/// configured terminal, then $TERMINAL, then the first known one installed
+pub fn detect_terminal() -> Result<String, String> {
+    let configured = settings().terminal;
+    if !configured.trim().is_empty() {
+        return Ok(configured);
+    }
+    if let Ok(term) = env::var("TERMINAL")
+        && find_in_path(&term).is_some()
+    {
+        return Ok(term);
+    }
+    KNOWN_TERMINALS
+        .iter()
+        .find(|t| find_in_path(t).is_some())
+        .map(|t| t.to_string())
+        .ok_or_else(|| "No terminal emulator found, set `terminal` in [settings]".to_string())
+}
+
+/// arguments placed between the terminal and the program it runs
+fn exec_args(terminal: &str) -> &'static [&'static str] {
+    let name = Path::new(terminal)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    match name.as_str() {
+        "kitty" | "foot" => &[],
+        "wezterm" => &["start", "--"],
+        "gnome-terminal" | "ptyxis" => &["--"],
+        "xfce4-terminal" => &["-x"],
+        _ => &["-e"],
+    }
+}
+
+/// open the command in a terminal window that stays open until Enter is pressed
+pub fn run_in_terminal(cmd: &UserCommand) -> Result<String, String> {
+    let (mut cmd, secrets) = crate::secrets::resolve(cmd.clone())?;
+    cmd.command = crate::prompt::strip_marker(&cmd.command);
+    let cmd = &cmd;
+    let terminal = detect_terminal()?;
+    let (program, flags) = shell_invocation(cmd);
+    let script = format!(
+        "{} {} {}; printf '\\n[exit %s] Press Enter to close' $?; read _",
+        shell_quote(&program),
+        flags.join(" "),
+        shell_quote(&cmd.command)
+    );
+    let mut process = Command::new(&terminal);
+    process.args(exec_args(&terminal)).args(["sh", "-c", &script]).envs(secrets);
+    if !cmd.cwd.trim().is_empty() {
+        process.current_dir(working_dir(&cmd.cwd)?);
+    }
+    spawn_detached(process, &terminal)?;
+    Ok(format!("Opened in {}", terminal))
+}
+
+/// interactive shell in a terminal window started in `cwd`
+pub fn open_terminal_at(cwd: &str) -> Result<String, String> {
+    let dir = working_dir(cwd)?;
+    let terminal = detect_terminal()?;
+    let mut process = Command::new(&terminal);
+    process.current_dir(&dir);
+    spawn_detached(process, &terminal)?;
+    Ok(format!("Opened {} in {}", terminal, dir.display()))
+}
+
+fn spawn_detached(process: Command, terminal: &str) -> Result<(), String> {
+    crate::flatpak::host(process)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to start terminal `{}`: {}", terminal, e))
+}
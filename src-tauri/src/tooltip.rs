@@ -0,0 +1,53 @@
+use std::sync::Mutex;
+use tauri::Manager;
+
+/// what the tray tooltip shows, each part updated by its owner
+#[derive(Default)]
+pub struct TrayStatus {
+    /// commands in the menu of this host
+    pub commands: usize,
+    /// last run: command, success, time
+    pub last: Option<(String, bool, String)>,
+    /// failing health checks
+    pub failing: Vec<String>,
+    pub queued: u64,
+    /// running commands a "Wait & quit" waits for
+    pub quitting: usize,
+}
+
+#[derive(Default)]
+pub struct Tooltip(Mutex<TrayStatus>);
+
+/// change the status and show it on the tray icon
+pub fn update(app: &tauri::AppHandle, change: impl FnOnce(&mut TrayStatus)) {
+    app.manage(Tooltip::default());
+    let tooltip = app.state::<Tooltip>();
+    let text = {
+        let mut status = tooltip.0.lock().unwrap_or_else(|e| e.into_inner());
+        change(&mut status);
+        text(&status)
+    };
+    if let Some(tray) = app.tray_by_id("main") {
+        let _ = tray.set_tooltip(Some(text));
+    }
+}
+
+fn text(status: &TrayStatus) -> String {
+    let mut lines = vec![
+        format!("Gucli {}", env!("CARGO_PKG_VERSION")),
+        format!("{} command(s)", status.commands),
+    ];
+    if let Some((command, success, time)) = &status.last {
+        lines.push(format!("Last: {} {} {}", if *success { "✓" } else { "✗" }, command, time));
+    }
+    if !status.failing.is_empty() {
+        lines.push(format!("Failing: {}", status.failing.join(", ")));
+    }
+    if status.queued > 0 {
+        lines.push(format!("{} queued", status.queued));
+    }
+    if status.quitting > 0 {
+        lines.push(format!("Quitting after {} running command(s)", status.quitting));
+    }
+    lines.join("\n")
+}
@@ -0,0 +1,96 @@
+use crate::{commands, create_tray, health, tooltip};
+use std::io::BufRead;
+#[cfg(target_os = "linux")]
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Stdio};
+use std::thread;
+use tracing::{debug, error, info};
+
+/// bus name of the StatusNotifier host (plasmashell, waybar, the GNOME extension...)
+const WATCHER: &str = "org.kde.StatusNotifierWatcher";
+
+/// register the tray icon again whenever a StatusNotifier host takes the bus name,
+/// icons of a crashed or reloaded panel are otherwise gone until the app restarts
+pub fn start(app: tauri::AppHandle) {
+    thread::spawn(move || {
+        let mut monitor = Command::new("gdbus");
+        monitor
+            .args([
+                "monitor",
+                "--session",
+                "--dest",
+                "org.freedesktop.DBus",
+                "--object-path",
+                "/org/freedesktop/DBus",
+            ])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null());
+        // ends with this thread, not left behind after quit; PR_SET_PDEATHSIG is Linux only
+        #[cfg(target_os = "linux")]
+        unsafe {
+            monitor.pre_exec(|| {
+                nix::libc::prctl(nix::libc::PR_SET_PDEATHSIG, nix::libc::SIGTERM);
+                Ok(())
+            });
+        }
+        let mut child = match monitor.spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                debug!("Tray host not watched, gdbus: {}", e);
+                return;
+            }
+        };
+        let Some(stdout) = child.stdout.take() else {
+            return;
+        };
+        for line in std::io::BufReader::new(stdout).lines().map_while(Result::ok) {
+            if host_appeared(&line) {
+                info!("StatusNotifier host (re)started, registering the tray icon again");
+                let handle = app.clone();
+                // tray icons are GTK objects, made on the main thread only
+                let _ = app.run_on_main_thread(move || reregister(&handle));
+            }
+        }
+        let _ = child.wait();
+    });
+}
+
+/// NameOwnerChanged ('org.kde.StatusNotifierWatcher', '<old>', '<new>') with a new owner
+fn host_appeared(line: &str) -> bool {
+    line.contains("NameOwnerChanged")
+        && line.contains(&format!("('{}',", WATCHER))
+        && !line.trim_end().ends_with(", '')")
+}
+
+fn reregister(app: &tauri::AppHandle) {
+    drop(app.remove_tray_by_id("main"));
+    if let Err(e) = create_tray(app, &commands(app)) {
+        error!("Tray icon not registered again: {}", e);
+        return;
+    }
+    // tooltip and badge live on the old icon, show them on the new one
+    let mut failing = false;
+    tooltip::update(app, |status| failing = !status.failing.is_empty());
+    health::show_badge(app, failing);
+}
+
+/// no StatusNotifier host on the session bus: a tray icon would be registered but never shown.
+/// False when gdbus can't tell
+pub fn host_missing() -> bool {
+    Command::new("gdbus")
+        .args([
+            "call",
+            "--session",
+            "--dest",
+            "org.freedesktop.DBus",
+            "--object-path",
+            "/org/freedesktop/DBus",
+            "--method",
+            "org.freedesktop.DBus.NameHasOwner",
+            WATCHER,
+        ])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .is_some_and(|output| String::from_utf8_lossy(&output.stdout).contains("false"))
+}
@@ -0,0 +1,157 @@
+use crate::UserCommand;
+use crate::autostart::{systemctl, write_file};
+use crate::executor;
+use crate::files::get_home_dir;
+use crate::prompt;
+use std::fs;
+use tracing::info;
+
+const UNIT_DIR: &str = ".config/systemd/user";
+const UNIT_MARKER: &str = "# exported from gucli";
+// how often a timer runs a command without health_interval, edit OnCalendar= for another
+const DEFAULT_CALENDAR: &str = "hourly";
+
+/// write gucli-<name>.service (and .timer) to ~/.config/systemd/user, not enabled
+#[tauri::command]
+pub fn export_unit(cmd: UserCommand, timer: bool) -> Result<String, String> {
+    // nobody is there to answer a dialog or unlock the keyring
+    if prompt::wants_stdin(&cmd.command) || prompt::wants_path(&cmd.command) {
+        return Err("Commands with {stdin}, {file} or {dir} ask at run time and cannot run as a unit".to_string());
+    }
+    if prompt::wants_password(&cmd.command) {
+        return Err("Commands with {password:…} ask at run time and cannot run as a unit".to_string());
+    }
+    if cmd.command.contains("{secret:") {
+        return Err("Commands with {secret:…} cannot run as a unit, the secret would be written into it".to_string());
+    }
+    if cmd.in_terminal {
+        return Err("in_terminal commands need a window and cannot run as a unit".to_string());
+    }
+
+    let process = executor::select(&cmd)?.prepare(&cmd)?;
+    let mut exec = vec![unit_arg(&process.get_program().to_string_lossy())];
+    exec.extend(process.get_args().map(|arg| unit_arg(&arg.to_string_lossy())));
+
+    let name = unit_name(&cmd);
+    let dir = get_home_dir()?.join(UNIT_DIR);
+    let mut service = format!(
+        "{}\n\
+        [Unit]\n\
+        Description=Gucli: {}\n\
+        \n\
+        [Service]\n\
+        Type={}\n\
+        ExecStart={}\n",
+        UNIT_MARKER,
+        cmd.display_name().replace('\n', " "),
+        if cmd.long_running && !timer { "simple" } else { "oneshot" },
+        exec.join(" ")
+    );
+    if let Some(cwd) = process.get_current_dir() {
+        // a path, not a command line: no quotes, only specifiers are expanded
+        service.push_str(&format!("WorkingDirectory={}\n", cwd.to_string_lossy().replace('%', "%%")));
+    }
+    // a timer starts the service, it needs no [Install] of its own
+    if !timer {
+        service.push_str("\n[Install]\nWantedBy=default.target\n");
+    }
+    write_unit(&dir.join(format!("{}.service", name)), &service)?;
+
+    let enable = if timer {
+        let schedule = match cmd.health_interval {
+            Some(secs) => format!("OnBootSec={}s\nOnUnitActiveSec={}s\n", secs, secs),
+            None => format!("OnCalendar={}\nPersistent=true\n", DEFAULT_CALENDAR),
+        };
+        let timer_unit = format!(
+            "{}\n\
+            [Unit]\n\
+            Description=Gucli timer: {}\n\
+            \n\
+            [Timer]\n\
+            {}\
+            \n\
+            [Install]\n\
+            WantedBy=timers.target\n",
+            UNIT_MARKER,
+            cmd.display_name().replace('\n', " "),
+            schedule
+        );
+        write_unit(&dir.join(format!("{}.timer", name)), &timer_unit)?;
+        format!("{}.timer", name)
+    } else {
+        format!("{}.service", name)
+    };
+    systemctl(&["daemon-reload"])?;
+    info!("Exported `{}` as {}", cmd.command, enable);
+    Ok(format!(
+        "Written {}/{}, enable it with: systemctl --user enable --now {}",
+        dir.display(),
+        enable,
+        enable
+    ))
+}
+
+/// a unit written by hand under the same name is not overwritten
+fn write_unit(path: &std::path::Path, content: &str) -> Result<(), String> {
+    if fs::read_to_string(path).is_ok_and(|unit| !unit.starts_with(UNIT_MARKER)) {
+        return Err(format!("{} exists and was not exported by gucli", path.display()));
+    }
+    write_file(&path.to_path_buf(), content)
+}
+
+/// gucli-<label or command>, lowercase letters, digits and dashes
+fn unit_name(cmd: &UserCommand) -> String {
+    let mut name = String::from("gucli");
+    for word in cmd
+        .display_name()
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .take(4)
+    {
+        name.push('-');
+        name.push_str(word);
+    }
+    // plain gucli.service is the autostart unit
+    if name == "gucli" {
+        name.push_str("-command");
+    }
+    name
+}
+
+/// one ExecStart= argument, double-quoted: systemd expands % specifiers and $VARIABLES
+pub(crate) fn unit_arg(arg: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in arg.chars() {
+        match c {
+            '"' | '\\' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            '%' => quoted.push_str("%%"),
+            '$' => quoted.push_str("$$"),
+            '\n' => quoted.push_str("\\n"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::unit_arg;
+
+    #[test]
+    fn unit_arg_quotes_paths() {
+        assert_eq!(unit_arg("/usr/bin/gucli"), r#""/usr/bin/gucli""#);
+        assert_eq!(unit_arg("/opt/My Apps/gucli"), r#""/opt/My Apps/gucli""#);
+    }
+
+    #[test]
+    fn unit_arg_escapes_specifiers_and_variables() {
+        assert_eq!(unit_arg("50% of $HOME"), r#""50%% of $$HOME""#);
+        assert_eq!(unit_arg(r#"say "hi" \ bye"#), r#""say \"hi\" \\ bye""#);
+        assert_eq!(unit_arg("a\nb"), r#""a\nb""#);
+    }
+}
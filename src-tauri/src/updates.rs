@@ -0,0 +1,77 @@
+use crate::AppCommandsConfig;
+use crate::files::settings;
+use crate::send_notification;
+use gucli_shared::ReleaseInfo;
+use std::thread;
+use std::time::Duration;
+use tracing::{debug, info};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// `check_updates` in [settings]: look for a newer release now and once a day, notify once per version
+pub fn start(config: &AppCommandsConfig) {
+    if !config.settings.check_updates {
+        return;
+    }
+    thread::spawn(|| {
+        let mut notified = String::new();
+        loop {
+            match latest() {
+                Ok(release) if release.newer && release.version != notified => {
+                    info!("Gucli {} is available: {}", release.version, release.url);
+                    send_notification(
+                        "Ok( Update available )",
+                        &format!("Gucli {} is out: {}", release.version, release.url),
+                    );
+                    notified = release.version;
+                }
+                Ok(_) => {}
+                Err(e) => debug!("Update check failed: {}", e),
+            }
+            thread::sleep(CHECK_INTERVAL);
+        }
+    });
+}
+
+/// latest release for the About tab, None while the check is off or when it failed
+#[tauri::command]
+pub async fn check_update() -> Option<ReleaseInfo> {
+    if !settings().check_updates {
+        return None;
+    }
+    // ureq blocks for up to its timeout, off the async runtime's workers
+    tauri::async_runtime::spawn_blocking(latest)
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|release| release)
+        .inspect_err(|e| debug!("Update check failed: {}", e))
+        .ok()
+}
+
+/// newest release of the repository from get_app_info, through the GitHub API
+fn latest() -> Result<ReleaseInfo, String> {
+    let api = env!("CARGO_PKG_REPOSITORY").replace("https://github.com/", "https://api.github.com/repos/");
+    let release: serde_json::Value = ureq::get(&format!("{}/releases/latest", api))
+        .set("User-Agent", concat!("gucli/", env!("CARGO_PKG_VERSION")))
+        .timeout(Duration::from_secs(10))
+        .call()
+        .map_err(|e| e.to_string())?
+        .into_json()
+        .map_err(|e| e.to_string())?;
+    let version = release["tag_name"]
+        .as_str()
+        .ok_or("no tag_name in the release")?
+        .trim_start_matches('v')
+        .to_string();
+    Ok(ReleaseInfo {
+        newer: is_newer(&version, env!("CARGO_PKG_VERSION")),
+        url: release["html_url"].as_str().unwrap_or(env!("CARGO_PKG_REPOSITORY")).to_string(),
+        version,
+    })
+}
+
+/// numeric compare of dotted versions, "1.10.0" is newer than "1.9.2"
+fn is_newer(version: &str, current: &str) -> bool {
+    let parts = |v: &str| -> Vec<u64> { v.split(['.', '-']).map_while(|p| p.parse().ok()).collect() };
+    parts(version) > parts(current)
+}
@@ -0,0 +1,81 @@
+use crate::files::config_dir;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use tauri::{Runtime, WebviewWindow};
+use tracing::{debug, warn};
+
+pub const WINDOW_STATE_FILE: &str = "window.json";
+
+/// settings window geometry in logical pixels, saved on close
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct WindowState {
+    pub x: Option<f64>,
+    pub y: Option<f64>,
+    pub width: f64,
+    pub height: f64,
+    #[serde(default)]
+    pub maximized: bool,
+}
+
+impl Default for WindowState {
+    fn default() -> Self {
+        // position left to the window manager
+        WindowState {
+            x: None,
+            y: None,
+            width: 800.0,
+            height: 600.0,
+            maximized: false,
+        }
+    }
+}
+
+pub fn load() -> WindowState {
+    config_dir()
+        .ok()
+        .and_then(|dir| fs::read(dir.join(WINDOW_STATE_FILE)).ok())
+        .and_then(|json| serde_json::from_slice(&json).ok())
+        .unwrap_or_default()
+}
+
+/// store the current geometry, a maximized window keeps its last normal size
+pub fn save<R: Runtime>(window: &WebviewWindow<R>) {
+    let mut state = load();
+    state.maximized = window.is_maximized().unwrap_or(false);
+    if !state.maximized {
+        let scale = window.scale_factor().unwrap_or(1.0);
+        if let Ok(size) = window.inner_size() {
+            let size = size.to_logical::<f64>(scale);
+            state.width = size.width;
+            state.height = size.height;
+        }
+        if let Ok(position) = window.outer_position() {
+            let position = position.to_logical::<f64>(scale);
+            state.x = Some(position.x);
+            state.y = Some(position.y);
+        }
+    }
+
+    let result = config_dir().and_then(|dir| {
+        let json = serde_json::to_vec_pretty(&state).map_err(|e| e.to_string())?;
+        fs::write(dir.join(WINDOW_STATE_FILE), json).map_err(|e| e.to_string())
+    });
+    match result {
+        Ok(()) => debug!("Window state saved: {:?}", state),
+        Err(e) => warn!("Window state not saved: {}", e),
+    }
+}
+
+/// saved position, if it is still on a connected monitor
+pub fn visible_position<R: Runtime>(window: &WebviewWindow<R>, state: &WindowState) -> Option<(f64, f64)> {
+    let (x, y) = (state.x?, state.y?);
+    let monitors = window.available_monitors().ok()?;
+    monitors
+        .iter()
+        .any(|m| {
+            let pos = m.position().to_logical::<f64>(m.scale_factor());
+            let size = m.size().to_logical::<f64>(m.scale_factor());
+            x >= pos.x && y >= pos.y && x < pos.x + size.width && y < pos.y + size.height
+        })
+        .then_some((x, y))
+}
@@ -1,36 +1,32 @@
 use leptos::prelude::*;
 use leptos::task::spawn_local;
-use serde::{Deserialize, Serialize};
+use gucli_shared::i18n::{LANGUAGES, tr};
+use gucli_shared::{
+    AppSettings, AproposEntry, AUTOSTART_BACKENDS, CommandExecuted, ConfigProblem, CustomTheme, ReleaseInfo, LEFT_CLICK_ACTIONS, ON_BUSY_POLICIES, RunStats, RunningProcess, SelfTestItem, TestResult, UserCommand as Command, tray_order,
+};
+use serde::Serialize;
 use serde_wasm_bindgen::{from_value, to_value};
 use wasm_bindgen::prelude::*;
 use chrono::Local;
 use leptos::ev::KeyboardEvent;
 use web_sys::window;
+use crate::output::{JsonView, parse_json};
+use crate::palette::{CommandPalette, PaletteAction, PaletteEntry};
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
-pub struct Command {
-    pub id: String,
-    pub shell: String,
-    pub command: String,
-    pub icon: String,
-    pub sn: bool,
+#[derive(Serialize)]
+struct RunTestArgs {
+    cmd: Command,
 }
 
-impl Command {
-    pub fn new(id: String) -> Self {
-        Command {
-            id,
-            shell: String::from("sh"),
-            command: String::from("new"),
-            icon: String::from(""),
-            sn: true,
-        }
-    }
+#[derive(Serialize)]
+struct EncryptArgs {
+    on: bool,
 }
 
 #[derive(Serialize)]
-struct RunTestArgs {
+struct ExportArgs {
     cmd: Command,
+    timer: bool,
 }
 
 #[derive(Serialize)]
@@ -38,6 +34,11 @@ struct SaveBackArgs {
     commands: Vec<Command>,
 }
 
+#[derive(Serialize)]
+struct SuggestArgs {
+    prefix: String,
+}
+
 #[derive(Serialize)]
 struct CtrlWindow<'a> {
     action: &'a str,
@@ -46,6 +47,32 @@ struct CtrlWindow<'a> {
 #[derive(Serialize)]
 struct ManHelp {
     cmd: String,
+    refresh: bool,
+}
+
+#[derive(Serialize)]
+struct AproposArgs {
+    keyword: String,
+}
+
+#[derive(Serialize)]
+struct LanguageArgs {
+    lang: String,
+}
+
+#[derive(Serialize)]
+struct BackendArgs {
+    backend: String,
+}
+
+#[derive(Serialize)]
+struct DelayArgs {
+    delay: u32,
+}
+
+#[derive(Serialize)]
+struct SettingsArgs {
+    settings: AppSettings,
 }
 
 #[derive(Serialize)]
@@ -57,34 +84,17 @@ struct OpenFile<'a> {
 extern "C" {
     // invoke without arguments
     #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"], js_name = invoke)]
-    async fn invoke_without_args(cmd: &str) -> JsValue;
+    pub(crate) async fn invoke_without_args(cmd: &str) -> JsValue;
     // invoke with arguments (default)
     #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"])]
-    async fn invoke(cmd: &str, args: JsValue) -> JsValue;
-}
-
-static SETTINGS_HELP: &str = 
-"<ul>
-    <li>Program executes shell commands that return either nothing or string-convertible output</li>
-    <li>Command settings can be edited in <code>/home/$USER/.config/gucli/commands.toml</code> without opening this window (restart required)</li>
-    <li>Errors and results are logged to <code>/home/$USER/.config/gucli/gucli.log</code> (100 line limit, no rotation needed)</li>
-    <li>Interactive commands or commands with continuous output are not recommended</li>
-    <li>Command execution timeout: 500ms (add <code>&</code> to bypass)</li>
-    <li>Notification text limited to 200 characters (long messages may freeze GTK)</li>
-    <li>Commands in tray menu display as-is - use <code>aliases</code> or shell scripts for long entries</li>
-    <li>icon: up to 8 UTF-8 characters (emoji, short text, or empty)</li>
-    <li>sn (show notification): show system notification (default: true). Error notifications always show</li>
-    <li>Always test commands first, even if you know what you're doing</li>
-</ul>";
-
-static SEARCH_HELP: &str = 
-"<ul>
-    <li>If you need an exact reference with specific attributes, write it in full</li>
-    <li>Otherwise, the program will iterate through this set:<br />
-    <code>[ --help, -h, --usage, help, -help, -?, --longhelp, --long-help, --help-all, info]</code><br />
-    then check the man pages, and return the first matching option found.</li>
-    <li>To prevent the window from freezing, the maximum result length is limited to 30,000 characters</li>
-</ul>";
+    pub(crate) async fn invoke(cmd: &str, args: JsValue) -> JsValue;
+    // backend events, the handler gets {event, payload}
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "event"])]
+    async fn listen(event: &str, handler: &Closure<dyn FnMut(JsValue)>) -> JsValue;
+}
+
+// settings table sort modes, only "manual" is the tray order
+const SORT_MODES: [&str; 4] = ["manual", "alphabetical", "most used", "recently failed"];
 
 #[component]
 pub fn App() -> impl IntoView {
@@ -93,49 +103,112 @@ pub fn App() -> impl IntoView {
     let (is_maximized, set_is_maximized) = signal("max0");
     let (autostart, set_autostart) = signal(false);
     let (status, set_status) = signal(String::from(""));
+    // last run shown in the status, kept as is and rendered as text in place of `status`
+    let test_output = RwSignal::new(None::<(String, TestResult)>);
+    let show_test = move |command: String, result: TestResult| {
+        set_status.set(String::new());
+        test_output.set(Some((command, result)));
+    };
 
     let reset = RwSignal::new(false);
+    // command id -> "ok" | "err" | "warn" (skipped), rows are colored by it
+    let test_results = RwSignal::new(std::collections::HashMap::<String, &'static str>::new());
+    // validate_config report of the last save, by row
+    let problems = RwSignal::new(Vec::<ConfigProblem>::new());
     let active_tab = RwSignal::new(0);
     let unsaved_changes = RwSignal::new("");
     let highlight = RwSignal::new(false);
     let ttime = RwSignal::new(String::from(""));
 
+    //+ UI language, shared with ManSearch & About through context
+    let lang = RwSignal::new(String::from("en"));
+    provide_context(lang);
+    spawn_local(async move {
+        let js = invoke_without_args("get_language").await;
+        if let Ok(code) = from_value::<String>(js) {
+            lang.set(code);
+        }
+    });
+    let t = move |key: &'static str| tr(&lang.get(), key);
+
     // Theme switcher
-        let mut initial_theme = "light".to_string();
         let local_storage_theme: Option<String> = window()//get value from localStorage 
             .and_then(|w| w.local_storage().ok())
             .and_then(|s| s?.get("theme").ok())
             .flatten();
-        // 2. get system theme IF localStorage None or empty
-        let prefer = match local_storage_theme.as_deref() {
-            Some(theme) if !theme.is_empty() => None,
-            _ => {
-                window()
-                    .and_then(|w| w.match_media("(prefers-color-scheme: dark)").ok())
-                    .flatten()
-                    .map(|mql| if mql.matches() { "dark" } else { "light" })
-            }
+        // 2. nothing chosen yet: "auto", the window follows the system theme
+        let initial_theme = match local_storage_theme {
+            Some(theme) if !theme.is_empty() => theme,
+            _ => "auto".to_string(),
         };
-        // 3. set theme
-        if let Some(theme) = local_storage_theme {
-            if !theme.is_empty() {
-                initial_theme = theme;
-            }
-        } else if let Some(pref) = prefer {
-            initial_theme = pref.to_string();
-        }
         let (theme, set_theme) = signal(initial_theme);
         let toggle_theme = move || {
             set_theme.update(|t| {
                 *t = match t.as_str() {
                     "light" => "dark".into(),
                     "dark" => "a11y".into(),
-                    "a11y" => "light".into(),
+                    "a11y" => "auto".into(),
+                    "auto" => "custom".into(),
+                    "custom" => "light".into(),
                     _ => "light".into(),
                 };
             });
         };
 
+        // "custom": [settings.custom_theme], edited in About through context
+        let custom_theme = RwSignal::new(CustomTheme::default());
+        provide_context(custom_theme);
+        spawn_local(async move {
+            let js = invoke_without_args("get_settings").await;
+            if let Ok(settings) = from_value::<AppSettings>(js) {
+                custom_theme.set(settings.custom_theme);
+            }
+        });
+
+        // "auto": desktop color-scheme (org.freedesktop.appearance), else the webview's prefers-color-scheme
+        let (auto_theme, set_auto_theme) = signal("light".to_string());
+        let refresh_auto_theme = move || {
+            spawn_local(async move {
+                let js = invoke_without_args("get_color_scheme").await;
+                let resolved = match from_value::<Option<String>>(js).ok().flatten() {
+                    Some(scheme) => scheme,
+                    None => {
+                        let dark = window()
+                            .and_then(|w| w.match_media("(prefers-color-scheme: dark)").ok().flatten())
+                            .is_some_and(|mql| mql.matches());
+                        if dark { "dark".into() } else { "light".into() }
+                    }
+                };
+                if auto_theme.get_untracked() != resolved {
+                    set_auto_theme.set(resolved);
+                }
+            });
+        };
+        refresh_auto_theme();
+        set_interval(refresh_auto_theme, std::time::Duration::from_secs(60));
+
+        // live switches: the portal's SettingChanged from the backend, the webview's media query
+        spawn_local(async move {
+            let on_scheme = Closure::<dyn FnMut(JsValue)>::new(move |event: JsValue| {
+                let payload = js_sys::Reflect::get(&event, &JsValue::from_str("payload")).unwrap_or(JsValue::NULL);
+                match from_value::<Option<String>>(payload).ok().flatten() {
+                    Some(scheme) => set_auto_theme.set(scheme),
+                    None => refresh_auto_theme(),
+                }
+            });
+            let _ = listen("color-scheme", &on_scheme).await;
+            on_scheme.forget();
+        });
+        if let Some(mql) = window().and_then(|w| w.match_media("(prefers-color-scheme: dark)").ok()).flatten() {
+            let on_change = Closure::<dyn FnMut(web_sys::MediaQueryListEvent)>::new(
+                move |ev: web_sys::MediaQueryListEvent| {
+                    set_auto_theme.set(if ev.matches() { "dark" } else { "light" }.to_string());
+                },
+            );
+            let _ = mql.add_event_listener_with_callback("change", on_change.as_ref().unchecked_ref());
+            on_change.forget();
+        }
+
         
     //+ init commands on open window
     spawn_local(async move {
@@ -149,33 +222,109 @@ pub fn App() -> impl IntoView {
         }
     });
 
-    //+ Save (check for uniqueness/non-emptiness of names and, if everything is ok, write it to commands & save to commands.toml)
-    let save = move |buf: Vec<Command>| {
-        // Check "name" - not empty & unique
-        let mut names = std::collections::HashSet::new();
-        for cmd in &buf {
-            if cmd.command.trim().is_empty() {
-                set_status.set("Err( Field `command` cannot be empty )".to_string());
-                return;
+    //+ commands.toml that failed to load at startup, with the parse error
+    let config_error = RwSignal::new(None::<String>);
+    spawn_local(async move {
+        let js = invoke_without_args("get_config_error").await;
+        config_error.set(from_value::<Option<String>>(js).ok().flatten());
+    });
+    let restore_backup = move || {
+        spawn_local(async move {
+            let js = invoke_without_args("restore_commands").await;
+            let result: Result<String, String> = from_value(js).map_err(|e| format!("deserialize failed: {e}"));
+            match result {
+                Ok(msg) => {
+                    set_status.set(format!("Ok( {msg} )"));
+                    let _ = invoke("request_restart", JsValue::NULL).await;
+                }
+                Err(e) => set_status.set(format!("Err( Restore failed: {e} )")),
             }
-            if !names.insert(cmd.command.clone()) {
-                set_status.set("Err( Field `command` must be unique )".to_string());
-                return;
+        });
+    };
+
+    //+ sort modes: all but "manual" only reorder the table, the tray order stays in manual_order until applied
+    let sort_mode = RwSignal::new("manual");
+    let manual_order = RwSignal::new(Vec::<String>::new());
+    let (run_stats, set_run_stats) = signal(Vec::<RunStats>::new());
+    let refresh_run_stats = move || {
+        spawn_local(async move {
+            let js = invoke_without_args("get_run_stats").await;
+            set_run_stats.set(from_value::<Vec<RunStats>>(js).unwrap_or_default());
+        });
+    };
+    refresh_run_stats();
+
+    //+ runs from the tray, the socket or HTTP while the window is open: status and stats follow
+    spawn_local(async move {
+        let on_executed = Closure::<dyn FnMut(JsValue)>::new(move |event: JsValue| {
+            let payload = js_sys::Reflect::get(&event, &JsValue::from_str("payload")).unwrap_or(JsValue::NULL);
+            if let Ok(executed) = from_value::<CommandExecuted>(payload) {
+                show_test(executed.command, executed.result);
+                refresh_run_stats();
             }
+        });
+        let _ = listen("command-executed", &on_executed).await;
+        on_executed.forget();
+    });
+    let sort_by = move |mode: &'static str| {
+        let mut buf = commands.get_untracked();
+        if sort_mode.get_untracked() == "manual" {
+            manual_order.set(buf.iter().map(|c| c.id.clone()).collect());
         }
-        // if ok -> save & restart
+        let stats = run_stats.get_untracked();
+        let stat = |c: &Command| stats.iter().find(|s| s.command == c.command).cloned().unwrap_or_default();
+        match mode {
+            "alphabetical" => buf.sort_by_key(|c| c.command.to_lowercase()),
+            "most used" => buf.sort_by_key(|c| std::cmp::Reverse(stat(c).runs)),
+            "recently failed" => buf.sort_by_key(|c| stat(c).failed_rank.unwrap_or(usize::MAX)),
+            _ => buf = in_order(buf, &manual_order.get_untracked()),
+        }
+        sort_mode.set(mode);
+        set_commands.set(buf);
+    };
+    // the sorted view becomes the manual (tray) order
+    let apply_sort = move || {
+        sort_mode.set("manual");
+        set_status.set("Ok( Sorted order applied, save to update the tray )".to_string());
+    };
+    // commands in the order they are saved in
+    let commands_in_tray_order = move || match sort_mode.get() {
+        "manual" => commands.get(),
+        _ => in_order(commands.get(), &manual_order.get()),
+    };
+
+    //+ Save (check for uniqueness/non-emptiness of names and, if everything is ok, write it to commands & save to commands.toml)
+    let save = move |buf: Vec<Command>| {
+        // a sorted view is saved in the manual order
+        let buf = match sort_mode.get_untracked() {
+            "manual" => buf,
+            _ => in_order(buf, &manual_order.get_untracked()),
+        };
+        sort_mode.set("manual");
         set_commands.update(move |c| *c = buf.clone());
         log::debug!("save->commands: {:?}", commands.get());
         spawn_local(async move {
             let commands = commands.get_untracked();
-            let args = to_value(&SaveBackArgs { commands }).unwrap();
+            // problems are shown under their rows, nothing is saved while there are errors
+            let args = to_value(&SaveBackArgs { commands: commands.clone() }).unwrap();
+            let found = from_value::<Vec<ConfigProblem>>(invoke("validate_config", args).await).unwrap_or_default();
+            let errors = found.iter().filter(|p| !p.warning).count();
+            problems.set(found);
+            if errors > 0 {
+                set_status.set(format!("Err( Not saved, {} problem(s), see the marked rows )", errors));
+                return;
+            }
+            let args = to_value(&SaveBackArgs { commands: commands.clone() }).unwrap();
             let js = invoke("set_commands", args).await;
             let result: Result<String, String> = from_value(js).map_err(|e| format!("deserialize failed: {e}"));
             match result {
-                Ok(_) => { set_status.set("Ok( Commands saved )".to_string());}
+                // the tray menu is rebuilt by the backend, the window stays open
+                Ok(_) => {
+                    set_status.set("Ok( Commands saved, tray updated )".to_string());
+                    set_commands0.set(commands);
+                }
                 Err(e) => set_status.set(format!("Err( Save failed: {e} )")),
             }
-            let _ = invoke("request_restart", JsValue::NULL).await;
         });
     };
 
@@ -199,14 +348,57 @@ pub fn App() -> impl IntoView {
         }
     };
 
+    // DEFAULT_SHELL of the backend's platform, the wasm build only ever sees "sh"
+    let (default_shell, set_default_shell) = signal(String::new());
+    spawn_local(async move {
+        let js = invoke_without_args("get_default_shell").await;
+        set_default_shell.set(from_value::<String>(js).unwrap_or_default());
+    });
+
     //+ Add a new row with default values
     let add_command = move || {
         let mut buf = commands.get();
-        buf.push(Command::new(generate_id()));
+        let mut cmd = Command::new(generate_id());
+        let shell = default_shell.get();
+        if !shell.is_empty() {
+            cmd.shell = shell;
+        }
+        buf.push(cmd);
         set_commands.update(move |b| *b = buf.clone());
         set_status.set("Warning( Specify the command and its parameters and test it )".to_string());
     };
 
+    //+ bulk actions on the rows checked in the order cell
+    let selected = RwSignal::new(std::collections::HashSet::<String>::new());
+    let delete_selected = move || {
+        let ids = selected.get();
+        set_commands.update(|cmds| cmds.retain(|c| !ids.contains(&c.id)));
+        set_status.set(format!("Ok( {} commands deleted )", ids.len()));
+        selected.set(Default::default());
+    };
+    let set_sn_selected = move |sn: bool| {
+        let ids = selected.get();
+        set_commands.update(|cmds| {
+            cmds.iter_mut().filter(|c| ids.contains(&c.id)).for_each(|c| c.sn = sn);
+        });
+        set_status.set(format!("Ok( Notifications {} for {} commands )", if sn { "on" } else { "off" }, ids.len()));
+    };
+
+    //+ Copy a command below the original, with a new id
+    let duplicate_command = move |index: usize| {
+        let mut buf = commands.get();
+        if let Some(cmd) = buf.get(index) {
+            let copy = Command {
+                id: generate_id(),
+                tray_position: None,
+                ..cmd.clone()
+            };
+            buf.insert(index + 1, copy);
+            set_commands.set(buf);
+            set_status.set("Warn( Command duplicated, edit it: commands must be unique )".to_string());
+        }
+    };
+
     //+ Delete a command by index (+ auto-save)
     let delete_command = move |index: usize| {
         let mut buf = commands.get();
@@ -224,10 +416,11 @@ pub fn App() -> impl IntoView {
             return;
         }
         spawn_local(async move {
+            let command = cmd.command.clone();
             let args = to_value(&RunTestArgs { cmd }).unwrap();
             let js = invoke("run_test", args).await;
-            match from_value::<String>(js) {
-                Ok(success_msg) => set_status.set(success_msg),
+            match from_value::<TestResult>(js) {
+                Ok(result) => show_test(command, result),
                 Err(e) => {
                     set_status.set(format!("Err( Command execution failed {e:?} )"));
                 }
@@ -235,13 +428,119 @@ pub fn App() -> impl IntoView {
         });
     };
 
+    // shown as text, the status is rendered as html
+    let preview = move |cmd: Command| {
+        spawn_local(async move {
+            let args = to_value(&RunTestArgs { cmd }).unwrap();
+            let js = invoke("preview_command", args).await;
+            match from_value::<Result<String, String>>(js) {
+                Ok(Ok(text)) => set_status.set(format!("Ok( Preview, not run ):\n{}", escape_html(&text))),
+                Ok(Err(e)) => set_status.set(format!("Err( Preview failed: {} )", escape_html(&e))),
+                Err(e) => set_status.set(format!("Err( Preview failed: {} )", e)),
+            }
+        });
+    };
+
+    let export_unit = move |cmd: Command, timer: bool| {
+        spawn_local(async move {
+            let args = to_value(&ExportArgs { cmd, timer }).unwrap();
+            let js = invoke("export_unit", args).await;
+            match from_value::<Result<String, String>>(js) {
+                Ok(Ok(text)) => set_status.set(format!("Ok( {} )", escape_html(&text))),
+                Ok(Err(e)) => set_status.set(format!("Err( Export failed: {} )", escape_html(&e))),
+                Err(e) => set_status.set(format!("Err( Export failed: {} )", e)),
+            }
+        });
+    };
+
+    //+ "Test all": every row run once, in table order, after a confirming second click
+    let testing_all = RwSignal::new(false);
+    let confirm_test_all = RwSignal::new(false);
+    let test_all = move || {
+        if testing_all.get_untracked() {
+            return;
+        }
+        if !confirm_test_all.get_untracked() {
+            confirm_test_all.set(true);
+            set_status.set(format!(
+                "Warn( Click again to run all {} commands one after another )",
+                commands.get_untracked().len()
+            ));
+            return;
+        }
+        confirm_test_all.set(false);
+        testing_all.set(true);
+        test_results.set(Default::default());
+        spawn_local(async move {
+            let (mut passed, mut failed) = (0, 0);
+            for cmd in commands.get_untracked() {
+                let id = cmd.id.clone();
+                let outcome = if cmd.command.trim().is_empty() {
+                    "err"
+                } else {
+                    let args = to_value(&RunTestArgs { cmd }).unwrap();
+                    from_value::<TestResult>(invoke("run_test", args).await).map_or("err", |result| test_outcome(&result))
+                };
+                match outcome {
+                    "ok" => passed += 1,
+                    "err" => failed += 1,
+                    _ => {}
+                }
+                test_results.update(|results| {
+                    results.insert(id, outcome);
+                });
+            }
+            testing_all.set(false);
+            set_status.set(if failed == 0 {
+                format!("Ok( All {} tested commands passed )", passed)
+            } else {
+                format!("Err( {} failed, {} passed )", failed, passed)
+            });
+        });
+    };
+    // destructive patterns get a red banner, the tray asks twice before running them
+    let danger = move |i: usize| {
+        commands.get().get(i).and_then(|c| gucli_shared::lint::dangerous(&c.command))
+    };
+    let row_problems = move |i: usize| {
+        problems.with(|all| all.iter().filter(|p| p.row == i).cloned().collect::<Vec<_>>())
+    };
+    // a row's closures can still run for an index that was just deleted, read it as a blank command
+    let row = move |i: usize| commands.get().get(i).cloned().unwrap_or_default();
+    let test_result = move |i: usize| {
+        let id = commands.get().get(i).map(|c| c.id.clone()).unwrap_or_default();
+        test_results.get().get(&id).copied()
+    };
+
+    //+ autocomplete for the command input: binaries from $PATH & previously run commands
+    let completions = RwSignal::new(Vec::<String>::new());
+    // debounced: only the last keystroke of a 200 ms pause asks the backend
+    let suggest_generation = RwSignal::new(0u32);
+    let suggest = move |prefix: String| {
+        let generation = suggest_generation.get_untracked().wrapping_add(1);
+        suggest_generation.set(generation);
+        set_timeout(
+            move || {
+                if suggest_generation.get_untracked() != generation {
+                    return;
+                }
+                spawn_local(async move {
+                    let args = to_value(&SuggestArgs { prefix }).unwrap();
+                    let js = invoke("suggest_completions", args).await;
+                    completions.set(from_value::<Vec<String>>(js).unwrap_or_default());
+                });
+            },
+            std::time::Duration::from_millis(200),
+        );
+    };
+
     let ctrl_window = move |ctrl| {
         if ctrl == "max0"{
             set_is_maximized.set("max1");
         }else {set_is_maximized.set("max0");}
         
         if ctrl == "close" {
-            let has_unsaved_changes = commands0.get() != commands.get();
+            let has_unsaved_changes = commands0.get() != commands_in_tray_order();
             let warn = "Warning( Are there unsaved changes, really quit? )".to_string();
             if has_unsaved_changes && !status.get().starts_with(&warn) {
                 set_status.set(warn);
@@ -267,6 +566,51 @@ pub fn App() -> impl IntoView {
     };
     autostart_status();
 
+    let (autostart_backend, set_autostart_backend) = signal("desktop".to_string());
+    spawn_local(async move {
+        let js = invoke_without_args("get_autostart_backend").await;
+        if let Ok(backend) = from_value::<String>(js) {
+            set_autostart_backend.set(backend);
+        }
+    });
+    // an enabled autostart is moved to the chosen backend
+    let change_autostart_backend = move |backend: String| {
+        spawn_local(async move {
+            let args = to_value(&BackendArgs { backend: backend.clone() }).unwrap();
+            let js = invoke("set_autostart_backend", args).await;
+            match from_value::<Result<String, String>>(js) {
+                Ok(Ok(_)) => {
+                    set_autostart_backend.set(backend);
+                    set_status.set("Ok( Autostart updated )".to_string());
+                }
+                Ok(Err(e)) => set_status.set(format!("Err( {} )", e)),
+                Err(e) => set_status.set(format!("Err( {} )", e)),
+            }
+            autostart_status();
+        });
+    };
+
+    let (autostart_delay, set_autostart_delay) = signal(0u32);
+    spawn_local(async move {
+        let js = invoke_without_args("get_autostart_delay").await;
+        if let Ok(delay) = from_value::<u32>(js) {
+            set_autostart_delay.set(delay);
+        }
+    });
+    let change_autostart_delay = move |value: String| {
+        let delay = value.trim().parse::<u32>().unwrap_or(0);
+        set_autostart_delay.set(delay);
+        spawn_local(async move {
+            let args = to_value(&DelayArgs { delay }).unwrap();
+            let js = invoke("set_autostart_delay", args).await;
+            match from_value::<Result<String, String>>(js) {
+                Ok(Ok(msg)) => set_status.set(format!("Ok( {} )", msg)),
+                Ok(Err(e)) => set_status.set(format!("Err( {} )", e)),
+                Err(e) => set_status.set(format!("Err( {} )", e)),
+            }
+        });
+    };
+
     let toggle_autostart = move || {
         spawn_local(async move {
             // Check current status for autostart
@@ -281,22 +625,103 @@ pub fn App() -> impl IntoView {
     //+ move command in vec - up & down id
     let move_command = move |up: bool,n:usize| {
         let mut buf = commands.get();
-        let dir = if up { n-1 } else { n+1 };
+        let dir = if up { n.wrapping_sub(1) } else { n + 1 };
+        if dir >= buf.len() || n >= buf.len() {
+            return;
+        }
         buf.swap(dir, n);
         set_commands.set(buf);
         set_status.set("Ok( Order updated )".to_string());
     };
 
+    //+ "tray order" mode: moves rewrite tray_position of all commands, the table order is untouched
+    let tray_mode = RwSignal::new(false);
+    let move_in_tray = move |up: bool, n: usize| {
+        let mut ids: Vec<String> = tray_order(&commands.get()).iter().map(|c| c.id.clone()).collect();
+        let dir = if up { n - 1 } else { n + 1 };
+        ids.swap(dir, n);
+        set_commands.update(|cmds| {
+            for cmd in cmds.iter_mut() {
+                cmd.tray_position = ids.iter().position(|id| id == &cmd.id).map(|p| p as u32 + 1);
+            }
+        });
+        set_status.set("Ok( Tray order updated )".to_string());
+    };
+    let reset_tray_order = move || {
+        set_commands.update(|cmds| cmds.iter_mut().for_each(|c| c.tray_position = None));
+        set_status.set("Ok( Tray follows the table order )".to_string());
+    };
+
+    //+ commands with a hosts filter not matching this machine are not in its tray
+    let (hostname, set_hostname) = signal(String::new());
+    spawn_local(async move {
+        let js = invoke_without_args("get_hostname").await;
+        set_hostname.set(from_value::<String>(js).unwrap_or_default());
+    });
+
+    //+ drag & drop: the order cell is the handle, any row is a drop target
+    let dragged = RwSignal::new(None::<usize>);
+    let drop_command = move |to: usize| {
+        if let Some(from) = dragged.get_untracked() && from != to && from.max(to) < commands.get_untracked().len() {
+            let mut buf = commands.get();
+            let cmd = buf.remove(from);
+            buf.insert(to, cmd);
+            set_commands.set(buf);
+            set_status.set("Ok( Order updated )".to_string());
+        }
+        dragged.set(None);
+    };
+
+    //+ shells installed on the system, others are skipped by set_shell
+    let (available_shells, set_available_shells) = signal(Vec::<String>::new());
+    spawn_local(async move {
+        let js = invoke_without_args("get_available_shells").await;
+        set_available_shells.set(from_value::<Vec<String>>(js).unwrap_or_default());
+    });
+    // SHELLS of the backend's platform, the wasm build of gucli-shared only ever sees the unix list
+    let (builtin_shells, set_builtin_shells) = signal(Vec::<String>::new());
+    spawn_local(async move {
+        let js = invoke_without_args("get_builtin_shells").await;
+        set_builtin_shells.set(from_value::<Vec<String>>(js).unwrap_or_default());
+    });
+    // warn about saved commands whose shell is missing, once both lists are loaded
+    Effect::new(move |_| {
+        let shells = available_shells.get();
+        let missing: Vec<String> = commands0
+            .get()
+            .into_iter()
+            .filter(|c| !shells.contains(&c.shell))
+            .map(|c| format!("`{}` ({})", c.command, c.shell))
+            .collect();
+        if !shells.is_empty() && !missing.is_empty() {
+            set_status.set(format!("Warn( Shell not installed for: {} )", missing.join(", ")));
+        }
+    });
+    let shell_available = move |shell: &str| {
+        let shells = available_shells.get();
+        shells.is_empty() || shells.iter().any(|s| s == shell)
+    };
+
     let set_shell = move |n:usize| {
         let mut buf = commands.get();
-        let shells = ["sh", "bash", "zsh", "fish"];
-        let cur = buf[n].shell.clone();
-        let idx = shells
-                    .iter()
-                    .position(|s| s == &cur.as_str())
-                    .unwrap();
-        let new = shells[(idx + 1)  % shells.len()].to_string();
-        buf[n].shell = new;
+        let Some(cur) = buf.get(n).map(|c| c.shell.clone()) else {
+            return;
+        };
+        // installed shells incl. custom ones from [settings], the platform's SHELLS until they are loaded
+        let mut shells = available_shells.get();
+        if shells.is_empty() {
+            shells = builtin_shells.get();
+        }
+        if shells.is_empty() {
+            return;
+        }
+        let new = match shells.iter().position(|s| s == &cur) {
+            Some(idx) => shells[(idx + 1) % shells.len()].clone(),
+            None => shells[0].clone(),
+        };
+        if let Some(cmd) = buf.get_mut(n) {
+            cmd.shell = new;
+        }
 
         set_commands.set(buf);
     };
@@ -308,6 +733,8 @@ pub fn App() -> impl IntoView {
             "F2" => active_tab.set(1),
             "F3" => active_tab.set(2),
             "F4" => toggle_theme(),
+            "F5" => active_tab.set(3),
+            "F6" => active_tab.set(4),
             "Escape" => ctrl_window("close"),
             "F11" => ctrl_window(if is_maximized.get() == "max1" { "max0" } else { "max1" }),
             _ => {}
@@ -317,14 +744,95 @@ pub fn App() -> impl IntoView {
     let open_file = move |file| {
         spawn_local(async move {
             let args = to_value(&OpenFile {name: file}).unwrap();
-            let _js_value = invoke("open_file", args).await;
+            let js = invoke("open_file", args).await;
+            if let Ok(Err(e)) = from_value::<Result<String, String>>(js) {
+                set_status.set(format!("Err( {} )", e));
+            }
+        });
+    };
+
+    //+ commands.toml.gpg instead of commands.toml, gpg-agent asks for the passphrase
+    let encrypted = RwSignal::new(false);
+    spawn_local(async move {
+        let js = invoke_without_args("is_commands_encrypted").await;
+        encrypted.set(from_value::<bool>(js).unwrap_or(false));
+    });
+    let toggle_encryption = move || {
+        let on = !encrypted.get_untracked();
+        spawn_local(async move {
+            let args = to_value(&EncryptArgs { on }).unwrap();
+            let js = invoke("set_commands_encrypted", args).await;
+            match from_value::<Result<String, String>>(js) {
+                Ok(Ok(text)) => {
+                    encrypted.set(on);
+                    set_status.set(text);
+                }
+                Ok(Err(e)) => set_status.set(format!("Err( {} )", escape_html(&e))),
+                Err(e) => set_status.set(format!("Err( {} )", e)),
+            }
         });
     };
 
+    //+ Command palette [Ctrl+K]: tabs, commands, theme, config & log
+    let palette_open = RwSignal::new(false);
+    let _ = window_event_listener(leptos::ev::keydown, move |ev| {
+        if ev.ctrl_key() && ev.key().eq_ignore_ascii_case("k") {
+            ev.prevent_default();
+            palette_open.update(|open| *open = !*open);
+        }
+    });
+    let palette_entries = Signal::derive(move || {
+        let mut entries = vec![
+            PaletteEntry::new(t("tab.commands"), "palette.tab", PaletteAction::Tab(0)),
+            PaletteEntry::new(t("tab.help"), "palette.tab", PaletteAction::Tab(1)),
+            PaletteEntry::new(t("tab.about"), "palette.tab", PaletteAction::Tab(2)),
+            PaletteEntry::new(t("tab.stats"), "palette.tab", PaletteAction::Tab(3)),
+            PaletteEntry::new(t("tab.log"), "palette.tab", PaletteAction::Tab(4)),
+            PaletteEntry::new(t("palette.theme"), "palette.action", PaletteAction::ToggleTheme),
+            PaletteEntry::new(t("btn.open_log"), "palette.action", PaletteAction::Open("log")),
+            PaletteEntry::new(t("btn.open_config"), "palette.action", PaletteAction::Open("commands")),
+        ];
+        entries.extend(
+            commands
+                .get()
+                .iter()
+                .filter(|c| !c.command.trim().is_empty())
+                .map(|c| PaletteEntry::new(c.display_name(), "palette.run", PaletteAction::Run(c.id.clone()))),
+        );
+        entries
+    });
+    let palette_pick = Callback::new(move |action: PaletteAction| match action {
+        PaletteAction::Tab(tab) => active_tab.set(tab),
+        PaletteAction::Run(id) => {
+            // the result shows in the status line of the commands tab
+            if let Some(cmd) = commands.get_untracked().into_iter().find(|c| c.id == id) {
+                active_tab.set(0);
+                run_test(cmd);
+            }
+        }
+        PaletteAction::ToggleTheme => toggle_theme(),
+        PaletteAction::Open(name) => open_file(name),
+    });
+
+    // `time_format` from [settings], empty = webview locale
+    let (time_format, set_time_format) = signal(String::new());
+    spawn_local(async move {
+        let js = invoke_without_args("get_time_format").await;
+        set_time_format.set(from_value::<String>(js).unwrap_or_default());
+    });
+
+    // any other status replaces the last run
+    Effect::new(move |_| {
+        if !status.get().is_empty() {
+            test_output.set(None);
+        }
+    });
+
     // monitored status changes update the time of the last operation
     Effect::new(move |_| {
         status.track();
-        ttime.set(Local::now().format("%Y-%m-%d %H:%M:%S.%3f").to_string());
+        test_output.track();
+        ttime.set(format_now(&time_format.get_untracked()));
         highlight.set(true);
         set_timeout(move || highlight.set(false), std::time::Duration::from_millis(300));
         log::debug!("effect 1 status: {:?}", status.get());
@@ -332,8 +840,7 @@ pub fn App() -> impl IntoView {
 
     // compare commands0 != commands & adds a line about unsaved changes to the unsaved_changes
     Effect::new(move |_| {
-        commands.track();
-        if commands0.get() != commands.get(){
+        if commands0.get() != commands_in_tray_order(){
             unsaved_changes.set("Unsaved changes")
         }else{ unsaved_changes.set("")}
     });
@@ -342,8 +849,17 @@ pub fn App() -> impl IntoView {
     Effect::new(move |_| {
         if let Some(window) = window() {
             if let Some(html_el) = window.document().and_then(|d| d.document_element()) {
-                let current_theme = theme.get();
+                let current_theme = match theme.get().as_str() {
+                    "auto" => auto_theme.get(),
+                    t => t.to_string(),
+                };
                 let _ = html_el.set_attribute("data-theme", &current_theme);
+                // custom variables inline, over the light theme's
+                if current_theme == "custom" {
+                    let _ = html_el.set_attribute("style", &custom_theme.get().css());
+                } else {
+                    let _ = html_el.remove_attribute("style");
+                }
             }
 
             if let Ok(Some(storage)) = window.local_storage() {
@@ -372,6 +888,8 @@ pub fn App() -> impl IntoView {
                         "light" => "🌞[F4]",
                         "dark" => "🌙[F4]",
                         "a11y" => "♿[F4]",
+                        "auto" => "🌓[F4]",
+                        "custom" => "🎨[F4]",
                         _ => "🌞[F4]",
                     }}
                 </button>
@@ -383,7 +901,7 @@ pub fn App() -> impl IntoView {
                 autofocus=move || active_tab.get() == 0
                 aria-keyshortcuts="F1"
             >
-                "Commands [F1]"
+                {move || t("tab.commands")}
             </button>
             <button
                 class:active=move || active_tab.get() == 1
@@ -392,7 +910,7 @@ pub fn App() -> impl IntoView {
                 autofocus=move || active_tab.get() == 1
                 aria-keyshortcuts="F2"
             >
-                "Find help || man [F2]"
+                {move || t("tab.help")}
             </button>
             <button
                 class:active=move || active_tab.get() == 2
@@ -401,7 +919,25 @@ pub fn App() -> impl IntoView {
                 autofocus=move || active_tab.get() == 2
                 aria-keyshortcuts="F3"
             >
-                "About [F3]"
+                {move || t("tab.about")}
+            </button>
+            <button
+                class:active=move || active_tab.get() == 3
+                class="tabs-header"
+                on:click=move |_| active_tab.set(3)
+                autofocus=move || active_tab.get() == 3
+                aria-keyshortcuts="F5"
+            >
+                {move || t("tab.stats")}
+            </button>
+            <button
+                class:active=move || active_tab.get() == 4
+                class="tabs-header"
+                on:click=move |_| active_tab.set(4)
+                autofocus=move || active_tab.get() == 4
+                aria-keyshortcuts="F6"
+            >
+                {move || t("tab.log")}
             </button>
 
             <div class="titlebar-controls">
@@ -417,61 +953,226 @@ pub fn App() -> impl IntoView {
             </div>
         </div>
 
+        <CommandPalette open=palette_open entries=palette_entries on_pick=palette_pick />
         <main class="container" role="main">
+            <Show when=move || config_error.with(Option::is_some)>
+                <div class="config-error" role="alert">
+                    <span class="err-text">{move || t("config.invalid")}</span>
+                    <pre>{move || config_error.get().unwrap_or_default()}</pre>
+                    <span class="warn-text">{move || t("config.overwrite")}</span>
+                    <div>
+                        <button on:click=move |_| restore_backup() class="ok-bg">
+                            {move || t("btn.restore_backup")}
+                        </button>
+                        <button on:click=move |_| open_file("commands") class="warn-bg">
+                            {move || t("btn.open_config")}
+                        </button>
+                    </div>
+                </div>
+            </Show>
             <div hidden=move || active_tab.get() != 0>
                 <div class="topline">
                     <button
                         on:click=move |_| toggle_autostart()
                         class=move || if autostart.get() { "ok-bg" } else { "" }
                     >
-                        {move || if autostart.get() { "Autostart: ON" } else { "Autostart: OFF" }}
+                        {move || if autostart.get() { t("btn.autostart_on") } else { t("btn.autostart_off") }}
                     </button>
+                    <select
+                        aria-label=move || t("label.autostart_backend")
+                        on:change=move |ev| change_autostart_backend(event_target_value(&ev))
+                    >
+                        {AUTOSTART_BACKENDS
+                            .into_iter()
+                            .map(|backend| {
+                                view! {
+                                    <option value=backend selected=move || autostart_backend.get() == backend>
+                                        {backend}
+                                    </option>
+                                }
+                            })
+                            .collect_view()}
+                    </select>
+                    <input
+                        type="number"
+                        min="0"
+                        class="autostart-delay"
+                        aria-label=move || t("label.autostart_delay")
+                        title=move || t("label.autostart_delay")
+                        prop:value=move || autostart_delay.get().to_string()
+                        on:change=move |ev| change_autostart_delay(event_target_value(&ev))
+                    />
                     <button on:click=move |_| open_file("log") class="warn-bg">
-                        "Open Log"
+                        {move || t("btn.open_log")}
                     </button>
                     <button on:click=move |_| open_file("commands") class="ok-bg">
-                        "Open Config"
+                        {move || t("btn.open_config")}
+                    </button>
+                    <button
+                        on:click=move |_| toggle_encryption()
+                        class:ok-bg=move || encrypted.get()
+                        title=move || t("label.encryption")
+                    >
+                        {move || if encrypted.get() { t("btn.decrypt") } else { t("btn.encrypt") }}
                     </button>
                     <button on:click=move |_| reset_commands() class="err-bg">
                         {move || match reset.get() {
-                            true => "Really reset?",
-                            false => "Reset & Restart",
+                            true => t("btn.really_reset"),
+                            false => t("btn.reset"),
                         }}
                     </button>
                 </div>
 
                 <div class="status">
                     <div>
-                        <span>"STATUS"</span>
+                        <span>{move || t("label.status")}</span>
                         <br />
-                        <span>"count: " {move || commands.get().len()}</span>
+                        <span>{move || t("label.count")} {move || commands.get().len()}</span>
                     </div>
                     <div>
                         <span class="ttime">{move || ttime.get()}</span>
                     </div>
                     <div role="status" aria-live="polite" aria-atomic="true">
-                        <span
-                            class="status-block"
-                            class:ok-text=move || status.get().starts_with("Ok")
-                            class:err-text=move || status.get().starts_with("Er")
-                            class:warn-text=move || status.get().starts_with("Warn")
-                            class:highlight=move || highlight.get()
-                            id=move || ttime.get().to_string()
-                            data-update=move || ttime.get().to_string()
-                            inner_html=move || status.get()
-                        ></span>
+                        {move || match test_output.get() {
+                            Some((command, result)) => {
+                                let outcome = test_outcome(&result);
+                                view! {
+                                    <span
+                                        class="status-block"
+                                        class:ok-text={outcome == "ok"}
+                                        class:err-text={outcome == "err"}
+                                        class:warn-text={outcome == "warn"}
+                                        class:highlight=move || highlight.get()
+                                        id=move || ttime.get().to_string()
+                                        data-update=move || ttime.get().to_string()
+                                    >
+                                        {test_text(&command, &result)}
+                                    </span>
+                                }
+                                    .into_any()
+                            }
+                            None => {
+                                view! {
+                                    <span
+                                        class="status-block"
+                                        class:ok-text=move || status.get().starts_with("Ok")
+                                        class:err-text=move || status.get().starts_with("Er")
+                                        class:warn-text=move || status.get().starts_with("Warn")
+                                        class:highlight=move || highlight.get()
+                                        id=move || ttime.get().to_string()
+                                        data-update=move || ttime.get().to_string()
+                                        inner_html=move || status.get()
+                                    ></span>
+                                }
+                                    .into_any()
+                            }
+                        }}
                     </div>
                 </div>
+                {move || {
+                    test_output
+                        .get()
+                        .filter(|(_, result)| test_outcome(result) == "ok")
+                        .and_then(|(_, result)| parse_json(&result.stdout))
+                        .map(|value| {
+                            view! {
+                                <details class="json-output" open>
+                                    <summary>"JSON"</summary>
+                                    <JsonView value />
+                                </details>
+                            }
+                        })
+                }}
 
                 <div class="commands form">
+                    <div class="sort">
+                        <label>
+                            "sort: "
+                            <select on:change=move |ev| {
+                                let mode = event_target_value(&ev);
+                                if let Some(mode) = SORT_MODES.into_iter().find(|m| *m == mode) {
+                                    sort_by(mode);
+                                }
+                            }>
+                                {SORT_MODES
+                                    .into_iter()
+                                    .map(|m| {
+                                        view! {
+                                            <option value=m selected=move || sort_mode.get() == m>
+                                                {m}
+                                            </option>
+                                        }
+                                    })
+                                    .collect_view()}
+                            </select>
+                        </label>
+                        <Show when=move || sort_mode.get() != "manual">
+                            <span class="warn-text">"view only, the tray keeps the manual order"</span>
+                            <button class="ok-bg" on:click=move |_| apply_sort()>
+                                "Apply order"
+                            </button>
+                        </Show>
+                        <button
+                            class:active=move || tray_mode.get()
+                            on:click=move |_| tray_mode.update(|t| *t = !*t)
+                        >
+                            {move || if tray_mode.get() { "Back to table" } else { "Tray order" }}
+                        </button>
+                    </div>
+                    <Show when=move || !selected.get().is_empty()>
+                        <div class="bulk">
+                            <span>{move || format!("{} selected:", selected.get().len())}</span>
+                            <button class="err-bg" on:click=move |_| delete_selected()>
+                                "Delete"
+                            </button>
+                            <button on:click=move |_| set_sn_selected(true)>"Notifications on"</button>
+                            <button on:click=move |_| set_sn_selected(false)>"Notifications off"</button>
+                            <button on:click=move |_| selected.set(Default::default())>"Clear selection"</button>
+                        </div>
+                    </Show>
+                    <Show when=move || tray_mode.get()>
+                        <ol class="tray-order">
+                            {move || {
+                                let cmds = commands.get();
+                                let last = cmds.len().saturating_sub(1);
+                                tray_order(&cmds)
+                                    .into_iter()
+                                    .enumerate()
+                                    .map(|(n, c)| {
+                                        let label = format!("{}    {}", c.icon, c.display_name());
+                                        view! {
+                                            <li>
+                                                <button
+                                                    on:click=move |_| move_in_tray(true, n)
+                                                    prop:disabled=n == 0
+                                                    aria-label=format!("Move '{}' up in the tray", label)
+                                                >
+                                                    "↑"
+                                                </button>
+                                                <button
+                                                    on:click=move |_| move_in_tray(false, n)
+                                                    prop:disabled=n == last
+                                                    aria-label=format!("Move '{}' down in the tray", label)
+                                                >
+                                                    "↓"
+                                                </button>
+                                                <span>{label.clone()}</span>
+                                            </li>
+                                        }
+                                    })
+                                    .collect_view()
+                            }}
+                        </ol>
+                        <button on:click=move |_| reset_tray_order()>"Use table order"</button>
+                    </Show>
                     <div class="row head">
                         <span>"#"</span>
-                        <span>"shell"</span>
-                        <span>"command"</span>
-                        <span>"icon"</span>
-                        <span>"sn"</span>
-                        <span>"delete"</span>
-                        <span>"test"</span>
+                        <span>{move || t("head.shell")}</span>
+                        <span>{move || t("head.command")}</span>
+                        <span>{move || t("head.icon")}</span>
+                        <span>{move || t("head.sn")}</span>
+                        <span>{move || t("head.rows")}</span>
+                        <span>{move || t("head.test")}</span>
                     </div>
 
                     <ForEnumerate
@@ -480,15 +1181,63 @@ pub fn App() -> impl IntoView {
                         let(i,
                         command)
                     >
-                        <div class="row">
-                            <div class="order">
+                        <div
+                            class="row"
+                            class:test-ok=move || test_result(i.get()) == Some("ok")
+                            class:test-err=move || {
+                                test_result(i.get()) == Some("err")
+                                    || row_problems(i.get()).iter().any(|p| !p.warning)
+                            }
+                            class:dangerous=move || danger(i.get()).is_some()
+                            class:test-warn=move || test_result(i.get()) == Some("warn")
+                            class:other-host=move || {
+                                let host = hostname.get();
+                                !host.is_empty() && !row(i.get()).available_on(&host)
+                            }
+                            class:drop-target=move || dragged.get().is_some_and(|from| from != i.get())
+                            on:dragover=move |ev| {
+                                if dragged.get_untracked().is_some() {
+                                    ev.prevent_default();
+                                }
+                            }
+                            on:drop=move |ev| {
+                                ev.prevent_default();
+                                drop_command(i.get());
+                            }
+                        >
+                            <div
+                                class="order"
+                                draggable=move || (sort_mode.get() == "manual").to_string()
+                                title="Drag to reorder"
+                                on:dragstart=move |_| dragged.set(Some(i.get()))
+                                on:dragend=move |_| dragged.set(None)
+                            >
+                                <input
+                                    type="checkbox"
+                                    prop:checked=move || selected.get().contains(&row(i.get()).id)
+                                    on:change=move |ev| {
+                                        let checked = event_target_checked(&ev);
+                                        let Some(id) = commands.get_untracked().get(i.get_untracked()).map(|c| c.id.clone()) else {
+                                            return;
+                                        };
+                                        selected
+                                            .update(|s| {
+                                                if checked {
+                                                    s.insert(id);
+                                                } else {
+                                                    s.remove(&id);
+                                                }
+                                            });
+                                    }
+                                    aria-label=move || format!("Select command '{}'", row(i.get()).command)
+                                />
                                 <button
                                     on:click=move |_| move_command(true, i.get())
-                                    prop:disabled=move || i.get() == 0
+                                    prop:disabled=move || i.get() == 0 || sort_mode.get() != "manual"
                                     aria-label=move || {
                                         format!(
                                             "Move command '{}' up",
-                                            commands.get()[i.get()].command.clone(),
+                                            row(i.get()).command,
                                         )
                                     }
                                 >
@@ -496,13 +1245,33 @@ pub fn App() -> impl IntoView {
                                 </button>
                                 <span class="nn">{i}</span>
                                 <button
-                                    on:click=move |_| move_command(false, i.get())
-                                    prop:disabled=move || i.get() == commands.get().len() - 1
+                                    class="pin"
+                                    class:warn-text=move || row(i.get()).pinned
+                                    on:click=move |_| {
+                                        set_commands
+                                            .update(|cmds| {
+                                                if let Some(cmd) = cmds.get_mut(i.get()) {
+                                                    cmd.pinned = !cmd.pinned;
+                                                }
+                                            })
+                                    }
+                                    aria-pressed=move || row(i.get()).pinned.to_string()
                                     aria-label=move || {
-                                        format!(
-                                            "Move command '{}' down",
-                                            commands.get()[i.get()].command.clone(),
-                                        )
+                                        format!("Pin command '{}' to the top of the tray", row(i.get()).command)
+                                    }
+                                >
+                                    {move || if row(i.get()).pinned { "★" } else { "☆" }}
+                                </button>
+                                <button
+                                    on:click=move |_| move_command(false, i.get())
+                                    prop:disabled=move || {
+                                        i.get() == commands.get().len() - 1 || sort_mode.get() != "manual"
+                                    }
+                                    aria-label=move || {
+                                        format!(
+                                            "Move command '{}' down",
+                                            row(i.get()).command,
+                                        )
                                     }
                                 >
                                     "↓"
@@ -510,18 +1279,23 @@ pub fn App() -> impl IntoView {
                             </div>
                             <button
                                 class="shell-switch"
+                                class:err-text=move || !shell_available(&row(i.get()).shell)
+                                title=move || {
+                                    let shell = row(i.get()).shell;
+                                    if shell_available(&shell) { String::new() } else { format!("{} is not installed", shell) }
+                                }
                                 on:click=move |_| set_shell(i.get())
                                 aria-live="polite"
                                 aria-atomic="true"
                                 aria-label=move || {
                                     format!(
                                         "Switch shell. Current: {}",
-                                        commands.get()[i.get()].clone().shell,
+                                        row(i.get()).shell,
                                     )
                                 }
                             >
                                 "▶|"
-                                <span>{move || commands.get()[i.get()].clone().shell}</span>
+                                <span>{move || row(i.get()).shell}</span>
                             </button>
                             <input
                                 class="coma"
@@ -530,37 +1304,73 @@ pub fn App() -> impl IntoView {
                                 value=move || command.command.clone()
                                 on:input=move |ev| {
                                     let value = event_target_value(&ev);
+                                    suggest(value.clone());
                                     set_commands
                                         .update(|cmds| {
-                                            cmds[i.get()].command = value;
+                                            if let Some(cmd) = cmds.get_mut(i.get()) {
+                                                cmd.command = value;
+                                            }
                                         });
                                 }
+                                list="command-completions"
+                                autocomplete="off"
                                 aria-description="Warning: Commands execute with user permissions. Test commands first."
                             />
+                            {move || {
+                                danger(i.get())
+                                    .map(|reason| {
+                                        view! {
+                                            <div class="danger-banner err-bg" role="alert">
+                                                {format!("⚠ {}: {}", t("label.dangerous"), reason)}
+                                            </div>
+                                        }
+                                    })
+                            }}
+                            {move || {
+                                row_problems(i.get())
+                                    .into_iter()
+                                    .map(|p| {
+                                        let line = p.line.map(|n| format!(" ({} {})", t("label.line"), n)).unwrap_or_default();
+                                        view! {
+                                            <div
+                                                class="problem-banner"
+                                                class:err-text=!p.warning
+                                                class:warn-text=p.warning
+                                                role="alert"
+                                            >
+                                                {format!("✗ {}{}: {}", p.field, line, p.message)}
+                                            </div>
+                                        }
+                                    })
+                                    .collect_view()
+                            }}
                             <input
                                 class="iicon"
                                 type="text"
                                 placeholder="8 chars"
                                 size="8"
-                                maxlength="8"
                                 value=move || command.icon.clone()
                                 on:input=move |ev| {
                                     let value = event_target_value(&ev);
                                     set_commands
                                         .update(|cmds| {
-                                            cmds[i.get()].icon = value;
+                                            if let Some(cmd) = cmds.get_mut(i.get()) {
+                                                cmd.icon = value;
+                                            }
                                         });
                                 }
                             />
                             <label class="chb">
                                 <input
                                     type="checkbox"
-                                    checked=move || commands.get()[i.get()].clone().sn
+                                    checked=move || row(i.get()).sn
                                     on:change=move |ev| {
                                         let checked = event_target_checked(&ev);
                                         set_commands
                                             .update(|cmds| {
-                                                cmds[i.get()].sn = checked;
+                                                if let Some(cmd) = cmds.get_mut(i.get()) {
+                                                    cmd.sn = checked;
+                                                }
                                             });
                                     }
                                     on:keydown=move |ev: KeyboardEvent| {
@@ -568,15 +1378,17 @@ pub fn App() -> impl IntoView {
                                             ev.prevent_default();
                                             set_commands
                                                 .update(|cmds| {
-                                                    cmds[i.get()].sn = !cmds[i.get()].sn;
+                                                    if let Some(cmd) = cmds.get_mut(i.get()) {
+                                                        cmd.sn = !cmd.sn;
+                                                    }
                                                 });
                                         }
                                     }
                                     aria-label=move || {
                                         format!(
                                             "Show system notification for command '{}'. Currently: {}",
-                                            commands.get()[i.get()].command.clone(),
-                                            if commands.get()[i.get()].clone().sn {
+                                            row(i.get()).command,
+                                            if row(i.get()).sn {
                                                 "on"
                                             } else {
                                                 "off"
@@ -586,39 +1398,414 @@ pub fn App() -> impl IntoView {
                                 />
                             </label>
                             <div>
+                                <button on:click=move |_| duplicate_command(i.get())>
+                                    {move || t("btn.duplicate")}
+                                </button>
                                 <button on:click=move |_| delete_command(i.get()) class="err-bg">
-                                    "Delete"
+                                    {move || t("btn.delete")}
                                 </button>
                             </div>
                             <div>
                                 <button
-                                    on:click=move |_| run_test(commands.get()[i.get()].clone())
+                                    on:click=move |_| {
+                                        if let Some(cmd) = commands.get().get(i.get()).cloned() {
+                                            run_test(cmd);
+                                        }
+                                    }
                                     class="warn-bg"
                                 >
-                                    "Run test"
+                                    {move || t("btn.run_test")}
+                                </button>
+                                <button
+                                    on:click=move |_| {
+                                        if let Some(cmd) = commands.get().get(i.get()).cloned() {
+                                            preview(cmd);
+                                        }
+                                    }
+                                    title=move || t("btn.preview")
+                                    aria-label=move || t("btn.preview")
+                                >
+                                    "👁"
                                 </button>
                             </div>
+                            <details class="options">
+                                <summary>{move || t("label.options")}</summary>
+                                <label class="flag">
+                                    "label"
+                                    <input
+                                        type="text"
+                                        placeholder="tray text, the command if empty"
+                                        prop:value=move || row(i.get()).label
+                                        on:input=move |ev| {
+                                            let value = event_target_value(&ev);
+                                            set_commands.update(|cmds| if let Some(cmd) = cmds.get_mut(i.get()) { cmd.label = value });
+                                        }
+                                    />
+                                </label>
+                                <label class="flag">
+                                    "mnemonic"
+                                    <input
+                                        type="text"
+                                        size="1"
+                                        maxlength="1"
+                                        prop:value=move || row(i.get()).mnemonic
+                                        on:input=move |ev| {
+                                            let value = event_target_value(&ev);
+                                            set_commands.update(|cmds| if let Some(cmd) = cmds.get_mut(i.get()) { cmd.mnemonic = value });
+                                        }
+                                    />
+                                </label>
+                                <label class="flag">
+                                    <input
+                                        type="checkbox"
+                                        prop:checked=move || row(i.get()).default
+                                        on:change=move |ev| {
+                                            let checked = event_target_checked(&ev);
+                                            // only one default command
+                                            set_commands
+                                                .update(|cmds| {
+                                                    for (n, cmd) in cmds.iter_mut().enumerate() {
+                                                        cmd.default = checked && n == i.get();
+                                                    }
+                                                });
+                                        }
+                                    />
+                                    "default: run by a double click on the tray icon"
+                                </label>
+                                <CommandFlag
+                                    commands
+                                    set_commands
+                                    i
+                                    label="sandbox: no network, read-only home, private /tmp"
+                                    get=|c| c.sandbox
+                                    set=|c, v| c.sandbox = v
+                                />
+                                <CommandFlag
+                                    commands
+                                    set_commands
+                                    i
+                                    label="interactive: load aliases & functions from rc files"
+                                    get=|c| c.interactive
+                                    set=|c, v| c.interactive = v
+                                    disabled=Callback::new(move |c: Command| !builtin_shells.get().contains(&c.shell))
+                                />
+                                <CommandFlag
+                                    commands
+                                    set_commands
+                                    i
+                                    label="confirm: ask before running from the tray"
+                                    get=|c| c.confirm
+                                    set=|c, v| c.confirm = v
+                                />
+                                <CommandFlag
+                                    commands
+                                    set_commands
+                                    i
+                                    label="in terminal: open in a terminal window"
+                                    get=|c| c.in_terminal
+                                    set=|c, v| c.in_terminal = v
+                                />
+                                <CommandFlag
+                                    commands
+                                    set_commands
+                                    i
+                                    label="long running: no timeout, notify when done"
+                                    get=|c| c.long_running
+                                    set=|c, v| c.long_running = v
+                                />
+                                <CommandFlag
+                                    commands
+                                    set_commands
+                                    i
+                                    label="run on start: execute when gucli starts"
+                                    get=|c| c.run_on_start
+                                    set=|c, v| c.run_on_start = v
+                                />
+                                <label class="flag">
+                                    "run on"
+                                    <select on:change=move |ev| {
+                                        let value = event_target_value(&ev);
+                                        set_commands.update(|cmds| if let Some(cmd) = cmds.get_mut(i.get()) { cmd.executor = value });
+                                    }>
+                                        {["local", "ssh", "container"]
+                                            .into_iter()
+                                            .map(|e| {
+                                                view! {
+                                                    <option
+                                                        value=e
+                                                        selected=move || {
+                                                            let cur = row(i.get()).executor;
+                                                            cur == e || (cur.is_empty() && e == "local")
+                                                        }
+                                                    >
+                                                        {e}
+                                                    </option>
+                                                }
+                                            })
+                                            .collect_view()}
+                                    </select>
+                                </label>
+                                <CommandFlag
+                                    commands
+                                    set_commands
+                                    i
+                                    label="gui: screenshot the desktop on failure"
+                                    get=|c| c.gui
+                                    set=|c, v| c.gui = v
+                                />
+                                <label class="flag">
+                                    "hosts"
+                                    <input
+                                        type="text"
+                                        placeholder=move || format!("all, this one is {}", hostname.get())
+                                        prop:value=move || row(i.get()).hosts.join(", ")
+                                        on:change=move |ev| {
+                                            let value = event_target_value(&ev);
+                                            set_commands
+                                                .update(|cmds| {
+                                                    if let Some(cmd) = cmds.get_mut(i.get()) {
+                                                        cmd.hosts = value
+                                                            .split(',')
+                                                            .map(|h| h.trim().to_string())
+                                                            .filter(|h| !h.is_empty())
+                                                            .collect();
+                                                    }
+                                                });
+                                        }
+                                    />
+                                </label>
+                                <label class="flag">
+                                    "health check every, s"
+                                    <input
+                                        type="number"
+                                        min="5"
+                                        placeholder="off"
+                                        prop:value=move || {
+                                            row(i.get())
+                                                .health_interval
+                                                .map(|s| s.to_string())
+                                                .unwrap_or_default()
+                                        }
+                                        on:change=move |ev| {
+                                            let value = event_target_value(&ev);
+                                            set_commands
+                                                .update(|cmds| {
+                                                    if let Some(cmd) = cmds.get_mut(i.get()) {
+                                                        cmd.health_interval = value
+                                                            .trim()
+                                                            .parse::<u64>()
+                                                            .ok()
+                                                            .filter(|s| *s > 0);
+                                                    }
+                                                });
+                                        }
+                                    />
+                                </label>
+                                <label class="flag">
+                                    "when running"
+                                    <select on:change=move |ev| {
+                                        let value = event_target_value(&ev);
+                                        set_commands.update(|cmds| if let Some(cmd) = cmds.get_mut(i.get()) { cmd.on_busy = value });
+                                    }>
+                                        {ON_BUSY_POLICIES
+                                            .into_iter()
+                                            .map(|policy| {
+                                                view! {
+                                                    <option
+                                                        value=policy
+                                                        selected=move || {
+                                                            let cur = row(i.get()).on_busy;
+                                                            cur == policy || (cur.is_empty() && policy == "skip")
+                                                        }
+                                                    >
+                                                        {policy}
+                                                    </option>
+                                                }
+                                            })
+                                            .collect_view()}
+                                    </select>
+                                </label>
+                                <label class="flag">
+                                    "queue"
+                                    <input
+                                        type="text"
+                                        placeholder="none"
+                                        prop:value=move || row(i.get()).queue
+                                        on:input=move |ev| {
+                                            let value = event_target_value(&ev);
+                                            set_commands.update(|cmds| if let Some(cmd) = cmds.get_mut(i.get()) { cmd.queue = value });
+                                        }
+                                    />
+                                </label>
+                                <label class="flag">
+                                    "notification cooldown, s"
+                                    <input
+                                        type="number"
+                                        min="0"
+                                        placeholder="off"
+                                        prop:value=move || {
+                                            row(i.get())
+                                                .notify_cooldown
+                                                .map(|s| s.to_string())
+                                                .unwrap_or_default()
+                                        }
+                                        on:change=move |ev| {
+                                            let value = event_target_value(&ev);
+                                            set_commands
+                                                .update(|cmds| {
+                                                    if let Some(cmd) = cmds.get_mut(i.get()) {
+                                                        cmd.notify_cooldown = value
+                                                            .trim()
+                                                            .parse::<u64>()
+                                                            .ok()
+                                                            .filter(|s| *s > 0);
+                                                    }
+                                                });
+                                        }
+                                    />
+                                </label>
+                                <label class="flag">
+                                    {move || t("label.notify_length")}
+                                    <input
+                                        type="number"
+                                        min="1"
+                                        placeholder=move || t("placeholder.default")
+                                        prop:value=move || {
+                                            row(i.get())
+                                                .notify_length
+                                                .map(|n| n.to_string())
+                                                .unwrap_or_default()
+                                        }
+                                        on:change=move |ev| {
+                                            let value = event_target_value(&ev);
+                                            set_commands
+                                                .update(|cmds| {
+                                                    if let Some(cmd) = cmds.get_mut(i.get()) {
+                                                        cmd.notify_length = value
+                                                            .trim()
+                                                            .parse::<usize>()
+                                                            .ok()
+                                                            .filter(|n| *n > 0);
+                                                    }
+                                                });
+                                        }
+                                    />
+                                </label>
+                                <label class="flag">
+                                    "output file"
+                                    <input
+                                        type="text"
+                                        placeholder="~/logs/%Y-%m-%d.log"
+                                        prop:value=move || row(i.get()).output_file
+                                        on:input=move |ev| {
+                                            let value = event_target_value(&ev);
+                                            set_commands.update(|cmds| if let Some(cmd) = cmds.get_mut(i.get()) { cmd.output_file = value });
+                                        }
+                                    />
+                                </label>
+                                <label class="flag">
+                                    "working dir"
+                                    <input
+                                        type="text"
+                                        placeholder="~/project"
+                                        prop:value=move || row(i.get()).cwd
+                                        on:input=move |ev| {
+                                            let value = event_target_value(&ev);
+                                            set_commands.update(|cmds| if let Some(cmd) = cmds.get_mut(i.get()) { cmd.cwd = value });
+                                        }
+                                    />
+                                </label>
+                                <Show when=move || row(i.get()).executor == "ssh">
+                                    <input
+                                        type="text"
+                                        placeholder="user@server"
+                                        prop:value=move || row(i.get()).host
+                                        on:input=move |ev| {
+                                            let value = event_target_value(&ev);
+                                            set_commands.update(|cmds| if let Some(cmd) = cmds.get_mut(i.get()) { cmd.host = value });
+                                        }
+                                    />
+                                    <input
+                                        type="text"
+                                        placeholder="ssh key, e.g. ~/.ssh/id_ed25519"
+                                        prop:value=move || row(i.get()).ssh_key
+                                        on:input=move |ev| {
+                                            let value = event_target_value(&ev);
+                                            set_commands.update(|cmds| if let Some(cmd) = cmds.get_mut(i.get()) { cmd.ssh_key = value });
+                                        }
+                                    />
+                                </Show>
+                                <Show when=move || row(i.get()).executor == "container">
+                                    <input
+                                        type="text"
+                                        placeholder="container name"
+                                        prop:value=move || row(i.get()).container
+                                        on:input=move |ev| {
+                                            let value = event_target_value(&ev);
+                                            set_commands.update(|cmds| if let Some(cmd) = cmds.get_mut(i.get()) { cmd.container = value });
+                                        }
+                                    />
+                                </Show>
+                                <div class="flag">
+                                    <button on:click=move |_| {
+                                        if let Some(cmd) = commands.get().get(i.get()).cloned() {
+                                            export_unit(cmd, false);
+                                        }
+                                    }>
+                                        {move || t("btn.export_service")}
+                                    </button>
+                                    <button on:click=move |_| {
+                                        if let Some(cmd) = commands.get().get(i.get()).cloned() {
+                                            export_unit(cmd, true);
+                                        }
+                                    }>
+                                        {move || t("btn.export_timer")}
+                                    </button>
+                                </div>
+                            </details>
                         </div>
                     </ForEnumerate>
 
+                    // shared by all rows, only the focused input shows it
+                    <datalist id="command-completions">
+                        {move || {
+                            completions
+                                .get()
+                                .into_iter()
+                                .map(|c| view! { <option value=c></option> })
+                                .collect_view()
+                        }}
+                    </datalist>
+
                     <div class="buttons tc">
                         <div>
                             <button class="ok-bg" on:click=move |_| add_command()>
-                                "Add command"
+                                {move || t("btn.add")}
+                            </button>
+                            <button class="warn-bg" disabled=move || testing_all.get() on:click=move |_| test_all()>
+                                {move || {
+                                    if testing_all.get() {
+                                        t("btn.testing")
+                                    } else if confirm_test_all.get() {
+                                        t("btn.really_test_all")
+                                    } else {
+                                        t("btn.test_all")
+                                    }
+                                }}
                             </button>
                         </div>
                         <span class="warn-text tc" inner_html=unsaved_changes></span>
                         <div>
                             <button class="ok-bg" on:click=move |_| save(commands.get())>
-                                "Save & Restart"
+                                {move || t("btn.save")}
                             </button>
                         </div>
                     </div>
                 </div>
 
                 <details>
-                    <summary>Help</summary>
-                    <div class="text-bg" inner_html=SETTINGS_HELP></div>
+                    <summary>{move || t("label.help")}</summary>
+                    <div class="text-bg" inner_html=move || t("help.settings")></div>
                 </details>
 
             </div>
@@ -628,26 +1815,109 @@ pub fn App() -> impl IntoView {
             <Show when=move || active_tab.get() == 2>
                 <About />
             </Show>
+            <Show when=move || active_tab.get() == 3>
+                <Processes />
+                <Stats />
+            </Show>
+            <Show when=move || active_tab.get() == 4>
+                <LogView />
+            </Show>
         </main>
     }
 }
 
+/// checkbox bound to one boolean field of the command at index `i`
+#[component]
+fn CommandFlag(
+    commands: ReadSignal<Vec<Command>>,
+    set_commands: WriteSignal<Vec<Command>>,
+    i: ReadSignal<usize>,
+    label: &'static str,
+    get: fn(&Command) -> bool,
+    set: fn(&mut Command, bool),
+    /// greyed out for commands the flag does nothing for
+    #[prop(optional)]
+    disabled: Option<Callback<Command, bool>>,
+) -> impl IntoView {
+    view! {
+        <label class="flag">
+            <input
+                type="checkbox"
+                prop:checked=move || commands.get().get(i.get()).map(get).unwrap_or(false)
+                prop:disabled=move || {
+                    disabled.is_some_and(|disabled| commands.get().get(i.get()).cloned().is_some_and(|c| disabled.run(c)))
+                }
+                on:change=move |ev| {
+                    let checked = event_target_checked(&ev);
+                    set_commands
+                        .update(|cmds| {
+                            if let Some(cmd) = cmds.get_mut(i.get()) {
+                                set(cmd, checked);
+                            }
+                        });
+                }
+            />
+            {label}
+        </label>
+    }
+}
+
 #[component]
 pub fn ManSearch() -> impl IntoView {
     use leptos::{ev::SubmitEvent};
     let (man, set_man) = signal(String::new());
+    let (apropos, set_apropos) = signal(Vec::<AproposEntry>::new());
     let (input_value, set_input_value) = signal("".to_string());
+    let keyword_mode = RwSignal::new(false);
+    let (executables, set_executables) = signal(Vec::<String>::new());
+    let lang = use_context::<RwSignal<String>>().unwrap_or_else(|| RwSignal::new("en".to_string()));
+    let t = move |key: &'static str| tr(&lang.get(), key);
 
-    let on_submit = move |ev: SubmitEvent| {
-        ev.prevent_default();
+    // binaries from $PATH for the input autocomplete
+    spawn_local(async move {
+        let js_value = invoke_without_args("list_executables").await;
+        if let Ok(list) = from_value::<Vec<String>>(js_value) {
+            set_executables.set(list);
+        }
+    });
+
+    // first matches of the typed prefix, the datalist stays small
+    let suggestions = move || {
+        const MAX_SUGGESTIONS: usize = 50;
+        let prefix = input_value.get();
+        if prefix.is_empty() || prefix.contains(' ') {
+            return Vec::new();
+        }
+        executables
+            .get()
+            .into_iter()
+            .filter(|name| name.starts_with(&prefix))
+            .take(MAX_SUGGESTIONS)
+            .collect::<Vec<_>>()
+    };
+
+    let search = move |refresh: bool| {
         let trimmed_value = input_value.get().trim().to_string();
         set_input_value.set(trimmed_value.clone());
+        set_man.set("".to_string());
+        set_apropos.set(Vec::new());
 
         if trimmed_value.is_empty() {
-            set_man.set("".to_string());
+            return;
+        }
+        if keyword_mode.get() {
+            spawn_local(async move {
+                let args = to_value(&AproposArgs {keyword: trimmed_value.clone()}).unwrap();
+                let js_value = invoke("get_apropos", args).await;
+                match from_value::<Vec<AproposEntry>>(js_value) {
+                    Ok(list) if list.is_empty() => set_man.set(format!("Nothing appropriate for '{}'", trimmed_value)),
+                    Ok(list) => set_apropos.set(list),
+                    Err(e) => set_man.set(format!("apropos search failed: {e}")),
+                }
+            });
         } else {
             spawn_local(async move {
-                let args = to_value(&ManHelp {cmd: trimmed_value}).unwrap();
+                let args = to_value(&ManHelp {cmd: trimmed_value, refresh}).unwrap();
                 let js_value = invoke("get_man", args).await;
                 let result: Result<String, String> = from_value(js_value).map_err(|e| format!("man pages get failed: {e}"));
                 match result {
@@ -658,27 +1928,86 @@ pub fn ManSearch() -> impl IntoView {
         }
     };
 
+    // load full help for an entry of the keyword search
+    let open_entry = move |name: String| {
+        keyword_mode.set(false);
+        set_input_value.set(name);
+        search(false);
+    };
+
+    let on_submit = move |ev: SubmitEvent| {
+        ev.prevent_default();
+        search(false);
+    };
+
     view! {
         <div role="search" aria-label="Command help search">
             <h4 class="tc" id="man-search-title">
-                "Get console help with command: man or built-in --help"
+                {move || t("man.title")}
             </h4>
 
             <form on:submit=on_submit class="man_form" role="search">
                 <input
                     type="text"
-                    placeholder="e.g. `id` or `mpg123 -?`"
+                    placeholder="e.g. `id`, `3 printf` or `mpg123 -?`"
                     size=40
                     prop:value=move || input_value.get()
                     on:input=move |ev| set_input_value.set(event_target_value(&ev))
                     aria-labelledby="man-search-title"
                     aria-describedby="search-help"
+                    list="executables"
+                    autocomplete="off"
                 />
+                <datalist id="executables">
+                    {move || {
+                        suggestions()
+                            .into_iter()
+                            .map(|name| view! { <option value=name></option> })
+                            .collect_view()
+                    }}
+                </datalist>
                 <button type="submit" class="ok-bg" aria-label="Run search">
-                    "Search"
+                    {move || t("btn.search")}
+                </button>
+                <button
+                    type="button"
+                    class="warn-bg"
+                    on:click=move |_| search(true)
+                    aria-label="Search again, bypassing the cache"
+                >
+                    {move || t("btn.refresh")}
                 </button>
+                <label class="flag" title="List commands matching a keyword (apropos)">
+                    <input
+                        type="checkbox"
+                        prop:checked=move || keyword_mode.get()
+                        on:change=move |ev| keyword_mode.set(event_target_checked(&ev))
+                    />
+                    "keyword"
+                </label>
             </form>
 
+            <ul class="apropos" hidden=move || apropos.get().is_empty() aria-live="polite">
+                {move || {
+                    apropos
+                        .get()
+                        .into_iter()
+                        .map(|entry| {
+                            let page = format!("{} {}", entry.section, entry.name);
+                            view! {
+                                <li>
+                                    <button type="button" on:click=move |_| open_entry(page.clone())>
+                                        {format!("{} ({})", entry.name, entry.section)}
+                                    </button>
+                                    " - "
+                                    {entry.description}
+                                </li>
+                            }
+                        })
+                        .collect_view()
+                }}
+            </ul>
+
             <pre
                 class="man_result"
                 inner_html=move || man.get()
@@ -688,8 +2017,8 @@ pub fn ManSearch() -> impl IntoView {
             ></pre>
 
             <details id="search-help">
-                <summary>Help</summary>
-                <div class="text-bg" inner_html=SEARCH_HELP></div>
+                <summary>{move || t("label.help")}</summary>
+                <div class="text-bg" inner_html=move || t("help.search")></div>
             </details>
         </div>
     }
@@ -698,6 +2027,64 @@ pub fn ManSearch() -> impl IntoView {
 #[component]
 pub fn About() -> impl IntoView { 
     let (info, set_info) = signal(Vec::<String>::new());
+    let (self_test, set_self_test) = signal(Vec::<SelfTestItem>::new());
+    let testing = RwSignal::new(false);
+    let lang = use_context::<RwSignal<String>>().unwrap_or_else(|| RwSignal::new("en".to_string()));
+    let t = move |key: &'static str| tr(&lang.get(), key);
+
+    // [settings] edited here, the tray menu is rebuilt on each change; ports, logging and health checks need a restart
+    let (app_settings, set_app_settings) = signal(AppSettings::default());
+    spawn_local(async move {
+        let js = invoke_without_args("get_settings").await;
+        if let Ok(loaded) = from_value::<AppSettings>(js) {
+            set_app_settings.set(loaded);
+        }
+    });
+    let custom_theme = use_context::<RwSignal<CustomTheme>>().unwrap_or_else(|| RwSignal::new(CustomTheme::default()));
+    let save_settings = move |change: fn(&mut AppSettings, String), value: String| {
+        let mut settings = app_settings.get_untracked();
+        change(&mut settings, value);
+        set_app_settings.set(settings.clone());
+        custom_theme.set(settings.custom_theme.clone());
+        spawn_local(async move {
+            let args = to_value(&SettingsArgs { settings }).unwrap();
+            let _ = invoke("set_settings", args).await;
+        });
+    };
+
+    // saved in [settings], the tray menu is rebuilt in it. Kept in app_settings too,
+    // the next save_settings writes the whole [settings] back
+    let set_language = move |code: String| {
+        lang.set(code.clone());
+        set_app_settings.update(|settings| settings.language = code.clone());
+        spawn_local(async move {
+            let args = to_value(&LanguageArgs { lang: code }).unwrap();
+            let _ = invoke("set_language", args).await;
+        });
+    };
+
+    // zip with log, config and system info for bug reports
+    let collecting = RwSignal::new(false);
+    let diagnostics = RwSignal::new(String::new());
+    let collect_diagnostics = move || {
+        collecting.set(true);
+        spawn_local(async move {
+            let js = invoke_without_args("collect_diagnostics").await;
+            diagnostics.set(from_value::<String>(js).unwrap_or_else(|e| format!("Err( {e:?} )")));
+            collecting.set(false);
+        });
+    };
+
+    let run_self_test = move || {
+        testing.set(true);
+        spawn_local(async move {
+            let js_value = invoke_without_args("run_self_test").await;
+            if let Ok(items) = from_value(js_value) {
+                set_self_test.set(items);
+            }
+            testing.set(false);
+        });
+    };
     Effect::new(move |_| {
         spawn_local(async move {
             let js_value = invoke_without_args("get_app_info").await;
@@ -710,12 +2097,9 @@ pub fn About() -> impl IntoView {
     view! {
         <div class="help tc">
             <p class="text-bg">
-                <h4>"Your personal command center in the system tray"</h4>
+                <h4>{move || t("about.title")}</h4>
 
-                <p>
-                    "Gucli (from GUI + CLI) is a simple system tray application"<br />
-                    "that turns your frequent console commands into menu items for one-click launching."
-                </p>
+                <p>{move || t("about.intro")}</p>
                 <p class="err-text">"⚠ Warning: Not a CLI replacement!"</p>
 
                 {move || {
@@ -733,62 +2117,525 @@ pub fn About() -> impl IntoView {
                                 }
                                     .into_any()
                             } else if n.starts_with("Version") {
-                                view! { <Releases current=n /> }.into_any()
+                                view! {
+                                    <Releases
+                                        current=n
+                                        enabled=Signal::derive(move || app_settings.get().check_updates)
+                                    />
+                                }
+                                    .into_any()
                             } else {
                                 view! { <p>{n}</p> }.into_any()
                             }
                         })
                         .collect_view()
                 }}
-                <p>
-                    "For information on compatibility, dependencies, or to report issues, please visit the homepage."
-                </p>
+                <p>{move || t("about.homepage")}</p>
+                <label>
+                    {move || t("label.language")}
+                    ": "
+                    <select on:change=move |ev| set_language(event_target_value(&ev))>
+                        {LANGUAGES
+                            .into_iter()
+                            .map(|(code, name)| {
+                                view! {
+                                    <option value=code selected=move || lang.get() == code>
+                                        {name}
+                                    </option>
+                                }
+                            })
+                            .collect_view()}
+                    </select>
+                </label>
+                <label>
+                    {move || t("label.left_click")}
+                    ": "
+                    <select on:change=move |ev| {
+                        save_settings(|s, v| s.left_click = v, event_target_value(&ev))
+                    }>
+                        {LEFT_CLICK_ACTIONS
+                            .into_iter()
+                            .map(|action| {
+                                view! {
+                                    <option
+                                        value=action
+                                        selected=move || {
+                                            let cur = app_settings.get().left_click;
+                                            cur == action || (cur.is_empty() && action == "menu")
+                                        }
+                                    >
+                                        {action}
+                                    </option>
+                                }
+                            })
+                            .collect_view()}
+                    </select>
+                </label>
+                <label>
+                    <input
+                        type="checkbox"
+                        prop:checked=move || app_settings.get().start_hidden
+                        on:change=move |ev| {
+                            save_settings(|s, v| s.start_hidden = v == "true", event_target_checked(&ev).to_string())
+                        }
+                    />
+                    {move || t("label.start_hidden")}
+                </label>
+                <label>
+                    <input
+                        type="checkbox"
+                        prop:checked=move || app_settings.get().check_updates
+                        on:change=move |ev| {
+                            save_settings(|s, v| s.check_updates = v == "true", event_target_checked(&ev).to_string())
+                        }
+                    />
+                    {move || t("label.check_updates")}
+                </label>
+                <label>
+                    {move || t("label.on_quit")}
+                    ": "
+                    <input
+                        type="text"
+                        spellcheck="false"
+                        placeholder="nmcli connection down work-vpn"
+                        prop:value=move || app_settings.get().on_quit
+                        on:change=move |ev| save_settings(|s, v| s.on_quit = v, event_target_value(&ev))
+                    />
+                </label>
+                <fieldset class="custom-theme">
+                    <legend>{move || t("label.custom_theme")}</legend>
+                    <label>
+                        {move || t("label.theme_accent")}
+                        <input
+                            type="color"
+                            prop:value=move || color_or(&app_settings.get().custom_theme.accent, "#10b981")
+                            on:change=move |ev| save_settings(|s, v| s.custom_theme.accent = v, event_target_value(&ev))
+                        />
+                    </label>
+                    <label>
+                        {move || t("label.theme_background")}
+                        <input
+                            type="color"
+                            prop:value=move || color_or(&app_settings.get().custom_theme.background, "#d4d9e0")
+                            on:change=move |ev| {
+                                save_settings(|s, v| s.custom_theme.background = v, event_target_value(&ev))
+                            }
+                        />
+                    </label>
+                    <label>
+                        {move || t("label.theme_text")}
+                        <input
+                            type="color"
+                            prop:value=move || color_or(&app_settings.get().custom_theme.text, "#030712")
+                            on:change=move |ev| save_settings(|s, v| s.custom_theme.text = v, event_target_value(&ev))
+                        />
+                    </label>
+                    <label>
+                        {move || t("label.theme_font_size")}
+                        <input
+                            type="number"
+                            min="8"
+                            max="40"
+                            placeholder="14"
+                            prop:value=move || {
+                                app_settings.get().custom_theme.font_size.map(|s| s.to_string()).unwrap_or_default()
+                            }
+                            on:change=move |ev| {
+                                save_settings(
+                                    |s, v| s.custom_theme.font_size = v.trim().parse().ok().filter(|s| (8..=40).contains(s)),
+                                    event_target_value(&ev),
+                                )
+                            }
+                        />
+                    </label>
+                    <button on:click=move |_| save_settings(|s, _| s.custom_theme = CustomTheme::default(), String::new())>
+                        {move || t("btn.reset_theme")}
+                    </button>
+                </fieldset>
                 <p>"♿ Accessibility"</p>
             </p>
+            <div class="text-bg self-test">
+                <button
+                    class="warn-bg"
+                    on:click=move |_| run_self_test()
+                    prop:disabled=move || testing.get()
+                >
+                    {move || if testing.get() { t("btn.testing") } else { t("btn.self_test") }}
+                </button>
+                <button on:click=move |_| collect_diagnostics() prop:disabled=move || collecting.get()>
+                    {move || if collecting.get() { t("btn.collecting") } else { t("btn.diagnostics") }}
+                </button>
+                <p aria-live="polite">{move || diagnostics.get()}</p>
+                <ul aria-live="polite">
+                    {move || {
+                        self_test
+                            .get()
+                            .into_iter()
+                            .map(|item| {
+                                view! {
+                                    <li class=if item.passed { "ok-text" } else { "err-text" }>
+                                        {if item.passed { "✓ " } else { "✗ " }}
+                                        <b>{item.name}</b>
+                                        ": "
+                                        {item.detail}
+                                    </li>
+                                }
+                            })
+                            .collect_view()
+                    }}
+                </ul>
+            </div>
         </div>
     }
 }
 
 use leptos::*;
 
+/// per-command numbers from gucli.log, flakiest first
 #[component]
-pub fn Releases(current: String) -> impl IntoView {
-    let current_version = current.split_once(' ').map(|(_, r)| r).unwrap_or("").to_string();
-    let (latest_version, set_latest_version) = signal(None::<String>);
-    
+pub fn Stats() -> impl IntoView {
+    let (stats, set_stats) = signal(Vec::<RunStats>::new());
+    let lang = use_context::<RwSignal<String>>().unwrap_or_else(|| RwSignal::new("en".to_string()));
+    let t = move |key: &'static str| tr(&lang.get(), key);
+
+    let load = move || {
+        spawn_local(async move {
+            let js = invoke_without_args("get_stats").await;
+            set_stats.set(from_value::<Vec<RunStats>>(js).unwrap_or_default());
+        });
+    };
+    load();
+
+    view! {
+        <div class="text-bg stats">
+            <button on:click=move |_| load()>{move || t("btn.refresh")}</button>
+            <Show
+                when=move || !stats.get().is_empty()
+                fallback=move || view! { <p>{move || t("stats.empty")}</p> }
+            >
+                <table>
+                    <thead>
+                        <tr>
+                            <th>{move || t("head.command")}</th>
+                            <th>{move || t("stats.runs")}</th>
+                            <th>{move || t("stats.failure_rate")}</th>
+                            <th>{move || t("stats.last_failed")}</th>
+                            <th>{move || t("stats.avg")}</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {move || {
+                            stats
+                                .get()
+                                .into_iter()
+                                .map(|s| {
+                                    let rate = s.failure_rate();
+                                    view! {
+                                        <tr class:err-text={rate > 0.0}>
+                                            <td>{s.command.clone()}</td>
+                                            <td>{s.runs}</td>
+                                            <td>{format!("{:.0}% ({})", rate * 100.0, s.failures)}</td>
+                                            <td>{s.last_failed.clone()}</td>
+                                            <td>{s.avg_ms.map(|ms| format!("{} ms", ms)).unwrap_or_default()}</td>
+                                        </tr>
+                                    }
+                                })
+                                .collect_view()
+                        }}
+                    </tbody>
+                </table>
+            </Show>
+            <p class="ttime">{move || t("stats.note")}</p>
+        </div>
+    }
+}
+
+#[derive(Serialize)]
+struct KillArgs {
+    pid: i32,
+}
+
+/// process groups of started commands still alive, with a kill button each
+#[component]
+pub fn Processes() -> impl IntoView {
+    let processes = RwSignal::new(Vec::<RunningProcess>::new());
+    let error = RwSignal::new(String::new());
+    let lang = use_context::<RwSignal<String>>().unwrap_or_else(|| RwSignal::new("en".to_string()));
+    let t = move |key: &'static str| tr(&lang.get(), key);
+
+    let load = move || {
+        spawn_local(async move {
+            let js = invoke_without_args("get_processes").await;
+            processes.set(from_value::<Vec<RunningProcess>>(js).unwrap_or_default());
+        });
+    };
+    load();
+    // `cmd &` jobs end without an event, so poll while the tab is open
+    if let Ok(handle) = set_interval_with_handle(load, std::time::Duration::from_secs(2)) {
+        on_cleanup(move || handle.clear());
+    }
+
+    let kill = move |pid: i32| {
+        spawn_local(async move {
+            let args = to_value(&KillArgs { pid }).unwrap();
+            let js = invoke("kill_process", args).await;
+            match from_value::<Result<String, String>>(js).map_err(|e| format!("deserialize failed: {e}")) {
+                Ok(_) => error.set(String::new()),
+                Err(e) => error.set(e),
+            }
+            load();
+        });
+    };
+
+    view! {
+        <div class="text-bg processes">
+            <h4>{move || t("processes.title")}</h4>
+            <Show
+                when=move || !processes.with(Vec::is_empty)
+                fallback=move || view! { <p>{move || t("processes.empty")}</p> }
+            >
+                <table>
+                    <thead>
+                        <tr>
+                            <th>"PID"</th>
+                            <th>{move || t("head.command")}</th>
+                            <th>{move || t("processes.started")}</th>
+                            <th></th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        <For
+                            each=move || processes.get()
+                            key=|p| (p.pid, p.started.clone())
+                            children=move |p| {
+                                let pid = p.pid;
+                                view! {
+                                    <tr>
+                                        <td>{pid}</td>
+                                        <td>{p.command}</td>
+                                        <td>{p.started}</td>
+                                        <td>
+                                            <button on:click=move |_| kill(pid)>{move || t("btn.kill")}</button>
+                                        </td>
+                                    </tr>
+                                }
+                            }
+                        />
+                    </tbody>
+                </table>
+            </Show>
+            <Show when=move || !error.get().is_empty()>
+                <p class="err-text">{move || error.get()}</p>
+            </Show>
+        </div>
+    }
+}
+
+// lines kept in the log tab, older ones are dropped while following
+const LOG_VIEW_LINES: usize = 500;
+
+#[derive(Serialize)]
+struct ReadLogArgs {
+    lines: usize,
+}
+
+#[derive(Serialize)]
+struct FollowArgs {
+    on: bool,
+}
+
+/// gucli.log, oldest line first; "follow" appends lines as the backend writes them
+#[component]
+pub fn LogView() -> impl IntoView {
+    let lines = RwSignal::new(Vec::<String>::new());
+    let follow = RwSignal::new(false);
+    let lang = use_context::<RwSignal<String>>().unwrap_or_else(|| RwSignal::new("en".to_string()));
+    let t = move |key: &'static str| tr(&lang.get(), key);
+    let log_ref = NodeRef::<leptos::html::Pre>::new();
+
+    let scroll_down = move || {
+        request_animation_frame(move || {
+            if let Some(pre) = log_ref.get_untracked() {
+                pre.set_scroll_top(pre.scroll_height());
+            }
+        });
+    };
+    let load = move || {
+        spawn_local(async move {
+            let args = to_value(&ReadLogArgs { lines: LOG_VIEW_LINES }).unwrap();
+            lines.set(from_value::<Vec<String>>(invoke("read_log", args).await).unwrap_or_default());
+            scroll_down();
+        });
+    };
+    load();
+
     spawn_local(async move {
-        let client = reqwest::Client::new();
-        if let Ok(response) = client
-            .get("https://api.github.com/repos/jager1win/gucli/releases/latest")
-            .header("User-Agent", "App/Version")
-            .send()
-            .await
-            && let Ok(release) = response.json::<serde_json::Value>().await
-                && let Some(latest_ver) = release["tag_name"].as_str() {
-                    set_latest_version.set(Some(latest_ver.trim_start_matches('v').to_string()));
-                }
+        let on_lines = Closure::<dyn FnMut(JsValue)>::new(move |event: JsValue| {
+            let payload = js_sys::Reflect::get(&event, &JsValue::from_str("payload")).unwrap_or(JsValue::NULL);
+            if !follow.get_untracked() {
+                return;
+            }
+            if let Ok(fresh) = from_value::<Vec<String>>(payload) {
+                lines.update(|all| {
+                    all.extend(fresh);
+                    let excess = all.len().saturating_sub(LOG_VIEW_LINES);
+                    all.drain(..excess);
+                });
+                scroll_down();
+            }
+        });
+        let _ = listen("log-lines", &on_lines).await;
+        on_lines.forget();
+    });
+
+    let set_follow = move |on: bool| {
+        follow.set(on);
+        spawn_local(async move {
+            let args = to_value(&FollowArgs { on }).unwrap();
+            let _ = invoke("follow_log", args).await;
+        });
+        // lines written while not following
+        if on {
+            load();
+        }
+    };
+    // the backend watcher stops with the tab
+    on_cleanup(move || {
+        spawn_local(async move {
+            let args = to_value(&FollowArgs { on: false }).unwrap();
+            let _ = invoke("follow_log", args).await;
+        });
+    });
+
+    view! {
+        <div class="text-bg log-view">
+            <div class="topline">
+                <label>
+                    <input
+                        type="checkbox"
+                        prop:checked=move || follow.get()
+                        on:change=move |ev| set_follow(event_target_checked(&ev))
+                    />
+                    {move || t("label.follow")}
+                </label>
+                <button on:click=move |_| load()>{move || t("btn.refresh")}</button>
+            </div>
+            <Show
+                when=move || !lines.with(Vec::is_empty)
+                fallback=move || view! { <p>{move || t("log.empty")}</p> }
+            >
+                <pre node_ref=log_ref aria-live=move || if follow.get() { "polite" } else { "off" }>
+                    {move || lines.get().join("\n")}
+                </pre>
+            </Show>
+        </div>
+    }
+}
+
+/// version line with the update check result, the backend asks GitHub only when `check_updates` is on
+#[component]
+pub fn Releases(current: String, enabled: Signal<bool>) -> impl IntoView {
+    // None: checking
+    let (release, set_release) = signal(None::<Option<ReleaseInfo>>);
+
+    Effect::new(move |_| {
+        if !enabled.get() {
+            set_release.set(Some(None));
+            return;
+        }
+        set_release.set(None);
+        spawn_local(async move {
+            let js = invoke_without_args("check_update").await;
+            set_release.set(Some(from_value::<Option<ReleaseInfo>>(js).ok().flatten()));
+        });
     });
 
-    view! { 
+    view! {
         <p>
             {current}
             <span>
-                <Transition fallback=move || view! { <span>"Checking..."</span> }>
-                    {move || match latest_version.get() {
-                        Some(latest) if latest == current_version => {
-                            view! { <span>" - latest version"</span> }.into_any()
+                {move || match release.get() {
+                    None => view! { <span>" - checking..."</span> }.into_any(),
+                    Some(None) if !enabled.get() => view! { <span>" - update check off"</span> }.into_any(),
+                    Some(None) => view! { <span>" - failed to check new version"</span> }.into_any(),
+                    Some(Some(latest)) if !latest.newer => view! { <span>" - latest version"</span> }.into_any(),
+                    Some(Some(latest)) => {
+                        view! {
+                            <span>
+                                " - new version "
+                                <a href=latest.url target="_blank">
+                                    {latest.version}
+                                </a>
+                                " available!"
+                            </span>
                         }
-                        Some(latest) => {
-                            view! { <span>" - new version " {latest} " available!"</span> }.into_any()
-                        }
-                        None => view! { <span>" - failed to check new version"</span> }.into_any(),
-                    }}
-                </Transition>
+                            .into_any()
+                    }
+                }}
             </span>
-        </p> 
+        </p>
     }
 }
 
+/// "ok", "err" or "warn" (note: skipped or left running) for a run, colors the status like the rows
+fn test_outcome(result: &TestResult) -> &'static str {
+    if !result.note.is_empty() {
+        "warn"
+    } else if result.success && result.exit_code.is_none_or(|code| code == 0) {
+        "ok"
+    } else {
+        "err"
+    }
+}
+
+/// status text of a run: command, exit code and duration, then the output of the outcome first
+fn test_text(command: &str, result: &TestResult) -> String {
+    if !result.note.is_empty() {
+        return format!("`{}` {}", command, result.note);
+    }
+    let exit = result.exit_code.map_or("no exit code".to_string(), |code| format!("exit {}", code));
+    let head = format!("`{}`: {}, {} ms", command, exit, result.duration_ms);
+    let (first, other, other_name) = if result.success {
+        (&result.stdout, &result.stderr, "stderr")
+    } else {
+        (&result.stderr, &result.stdout, "stdout")
+    };
+    let mut text = format!("{}:\n{}", head, first);
+    if !other.trim().is_empty() {
+        text.push_str(&format!("\n{}:\n{}", other_name, other));
+    }
+    text
+}
+
+/// text shown in the status, which is rendered as html
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// commands ordered like ids, others (new rows) last
+fn in_order(mut buf: Vec<Command>, ids: &[String]) -> Vec<Command> {
+    buf.sort_by_key(|c| ids.iter().position(|id| id == &c.id).unwrap_or(usize::MAX));
+    buf
+}
+
+/// current time in `format`, in the webview locale when empty or invalid
+fn format_now(format: &str) -> String {
+    use std::fmt::Write;
+    let mut out = String::new();
+    if !format.trim().is_empty() && write!(out, "{}", Local::now().format(format)).is_ok() {
+        return out;
+    }
+    js_sys::Date::new_0()
+        .to_locale_string("default", &JsValue::UNDEFINED)
+        .into()
+}
+
+/// a color input needs #rrggbb, `fallback` for unset or other CSS colors
+fn color_or(value: &str, fallback: &str) -> String {
+    let hex = value.len() == 7 && value.starts_with('#') && value[1..].chars().all(|c| c.is_ascii_hexdigit());
+    if hex { value.to_string() } else { fallback.to_string() }
+}
+
 fn generate_id() -> String {
     Local::now().timestamp_nanos_opt()
         .unwrap_or(0)
@@ -797,19 +2644,21 @@ fn generate_id() -> String {
 
 #[component]
 pub fn ShellSwitch() -> impl IntoView {
-    let shells = vec!["sh", "bash", "zsh", "fish"];
+    let (shells, set_shells) = signal(Vec::<String>::new());
+    spawn_local(async move {
+        let js = invoke_without_args("get_builtin_shells").await;
+        set_shells.set(from_value::<Vec<String>>(js).unwrap_or_default());
+    });
     let (current_index, set_current_index) = signal(0);
 
-    let next_shell = {
-        let shells = shells.clone();
-        move |_| {
-            set_current_index.update(|idx| *idx = (*idx + 1) % shells.len());
-        }
+    let next_shell = move |_| {
+        let count = shells.with(Vec::len).max(1);
+        set_current_index.update(|idx| *idx = (*idx + 1) % count);
     };
 
     view! {
         <div class="shell-switch">
-            <span class="current-shell">{shells[current_index.get()]}</span>
+            <span class="current-shell">{move || shells.with(|s| s.get(current_index.get()).cloned().unwrap_or_default())}</span>
             <button class="shell-button" on:click=next_shell>
                 "▶"
             </button>
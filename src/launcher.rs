@@ -0,0 +1,85 @@
+use crate::app::{invoke, invoke_without_args};
+use gucli_shared::i18n::tr;
+use gucli_shared::{UserCommand as Command, tray_order};
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use serde::Serialize;
+use serde_wasm_bindgen::{from_value, to_value};
+use web_sys::window;
+
+#[derive(Serialize)]
+struct LaunchArgs {
+    cmd: Command,
+}
+
+/// window opened instead of the tray on desktops without one: the tray's commands as buttons
+#[component]
+pub fn Launcher() -> impl IntoView {
+    let commands = RwSignal::new(Vec::<Command>::new());
+    let status = RwSignal::new(String::new());
+    let lang = RwSignal::new(String::from("en"));
+    let t = move |key: &'static str| tr(&lang.get(), key);
+
+    // the theme picked in the settings window, "auto" and "custom" follow the system
+    if let Some(window) = window() {
+        let stored = window.local_storage().ok().flatten().and_then(|s| s.get("theme").ok().flatten());
+        let theme = match stored.as_deref() {
+            Some(theme @ ("light" | "dark" | "a11y")) => theme.to_string(),
+            _ => match window.match_media("(prefers-color-scheme: dark)").ok().flatten() {
+                Some(mql) if mql.matches() => "dark".to_string(),
+                _ => "light".to_string(),
+            },
+        };
+        if let Some(html) = window.document().and_then(|d| d.document_element()) {
+            let _ = html.set_attribute("data-theme", &theme);
+        }
+    }
+
+    spawn_local(async move {
+        if let Ok(code) = from_value::<String>(invoke_without_args("get_language").await) {
+            lang.set(code);
+        }
+        let host = from_value::<String>(invoke_without_args("get_hostname").await).unwrap_or_default();
+        match from_value::<Vec<Command>>(invoke_without_args("get_commands").await) {
+            // same entries and order as the tray menu
+            Ok(all) => commands.set(tray_order(&all).into_iter().filter(|c| c.available_on(&host)).cloned().collect()),
+            Err(e) => status.set(format!("Err( {} )", e)),
+        }
+    });
+
+    let launch = move |cmd: Command| {
+        status.set(format!("{} `{}`", t("launcher.started"), cmd.display_name()));
+        spawn_local(async move {
+            let args = to_value(&LaunchArgs { cmd }).unwrap();
+            let _ = invoke("launch_command", args).await;
+        });
+    };
+
+    view! {
+        <main class="container launcher" role="main">
+            <For
+                each=move || commands.get()
+                key=|cmd| cmd.id.clone()
+                children=move |cmd| {
+                    let text = format!("{}  {}", cmd.icon, cmd.display_name());
+                    view! {
+                        <button title=cmd.command.clone() on:click=move |_| launch(cmd.clone())>
+                            {text}
+                        </button>
+                    }
+                }
+            />
+            <Show when=move || commands.with(Vec::is_empty)>
+                <div class="tc">{move || t("launcher.empty")}</div>
+            </Show>
+            <button class="ok-bg" on:click=move |_| {
+                spawn_local(async move {
+                    let _ = invoke_without_args("show_settings").await;
+                });
+            }>
+                {move || t("tray.settings")}
+            </button>
+            <div role="status" aria-live="polite">{move || status.get()}</div>
+        </main>
+    }
+}
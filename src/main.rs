@@ -1,14 +1,24 @@
 mod app;
+mod launcher;
+mod output;
+mod palette;
 
 use app::*;
+use launcher::Launcher;
 use leptos::prelude::*;
 
 fn main() {
     console_error_panic_hook::set_once();
     _ = console_log::init_with_level(log::Level::Debug);
-    mount_to_body(|| {
-        view! {
-            <App/>
+    // the launcher window (no tray on this desktop) loads the same page with ?view=launcher
+    let launcher = web_sys::window()
+        .and_then(|w| w.location().search().ok())
+        .is_some_and(|search| search.contains("view=launcher"));
+    mount_to_body(move || {
+        if launcher {
+            view! { <Launcher/> }.into_any()
+        } else {
+            view! { <App/> }.into_any()
         }
     })
 }
@@ -0,0 +1,82 @@
+use leptos::prelude::*;
+use serde_json::Value;
+
+/// JSON in the stdout of a run, None for plain text
+pub fn parse_json(stdout: &str) -> Option<Value> {
+    let output = stdout.trim();
+    // bare numbers and strings are valid JSON too, but nothing to structure
+    if !(output.starts_with('{') || output.starts_with('[')) {
+        return None;
+    }
+    serde_json::from_str(output).ok()
+}
+
+/// arrays of objects as a table, objects as key/value rows, nested values collapsible
+#[component]
+pub fn JsonView(value: Value) -> impl IntoView {
+    render(&value)
+}
+
+fn render(value: &Value) -> AnyView {
+    match value {
+        Value::Array(items) if !items.is_empty() && items.iter().all(Value::is_object) => {
+            // union of keys, in order of first appearance
+            let mut columns: Vec<String> = Vec::new();
+            for item in items {
+                for key in item.as_object().into_iter().flat_map(|o| o.keys()) {
+                    if !columns.contains(key) {
+                        columns.push(key.clone());
+                    }
+                }
+            }
+            let rows = items
+                .iter()
+                .map(|item| {
+                    let cells = columns
+                        .iter()
+                        .map(|key| view! { <td>{item.get(key).map(render).unwrap_or_else(|| ().into_any())}</td> })
+                        .collect_view();
+                    view! { <tr>{cells}</tr> }
+                })
+                .collect_view();
+            let head = columns.into_iter().map(|key| view! { <th>{key}</th> }).collect_view();
+            view! {
+                <table>
+                    <thead>
+                        <tr>{head}</tr>
+                    </thead>
+                    <tbody>{rows}</tbody>
+                </table>
+            }
+            .into_any()
+        }
+        Value::Array(items) => {
+            let rows = items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| view! { <tr><th>{i}</th><td>{render(item)}</td></tr> })
+                .collect_view();
+            nested(format!("[{}]", items.len()), view! { <table><tbody>{rows}</tbody></table> }.into_any())
+        }
+        Value::Object(map) => {
+            let rows = map
+                .iter()
+                .map(|(key, item)| view! { <tr><th>{key.clone()}</th><td>{render(item)}</td></tr> })
+                .collect_view();
+            nested(format!("{{{}}}", map.len()), view! { <table><tbody>{rows}</tbody></table> }.into_any())
+        }
+        Value::String(s) => s.clone().into_any(),
+        Value::Null => "null".into_any(),
+        other => other.to_string().into_any(),
+    }
+}
+
+fn nested(summary: String, content: AnyView) -> AnyView {
+    view! {
+        <details open>
+            <summary>{summary}</summary>
+            {content}
+        </details>
+    }
+    .into_any()
+}
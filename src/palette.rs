@@ -0,0 +1,205 @@
+use leptos::ev::KeyboardEvent;
+use leptos::prelude::*;
+use gucli_shared::i18n::tr;
+
+// rows shown at once, the query narrows the rest
+const MAX_ROWS: usize = 12;
+
+/// what a palette entry does, the App carries it out
+#[derive(Clone, Debug, PartialEq)]
+pub enum PaletteAction {
+    Tab(usize),
+    /// command id
+    Run(String),
+    ToggleTheme,
+    /// "log" or "commands", see the open_file command
+    Open(&'static str),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PaletteEntry {
+    pub label: String,
+    /// i18n key of the kind shown on the right
+    pub kind: &'static str,
+    pub action: PaletteAction,
+}
+
+impl PaletteEntry {
+    pub fn new(label: impl Into<String>, kind: &'static str, action: PaletteAction) -> Self {
+        Self { label: label.into(), kind, action }
+    }
+}
+
+/// query letters in order somewhere in the text, None when they are not.
+/// Higher is better: runs of letters and hits at word starts count more
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut pos = 0;
+    let mut last: Option<usize> = None;
+    for q in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = pos + text[pos..].iter().position(|c| *c == q)?;
+        score += 1;
+        if last.is_some_and(|l| l + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        last = Some(found);
+        pos = found + 1;
+    }
+    // shorter labels first among equal hits
+    Some(score * 100 - text.len() as i32)
+}
+
+/// Ctrl+K overlay: type to filter, arrows to move, Enter to pick, Escape to close
+#[component]
+pub fn CommandPalette(
+    open: RwSignal<bool>,
+    entries: Signal<Vec<PaletteEntry>>,
+    on_pick: Callback<PaletteAction>,
+) -> impl IntoView {
+    let lang = use_context::<RwSignal<String>>().unwrap_or_else(|| RwSignal::new("en".to_string()));
+    let t = move |key: &'static str| tr(&lang.get(), key);
+    let query = RwSignal::new(String::new());
+    let selected = RwSignal::new(0usize);
+    let input_ref = NodeRef::<leptos::html::Input>::new();
+
+    let matches = Memo::new(move |_| {
+        let query = query.get();
+        // nothing typed yet: the given order, the length tie-break would reshuffle it
+        if query.trim().is_empty() {
+            return entries.get().into_iter().take(MAX_ROWS).collect::<Vec<_>>();
+        }
+        let mut scored: Vec<(i32, PaletteEntry)> = entries
+            .get()
+            .into_iter()
+            .filter_map(|entry| fuzzy_score(&query, &entry.label).map(|score| (score, entry)))
+            .collect();
+        scored.sort_by_key(|(score, _)| -score);
+        scored.into_iter().take(MAX_ROWS).map(|(_, entry)| entry).collect::<Vec<_>>()
+    });
+
+    let close = move || {
+        open.set(false);
+        query.set(String::new());
+        selected.set(0);
+    };
+    let pick = move |action: PaletteAction| {
+        close();
+        on_pick.run(action);
+    };
+
+    // the input is only mounted now, focus it on the next frame
+    Effect::new(move |_| {
+        if open.get() {
+            request_animation_frame(move || {
+                if let Some(input) = input_ref.get_untracked() {
+                    let _ = input.focus();
+                }
+            });
+        }
+    });
+
+    let on_keydown = move |ev: KeyboardEvent| {
+        let count = matches.with_untracked(Vec::len);
+        match &ev.key()[..] {
+            "ArrowDown" if count > 0 => {
+                ev.prevent_default();
+                selected.update(|n| *n = (*n + 1) % count);
+            }
+            "ArrowUp" if count > 0 => {
+                ev.prevent_default();
+                selected.update(|n| *n = (*n + count - 1) % count);
+            }
+            "Enter" => {
+                if let Some(entry) = matches.with_untracked(|m| m.get(selected.get_untracked()).cloned()) {
+                    pick(entry.action);
+                }
+            }
+            "Escape" => close(),
+            _ => {}
+        }
+    };
+
+    view! {
+        <Show when=move || open.get()>
+            <div class="palette-backdrop" on:click=move |_| close()>
+                <div
+                    class="palette"
+                    role="dialog"
+                    aria-label=move || t("palette.title")
+                    on:click=|ev| ev.stop_propagation()
+                >
+                    <input
+                        type="text"
+                        node_ref=input_ref
+                        placeholder=move || t("palette.placeholder")
+                        aria-keyshortcuts="Control+K"
+                        prop:value=move || query.get()
+                        on:input=move |ev| {
+                            query.set(event_target_value(&ev));
+                            selected.set(0);
+                        }
+                        on:keydown=on_keydown
+                    />
+                    <ul role="listbox">
+                        <For
+                            each=move || matches.get().into_iter().enumerate()
+                            key=|(n, entry)| (*n, entry.label.clone(), entry.kind)
+                            children=move |(n, entry)| {
+                                let action = entry.action.clone();
+                                view! {
+                                    <li
+                                        role="option"
+                                        class:selected=move || selected.get() == n
+                                        aria-selected=move || (selected.get() == n).to_string()
+                                        on:mouseenter=move |_| selected.set(n)
+                                        on:click=move |_| pick(action.clone())
+                                    >
+                                        <span>{entry.label}</span>
+                                        <span class="palette-kind">{move || t(entry.kind)}</span>
+                                    </li>
+                                }
+                            }
+                        />
+                    </ul>
+                    <Show when=move || matches.with(Vec::is_empty)>
+                        <div class="palette-empty">{move || t("palette.empty")}</div>
+                    </Show>
+                </div>
+            </div>
+        </Show>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fuzzy_score;
+
+    #[test]
+    fn needs_letters_in_order() {
+        assert!(fuzzy_score("dkr", "docker ps").is_some());
+        assert_eq!(fuzzy_score("rkd", "docker ps"), None);
+        assert_eq!(fuzzy_score("x", "docker ps"), None);
+    }
+
+    #[test]
+    fn ignores_case_and_spaces() {
+        assert_eq!(fuzzy_score("D P", "docker ps"), fuzzy_score("dp", "docker ps"));
+    }
+
+    #[test]
+    fn prefers_runs_and_word_starts() {
+        let run = fuzzy_score("dock", "docker ps").unwrap();
+        let scattered = fuzzy_score("dock", "d o c k").unwrap();
+        assert!(run > scattered);
+        assert!(fuzzy_score("ps", "docker ps").unwrap() > fuzzy_score("ps", "dropsql").unwrap());
+    }
+
+    #[test]
+    fn shorter_text_wins_a_tie() {
+        assert!(fuzzy_score("up", "uptime").unwrap() > fuzzy_score("up", "uptime -p").unwrap());
+    }
+}